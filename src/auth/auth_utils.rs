@@ -1,7 +1,8 @@
-use dialoguer::{Confirm, theme::ColorfulTheme};
 use log::debug;
 
-use crate::config::global::{BGitGlobalConfig, PreferredAuth};
+use crate::auth::ssh::resolve_ssh_alias_url;
+use crate::config::global::{BGitGlobalConfig, GlobalAuth, PreferredAuth};
+use crate::prompt;
 
 /// Prompt the user to persist the preferred authentication method and save to global config.
 /// No-op if the preferred method already matches.
@@ -15,11 +16,7 @@ pub fn prompt_persist_preferred_auth(cfg: &BGitGlobalConfig, method: PreferredAu
         PreferredAuth::RepositoryURLBased => "Repository URL based",
     };
     let question = format!("Set preferred auth to {} for future operations?", label);
-    let confirm = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt(question)
-        .default(true)
-        .interact()
-        .unwrap_or(false);
+    let confirm = prompt::confirm("auth.save_preferred", &question, true).unwrap_or(false);
     if !confirm {
         debug!("User declined setting preferred auth to {:?}", method);
         return;
@@ -34,70 +31,175 @@ pub fn prompt_persist_preferred_auth(cfg: &BGitGlobalConfig, method: PreferredAu
     }
 }
 
-/// Transform a repository URL to match the preferred auth method for known hosts.
+/// Built-in hosting services whose SSH and HTTPS URLs map onto each other 1:1
+const KNOWN_HOSTS: [&str; 3] = ["github.com", "gitlab.com", "bitbucket.org"];
+
+/// How to rewrite URLs for one host, from `[[auth.hosts]]` or the built-in list
+struct HostRule {
+    ssh_user: String,
+    ssh_port: Option<u16>,
+    /// HTTPS port from a `host:port` entry, `None` for the default one
+    https_port: Option<u16>,
+    /// Normalized to either "" or "/prefix" without a trailing slash
+    https_base_path: String,
+    preferred: Option<PreferredAuth>,
+}
+
+impl HostRule {
+    fn find(host: &str, auth: &GlobalAuth) -> Option<Self> {
+        if let Some(configured) = auth
+            .hosts
+            .iter()
+            .find(|h| split_host_port(&h.host).0.eq_ignore_ascii_case(host))
+        {
+            let base = configured
+                .https_base_path
+                .as_deref()
+                .unwrap_or("")
+                .trim_matches('/');
+            return Some(HostRule {
+                ssh_user: configured
+                    .ssh_user
+                    .clone()
+                    .unwrap_or_else(|| "git".to_string()),
+                ssh_port: configured.ssh_port,
+                https_port: split_host_port(&configured.host).1,
+                https_base_path: if base.is_empty() {
+                    String::new()
+                } else {
+                    format!("/{base}")
+                },
+                preferred: configured.preferred,
+            });
+        }
+
+        KNOWN_HOSTS
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(host))
+            .then(|| HostRule {
+                ssh_user: "git".to_string(),
+                ssh_port: None,
+                https_port: None,
+                https_base_path: String::new(),
+                preferred: None,
+            })
+    }
+}
+
+/// Transform a repository URL to match the preferred auth method for known and configured hosts.
+/// A host's own `preferred` setting overrides `auth.preferred`.
 /// Returns Some(new_url) when a transformation was applied, or None if unknown/unchanged.
-pub fn transform_url_for_preference(url: &str, preferred: PreferredAuth) -> Option<String> {
-    // Recognize current scheme/type
-    let is_https = url.starts_with("https://");
-    let is_ssh_scp = url.starts_with("git@") || url.starts_with("ssh://");
+pub fn transform_url_for_preference(url: &str, auth: &GlobalAuth) -> Option<String> {
+    if let Some((host, port, path)) = parse_ssh_like(url) {
+        let rule = HostRule::find(&host, auth)?;
+        return match rule.preferred.unwrap_or(auth.preferred) {
+            PreferredAuth::Https => to_https(&host, port, &path, &rule),
+            _ => None,
+        };
+    }
 
-    match preferred {
+    let is_http = url.starts_with("http://");
+    let (authority, path) = parse_http(url)?;
+    let (user_info, host_port) = match authority.rsplit_once('@') {
+        Some((user_info, host_port)) => (format!("{user_info}@"), host_port),
+        None => (String::new(), authority.as_str()),
+    };
+    let (host, port) = split_host_port(host_port);
+    let rule = HostRule::find(host, auth)?;
+    // A host configured with a port only covers HTTPS on that port
+    if !is_http && rule.https_port.is_some_and(|p| port.unwrap_or(443) != p) {
+        return None;
+    }
+    match rule.preferred.unwrap_or(auth.preferred) {
         PreferredAuth::RepositoryURLBased => None, // keep as-is
-        PreferredAuth::Https => {
-            if is_https {
-                None
-            } else {
-                to_https(url) // convert http/ssh->https for known hosts
-            }
-        }
-        PreferredAuth::Ssh => {
-            if is_ssh_scp {
-                None
-            } else {
-                to_ssh(url) // convert https->ssh for known hosts
-            }
-        }
+        // Upgrade http->https for known hosts. A non-default HTTP port says nothing about
+        // the HTTPS one, so that needs a configured port.
+        PreferredAuth::Https if is_http => match (port, rule.https_port) {
+            (Some(p), None) if p != 80 => None,
+            _ => Some(format!(
+                "https://{user_info}{}/{path}",
+                with_port(host, rule.https_port)
+            )),
+        },
+        PreferredAuth::Https => None,
+        // The HTTPS port does not carry over, SSH listens on 22 or the configured `ssh_port`
+        PreferredAuth::Ssh => Some(to_ssh(host, &path, &rule)),
     }
 }
 
-fn to_https(url: &str) -> Option<String> {
-    // SSH forms to convert:
-    // - git@host:owner/repo(.git)
-    // - ssh://git@host/owner/repo(.git)
-    if let Some((host, path)) = parse_ssh_like(url) {
-        if !is_known_host(&host) {
-            return None;
-        }
-        let path_no_slash = path.strip_prefix('/').unwrap_or(&path);
-        Some(format!("https://{}/{}", host, path_no_slash))
-    } else if url.starts_with("http://") {
-        // Upgrade http->https for known hosts
-        if let Some(host) = host_from_http(url)
-            && is_known_host(host)
-        {
-            return Some(url.replacen("http://", "https://", 1));
-        }
-        None
-    } else {
-        None
+/// URL to actually connect to for a remote: ssh_config aliases are resolved first, then the
+/// preferred auth transformation is applied. Returns `None` if the URL is used unchanged.
+pub fn resolve_remote_url(url: &str, auth: &GlobalAuth) -> Option<String> {
+    let resolved = resolve_ssh_alias_url(url);
+    let base = resolved.as_deref().unwrap_or(url);
+    transform_url_for_preference(base, auth).or(resolved)
+}
+
+/// Host (with port, if any) and path of an http(s) URL, without user info.
+/// `https://user@github.com/owner/repo.git` yields `("github.com", "owner/repo.git")`.
+pub fn https_host_and_path(url: &str) -> Option<(String, String)> {
+    let (authority, path) = parse_http(url)?;
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority.as_str(), |(_, host)| host);
+    Some((host.to_ascii_lowercase(), path))
+}
+
+fn to_https(host: &str, port: Option<u16>, path: &str, rule: &HostRule) -> Option<String> {
+    // A non-default SSH port that is not the configured one points at a different service
+    if port.is_some_and(|p| p != 22 && Some(p) != rule.ssh_port) {
+        return None;
     }
+    let path_no_slash = path.trim_start_matches('/');
+    Some(format!(
+        "https://{}{}/{}",
+        with_port(host, rule.https_port),
+        rule.https_base_path,
+        path_no_slash
+    ))
 }
 
-fn to_ssh(url: &str) -> Option<String> {
-    // HTTP form to SSH scp-like: https://host/owner/repo(.git) -> git@host:owner/repo(.git)
-    if let Some((host, path)) = parse_http(url) {
-        if !is_known_host(&host) {
-            return None;
-        }
-        let path_no_slash = path.strip_prefix('/').unwrap_or(&path);
-        Some(format!("git@{}:{}", host, path_no_slash))
+fn to_ssh(host: &str, path: &str, rule: &HostRule) -> String {
+    // HTTP form to SSH: https://host/base/owner/repo(.git) -> git@host:owner/repo(.git)
+    let path_no_slash = path.trim_start_matches('/');
+    let base = rule.https_base_path.trim_start_matches('/');
+    let repo_path = if base.is_empty() {
+        path_no_slash
     } else {
-        None
+        path_no_slash
+            .strip_prefix(base)
+            .map_or(path_no_slash, |rest| rest.trim_start_matches('/'))
+    };
+
+    match rule.ssh_port {
+        Some(port) if port != 22 => {
+            format!("ssh://{}@{}:{}/{}", rule.ssh_user, host, port, repo_path)
+        }
+        _ => format!("{}@{}:{}", rule.ssh_user, host, repo_path),
+    }
+}
+
+/// `host:port`, or just `host` when there is no port
+fn with_port(host: &str, port: Option<u16>) -> String {
+    match port {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    }
+}
+
+/// Split `host[:port]`, as found in URL authorities and `[[auth.hosts]]` entries
+fn split_host_port(host_port: &str) -> (&str, Option<u16>) {
+    match host_port.split_once(':') {
+        Some((host, port)) => (host, port.parse().ok()),
+        None => (host_port, None),
     }
 }
 
 fn parse_http(url: &str) -> Option<(String, String)> {
     // naive parse: scheme://host/path
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return None;
+    }
     let scheme_split = url.splitn(2, "://").collect::<Vec<_>>();
     if scheme_split.len() != 2 {
         return None;
@@ -109,54 +211,162 @@ fn parse_http(url: &str) -> Option<(String, String)> {
     Some((host, path))
 }
 
-fn host_from_http(url: &str) -> Option<&str> {
-    let scheme_split = url.splitn(2, "://").collect::<Vec<_>>();
-    if scheme_split.len() != 2 {
-        return None;
+/// Parse `user@host:path` and `ssh://user@host[:port]/path` into host, port and path
+fn parse_ssh_like(url: &str) -> Option<(String, Option<u16>, String)> {
+    // ssh://git@host[:port]/owner/repo(.git)
+    if let Some(without_scheme) = url.strip_prefix("ssh://") {
+        let (authority, path) = without_scheme
+            .split_once('/')
+            .unwrap_or((without_scheme, ""));
+        let host_port = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        let (host, port) = split_host_port(host_port);
+        return Some((host.to_string(), port, path.to_string()));
     }
-    let rest = scheme_split[1];
-    let mut parts = rest.splitn(2, '/');
-    parts.next()
-}
-
-fn parse_ssh_like(url: &str) -> Option<(String, String)> {
     // git@host:owner/repo(.git)
-    if url.starts_with("git@") {
-        let after_at = url.split_once('@')?.1;
-        let mut host_path = after_at.splitn(2, ':');
-        let host = host_path.next()?.to_string();
-        let path = host_path.next()?.to_string();
-        return Some((host, path));
-    }
-    // ssh://git@host/owner/repo(.git)
-    if let Some(without_scheme) = url.strip_prefix("ssh://") {
-        let after_at = without_scheme.split_once('@')?.1;
-        let mut host_path = after_at.splitn(2, '/');
-        let host = host_path.next()?.to_string();
-        let path = host_path.next().unwrap_or("").to_string();
-        return Some((host, path));
+    if url.contains("://") {
+        return None;
     }
-    None
-}
-
-fn is_known_host(host: &str) -> bool {
-    matches!(host, "github.com" | "gitlab.com" | "bitbucket.org")
+    let (user_host, path) = url.split_once(':')?;
+    let (_, host) = user_host.split_once('@')?;
+    Some((host.to_string(), None, path.to_string()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::global::GitHost;
+
+    fn auth(preferred: PreferredAuth) -> GlobalAuth {
+        GlobalAuth {
+            preferred,
+            ..GlobalAuth::default()
+        }
+    }
+
+    fn self_hosted_auth(preferred: PreferredAuth) -> GlobalAuth {
+        GlobalAuth {
+            preferred,
+            hosts: vec![
+                GitHost {
+                    host: "git.example.com".to_string(),
+                    ssh_user: Some("gitlab".to_string()),
+                    ssh_port: Some(2222),
+                    https_base_path: Some("/gitlab/".to_string()),
+                    preferred: None,
+                },
+                GitHost {
+                    host: "forgejo.example.org".to_string(),
+                    preferred: Some(PreferredAuth::Ssh),
+                    ..GitHost::default()
+                },
+            ],
+            ..GlobalAuth::default()
+        }
+    }
 
     #[test]
-    fn ssh_scp_to_https_known_hosts() {
+    fn configured_hosts_use_ssh_user_port_and_base_path() {
+        let to_ssh = self_hosted_auth(PreferredAuth::Ssh);
         assert_eq!(
-            transform_url_for_preference("git@github.com:owner/repo.git", PreferredAuth::Https)
+            transform_url_for_preference("https://git.example.com/gitlab/group/repo.git", &to_ssh)
                 .as_deref(),
-            Some("https://github.com/owner/repo.git")
+            Some("ssh://gitlab@git.example.com:2222/group/repo.git")
+        );
+
+        let to_https = self_hosted_auth(PreferredAuth::Https);
+        assert_eq!(
+            transform_url_for_preference(
+                "ssh://gitlab@git.example.com:2222/group/repo.git",
+                &to_https
+            )
+            .as_deref(),
+            Some("https://git.example.com/gitlab/group/repo.git")
+        );
+        // A port other than the configured one is some other service
+        assert!(
+            transform_url_for_preference(
+                "ssh://git@git.example.com:7999/group/repo.git",
+                &to_https
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn https_ports_are_matched_and_never_reused_for_ssh() {
+        let mut cfg = self_hosted_auth(PreferredAuth::Ssh);
+        // Any HTTPS port matches a host configured without one
+        assert_eq!(
+            transform_url_for_preference("https://forgejo.example.org:8443/me/repo.git", &cfg)
+                .as_deref(),
+            Some("git@forgejo.example.org:me/repo.git")
+        );
+
+        cfg.hosts.push(GitHost {
+            host: "gitlab.example.com:8443".to_string(),
+            ..GitHost::default()
+        });
+        assert_eq!(
+            transform_url_for_preference(
+                "https://alice@gitlab.example.com:8443/group/repo.git",
+                &cfg
+            )
+            .as_deref(),
+            Some("git@gitlab.example.com:group/repo.git")
+        );
+        // Another port on a host configured with one is some other service
+        assert!(
+            transform_url_for_preference("https://gitlab.example.com/group/repo.git", &cfg)
+                .is_none()
+        );
+
+        cfg.preferred = PreferredAuth::Https;
+        assert_eq!(
+            transform_url_for_preference("git@gitlab.example.com:group/repo.git", &cfg).as_deref(),
+            Some("https://gitlab.example.com:8443/group/repo.git")
+        );
+        assert_eq!(
+            transform_url_for_preference("http://gitlab.example.com/group/repo.git", &cfg)
+                .as_deref(),
+            Some("https://gitlab.example.com:8443/group/repo.git")
+        );
+        // The HTTPS port behind a plain HTTP port is unknown without a configured one
+        cfg.hosts[1].preferred = None;
+        assert!(
+            transform_url_for_preference("http://forgejo.example.org:3000/me/repo.git", &cfg)
+                .is_none()
         );
+    }
+
+    #[test]
+    fn per_host_preference_overrides_global() {
+        let cfg = self_hosted_auth(PreferredAuth::RepositoryURLBased);
         assert_eq!(
-            transform_url_for_preference("ssh://git@gitlab.com/owner/repo", PreferredAuth::Https)
+            transform_url_for_preference("https://forgejo.example.org/me/repo.git", &cfg)
                 .as_deref(),
+            Some("git@forgejo.example.org:me/repo.git")
+        );
+        assert!(transform_url_for_preference("https://github.com/owner/repo.git", &cfg).is_none());
+    }
+
+    #[test]
+    fn ssh_scp_to_https_known_hosts() {
+        assert_eq!(
+            transform_url_for_preference(
+                "git@github.com:owner/repo.git",
+                &auth(PreferredAuth::Https)
+            )
+            .as_deref(),
+            Some("https://github.com/owner/repo.git")
+        );
+        assert_eq!(
+            transform_url_for_preference(
+                "ssh://git@gitlab.com/owner/repo",
+                &auth(PreferredAuth::Https)
+            )
+            .as_deref(),
             Some("https://gitlab.com/owner/repo")
         );
     }
@@ -164,13 +374,19 @@ mod tests {
     #[test]
     fn https_to_ssh_known_hosts() {
         assert_eq!(
-            transform_url_for_preference("https://github.com/owner/repo.git", PreferredAuth::Ssh)
-                .as_deref(),
+            transform_url_for_preference(
+                "https://github.com/owner/repo.git",
+                &auth(PreferredAuth::Ssh)
+            )
+            .as_deref(),
             Some("git@github.com:owner/repo.git")
         );
         assert_eq!(
-            transform_url_for_preference("https://bitbucket.org/owner/repo", PreferredAuth::Ssh)
-                .as_deref(),
+            transform_url_for_preference(
+                "https://bitbucket.org/owner/repo",
+                &auth(PreferredAuth::Ssh)
+            )
+            .as_deref(),
             Some("git@bitbucket.org:owner/repo")
         );
     }
@@ -178,36 +394,65 @@ mod tests {
     #[test]
     fn http_upgrade_to_https_for_known_hosts() {
         assert_eq!(
-            transform_url_for_preference("http://github.com/owner/repo", PreferredAuth::Https)
-                .as_deref(),
+            transform_url_for_preference(
+                "http://github.com/owner/repo",
+                &auth(PreferredAuth::Https)
+            )
+            .as_deref(),
             Some("https://github.com/owner/repo")
         );
     }
 
+    #[test]
+    fn https_host_and_path_strips_user_info() {
+        assert_eq!(
+            https_host_and_path("https://alice@GitHub.com/our-org/repo.git"),
+            Some(("github.com".to_string(), "our-org/repo.git".to_string()))
+        );
+        assert_eq!(
+            https_host_and_path("https://git.example.com:8443/group/sub/repo"),
+            Some((
+                "git.example.com:8443".to_string(),
+                "group/sub/repo".to_string()
+            ))
+        );
+        assert!(https_host_and_path("git@github.com:owner/repo.git").is_none());
+    }
+
     #[test]
     fn unknown_hosts_do_not_transform() {
         assert!(
-            transform_url_for_preference("git@example.com:owner/repo", PreferredAuth::Https)
+            transform_url_for_preference("git@example.com:owner/repo", &auth(PreferredAuth::Https))
                 .is_none()
         );
         assert!(
-            transform_url_for_preference("https://example.com/owner/repo", PreferredAuth::Ssh)
-                .is_none()
+            transform_url_for_preference(
+                "https://example.com/owner/repo",
+                &auth(PreferredAuth::Ssh)
+            )
+            .is_none()
         );
         assert!(
-            transform_url_for_preference("http://example.com/owner/repo", PreferredAuth::Https)
-                .is_none()
+            transform_url_for_preference(
+                "http://example.com/owner/repo",
+                &auth(PreferredAuth::Https)
+            )
+            .is_none()
         );
     }
 
     #[test]
     fn no_op_when_already_matching_preference() {
         assert!(
-            transform_url_for_preference("https://github.com/owner/repo", PreferredAuth::Https)
-                .is_none()
+            transform_url_for_preference(
+                "https://github.com/owner/repo",
+                &auth(PreferredAuth::Https)
+            )
+            .is_none()
         );
         assert!(
-            transform_url_for_preference("git@github.com:owner/repo", PreferredAuth::Ssh).is_none()
+            transform_url_for_preference("git@github.com:owner/repo", &auth(PreferredAuth::Ssh))
+                .is_none()
         );
     }
 }
@@ -1,14 +1,26 @@
-use git2::{CertificateCheckStatus, CredentialType, RemoteCallbacks};
-use log::debug;
+use git2::{CredentialType, RemoteCallbacks};
 use std::sync::{Arc, Mutex};
 
 use crate::{
-    auth::{git_http::try_userpass_authentication, git_ssh::ssh_authenticate_git},
+    auth::{
+        git_http::try_userpass_authentication,
+        git_ssh::ssh_authenticate_git,
+        ssh::{ssh_port_for_url, verify_host_key},
+        tls::{apply_ca_bundle, check_certificate},
+    },
     config::global::BGitGlobalConfig,
 };
 
-pub fn setup_auth_callbacks<'a>(global_config: &'a BGitGlobalConfig) -> RemoteCallbacks<'a> {
+/// Callbacks for talking to the remote at `url`, which is also where the SSH port checked
+/// against known_hosts comes from
+pub fn setup_auth_callbacks<'a>(
+    global_config: &'a BGitGlobalConfig,
+    url: &str,
+) -> RemoteCallbacks<'a> {
     let mut callbacks = RemoteCallbacks::new();
+    let ssh_port = ssh_port_for_url(url);
+
+    apply_ca_bundle(global_config);
 
     // Track attempt count across callback invocations
     let attempt_count: Arc<Mutex<usize>> = Arc::new(Mutex::new(0));
@@ -20,7 +32,7 @@ pub fn setup_auth_callbacks<'a>(global_config: &'a BGitGlobalConfig) -> RemoteCa
         drop(count);
 
         if allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT) {
-            try_userpass_authentication(username_from_url, global_config)
+            try_userpass_authentication(url, username_from_url, global_config)
         } else {
             ssh_authenticate_git(
                 url,
@@ -32,11 +44,13 @@ pub fn setup_auth_callbacks<'a>(global_config: &'a BGitGlobalConfig) -> RemoteCa
         }
     });
 
-    // Set up certificate check callback for HTTPS
-    callbacks.certificate_check(|_cert, _host| {
-        // TODO(rootCircle): make this configurable and secure. For now we accept all certs.
-        debug!("Skipping certificate verification (INSECURE)");
-        Ok(CertificateCheckStatus::CertificateOk)
+    // HTTPS certificates go through the TLS policy, SSH host keys through known_hosts
+    callbacks.certificate_check(move |cert, host| {
+        if cert.as_hostkey().is_some() {
+            verify_host_key(cert, host, ssh_port)
+        } else {
+            check_certificate(cert, host, &global_config.auth.tls)
+        }
     });
 
     callbacks
@@ -0,0 +1,235 @@
+use log::debug;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+/// Credential handed to libgit2 during the current operation, approved or rejected
+/// through the helper once the operation's outcome is known
+static PENDING_CREDENTIAL: Mutex<Option<GitCredential>> = Mutex::new(None);
+
+/// A credential in `git credential` attribute form, as read from or written to helpers
+#[derive(Debug, Clone)]
+pub(crate) struct GitCredential {
+    attributes: Vec<(String, String)>,
+    work_dir: PathBuf,
+}
+
+impl GitCredential {
+    /// Credential entered by the user, to be stored by helpers if it works
+    pub fn new(work_dir: &Path, url: &str, username: &str, password: &str) -> Self {
+        GitCredential {
+            attributes: vec![
+                ("url".to_string(), url.to_string()),
+                ("username".to_string(), username.to_string()),
+                ("password".to_string(), password.to_string()),
+            ],
+            work_dir: work_dir.to_path_buf(),
+        }
+    }
+
+    /// Ask the configured `credential.helper`s for a credential via `git credential fill`.
+    /// Returns `None` when no helper knows one; git itself is never allowed to prompt.
+    pub fn fill(work_dir: &Path, url: &str, username: Option<&str>) -> Option<Self> {
+        let mut request = vec![("url".to_string(), url.to_string())];
+        if let Some(username) = username {
+            request.push(("username".to_string(), username.to_string()));
+        }
+
+        let output = run_git_credential(work_dir, "fill", &request)?;
+        let attributes: Vec<(String, String)> = output
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        let credential = GitCredential {
+            attributes,
+            work_dir: work_dir.to_path_buf(),
+        };
+        match (credential.username(), credential.password()) {
+            (Some(u), Some(p)) if !u.is_empty() && !p.is_empty() => Some(credential),
+            _ => None,
+        }
+    }
+
+    pub fn username(&self) -> Option<&str> {
+        self.get("username")
+    }
+
+    pub fn password(&self) -> Option<&str> {
+        self.get("password")
+    }
+
+    /// Tell helpers the credential worked so they can store it
+    pub fn approve(&self) {
+        run_git_credential(&self.work_dir, "approve", &self.attributes);
+    }
+
+    /// Tell helpers the credential was refused so they can erase it
+    pub fn reject(&self) {
+        run_git_credential(&self.work_dir, "reject", &self.attributes);
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Remember the credential given to libgit2 until the remote operation finishes
+pub(crate) fn set_pending_credential(credential: GitCredential) {
+    *PENDING_CREDENTIAL.lock().unwrap_or_else(|e| e.into_inner()) = Some(credential);
+}
+
+pub(crate) fn take_pending_credential() -> Option<GitCredential> {
+    PENDING_CREDENTIAL
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+}
+
+/// The remote operation succeeded: let helpers store the credential that was used
+pub(crate) fn approve_pending_credential() {
+    if let Some(credential) = take_pending_credential() {
+        debug!("Approving credential with git credential helpers");
+        credential.approve();
+    }
+}
+
+/// The remote operation failed: erase the credential from helpers if it was refused
+pub(crate) fn reject_pending_credential(error: &git2::Error) {
+    let Some(credential) = take_pending_credential() else {
+        return;
+    };
+    if is_auth_failure(error) {
+        debug!("Rejecting credential with git credential helpers: {error}");
+        credential.reject();
+    }
+}
+
+fn is_auth_failure(error: &git2::Error) -> bool {
+    let message = error.message().to_ascii_lowercase();
+    error.code() == git2::ErrorCode::Auth
+        || (error.class() == git2::ErrorClass::Http
+            && (message.contains("401") || message.contains("authentication")))
+}
+
+fn run_git_credential(
+    work_dir: &Path,
+    action: &str,
+    attributes: &[(String, String)],
+) -> Option<String> {
+    let mut child = Command::new("git")
+        .current_dir(work_dir)
+        .args(["credential", action])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| debug!("Failed to run git credential {action}: {e}"))
+        .ok()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let mut input = String::new();
+        for (key, value) in attributes {
+            input.push_str(&format!("{key}={value}\n"));
+        }
+        input.push('\n');
+        if let Err(e) = stdin.write_all(input.as_bytes()) {
+            debug!("Failed to write to git credential {action}: {e}");
+        }
+    }
+
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        debug!("git credential {action} exited with {}", output.status);
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Repository whose `credential.helper` is a script that answers `get` with a fixed
+    /// credential and logs every `store` and `erase` request
+    fn repo_with_fake_helper() -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+
+        let log_path = dir.path().join("helper.log");
+        let helper_path = dir.path().join("fake-helper.sh");
+        fs::write(
+            &helper_path,
+            format!(
+                "#!/bin/sh\n\
+                 input=$(cat)\n\
+                 case \"$1\" in\n\
+                   get) echo username=bob; echo password=s3cret ;;\n\
+                   *) printf '%s\\n%s\\n' \"$1\" \"$input\" >> '{}' ;;\n\
+                 esac\n",
+                log_path.display()
+            ),
+        )
+        .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&helper_path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("credential.helper", &helper_path.to_string_lossy())
+            .unwrap();
+        (dir, log_path)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fill_approve_reject_through_helper() {
+        let (dir, log_path) = repo_with_fake_helper();
+        let url = "https://git.example.com/team/repo.git";
+
+        let credential = GitCredential::fill(dir.path(), url, None).unwrap();
+        assert_eq!(credential.username(), Some("bob"));
+        assert_eq!(credential.password(), Some("s3cret"));
+
+        credential.approve();
+        credential.reject();
+
+        let log = fs::read_to_string(&log_path).unwrap();
+        let store = log.find("store").unwrap();
+        let erase = log.find("erase").unwrap();
+        assert!(store < erase);
+        assert!(log.contains("host=git.example.com"));
+        assert!(log.contains("password=s3cret"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_only_on_auth_failure() {
+        let (dir, log_path) = repo_with_fake_helper();
+        let credential = GitCredential::new(dir.path(), "https://example.com/r.git", "a", "b");
+
+        set_pending_credential(credential.clone());
+        reject_pending_credential(&git2::Error::from_str("connection reset"));
+        assert!(!log_path.exists());
+
+        set_pending_credential(credential);
+        reject_pending_credential(&git2::Error::new(
+            git2::ErrorCode::Auth,
+            git2::ErrorClass::Http,
+            "authentication required",
+        ));
+        assert!(fs::read_to_string(&log_path).unwrap().contains("erase"));
+    }
+}
@@ -1,18 +1,22 @@
-use dialoguer::{Confirm, Input, Password, theme::ColorfulTheme};
 use git2::{Cred, Error, ErrorClass, ErrorCode};
 use log::debug;
+use std::path::Path;
 
-use crate::auth::auth_utils::prompt_persist_preferred_auth;
+use crate::auth::auth_utils::{https_host_and_path, prompt_persist_preferred_auth};
+use crate::auth::git_credential::{GitCredential, set_pending_credential, take_pending_credential};
 use crate::config::global::{BGitGlobalConfig, PreferredAuth};
+use crate::prompt;
 
 pub fn try_userpass_authentication(
+    url: &str,
     username_from_url: Option<&str>,
     cfg: &BGitGlobalConfig,
 ) -> Result<Cred, Error> {
     debug!("USER_PASS_PLAINTEXT authentication allowed; trying global config first");
-    // Try global config first; fall back to prompt if it fails
-    if let Some((u, t)) = cfg.get_https_credentials() {
-        match Cred::userpass_plaintext(u, t) {
+    // Try global config first, then git credential helpers; fall back to prompt if both fail
+    let (host, path) = https_host_and_path(url).unwrap_or_default();
+    if let Some((u, t)) = cfg.get_https_credentials_for(&host, &path) {
+        match Cred::userpass_plaintext(&u, &t) {
             Ok(cred) => {
                 debug!("Using HTTPS credentials from global config");
                 return Ok(cred);
@@ -23,39 +27,60 @@ pub fn try_userpass_authentication(
         }
     }
 
+    // libgit2 only asks again when the credential handed out last was refused
+    let refused = take_pending_credential();
+    if let Some(previous) = &refused {
+        debug!("Previous credential was refused by the server");
+        previous.reject();
+    } else if let Some(credential) = GitCredential::fill(Path::new("."), url, username_from_url)
+        && let (Some(u), Some(t)) = (credential.username(), credential.password())
+    {
+        match Cred::userpass_plaintext(u, t) {
+            Ok(cred) => {
+                debug!("Using HTTPS credentials from git credential helper");
+                set_pending_credential(credential);
+                return Ok(cred);
+            }
+            Err(e) => {
+                debug!("Credential helper credentials failed: {e}; falling back to prompt");
+            }
+        }
+    }
+
     let username = if let Some(user) = username_from_url {
         user.to_string()
     } else {
-        Input::<String>::with_theme(&ColorfulTheme::default())
-            .with_prompt("Enter your username")
-            .interact()
-            .map_err(|e| {
-                Error::new(
-                    ErrorCode::Auth,
-                    ErrorClass::Net,
-                    format!("Failed to read username: {e}"),
-                )
-            })?
-    };
-
-    let token = Password::with_theme(&ColorfulTheme::default())
-        .with_prompt("Enter your personal access token")
-        .interact()
-        .map_err(|e| {
+        prompt::input("auth.https_username", "Enter your username", None).map_err(|e| {
             Error::new(
                 ErrorCode::Auth,
                 ErrorClass::Net,
-                format!("Failed to read token: {e}"),
+                format!("Failed to read username: {e}"),
             )
-        })?;
+        })?
+    };
+
+    let token = prompt::password(
+        "auth.https_token",
+        "Enter your personal access token",
+        false,
+    )
+    .map_err(|e| {
+        Error::new(
+            ErrorCode::Auth,
+            ErrorClass::Net,
+            format!("Failed to read token: {e}"),
+        )
+    })?;
 
     if !username.is_empty() && !token.is_empty() {
         debug!("Creating credentials with username and token");
         match Cred::userpass_plaintext(&username, &token) {
             Ok(cred) => {
                 debug!("Username/token authentication succeeded");
+                // Stored by credential helpers once the server accepts it
+                set_pending_credential(GitCredential::new(Path::new("."), url, &username, &token));
                 // Offer to save to global config
-                prompt_persist_https_credentials(cfg, &username, &token);
+                prompt_persist_https_credentials(cfg, url, &username, &token);
                 // Offer to set preferred auth to HTTPS
                 prompt_persist_preferred_auth(cfg, PreferredAuth::Https);
                 Ok(cred)
@@ -75,37 +100,90 @@ pub fn try_userpass_authentication(
     }
 }
 
-fn prompt_persist_https_credentials(cfg: &BGitGlobalConfig, username: &str, token: &str) {
+/// Where prompted HTTPS credentials get saved
+enum SaveTarget {
+    Account(String),
+    Default,
+    Skip,
+}
+
+fn prompt_persist_https_credentials(
+    cfg: &BGitGlobalConfig,
+    url: &str,
+    username: &str,
+    token: &str,
+) {
+    let (host, path) = https_host_and_path(url).unwrap_or_default();
+
     // Skip if already configured with identical values
-    if cfg.auth.https.username.as_deref() == Some(username)
-        && cfg.auth.https.pat.as_deref() == Some(token)
+    if cfg
+        .get_https_credentials_for(&host, &path)
+        .is_some_and(|(u, t)| u == username && t == token)
     {
         return;
     }
 
+    // Offer the matching account first, then narrower to broader scopes
+    let mut targets: Vec<(String, SaveTarget)> = Vec::new();
+    if let Some(account) = cfg.find_https_account(&host, &path) {
+        targets.push((
+            format!("Update the account for {}", account.host),
+            SaveTarget::Account(account.host.clone()),
+        ));
+    } else if !host.is_empty() {
+        if let Some(owner) = path.split('/').next().filter(|o| !o.is_empty()) {
+            targets.push((
+                format!("Only for {host}/{owner}/*"),
+                SaveTarget::Account(format!("{host}/{owner}/*")),
+            ));
+        }
+        targets.push((
+            format!("For every repository on {host}"),
+            SaveTarget::Account(host.clone()),
+        ));
+    }
+    targets.push((
+        "As the default for all hosts".to_string(),
+        SaveTarget::Default,
+    ));
+    targets.push(("Don't save".to_string(), SaveTarget::Skip));
+
+    let labels: Vec<&str> = targets.iter().map(|(label, _)| label.as_str()).collect();
     let question = format!(
-        "Save HTTPS credentials for '{}' to global config? (token stored base64-encoded)",
+        "Save HTTPS credentials for '{}' to global config? (token stored in the encrypted vault)",
         username
     );
-    let confirm = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt(question)
-        .default(false)
-        .interact()
-        .unwrap_or(false);
-    if !confirm {
-        debug!("User declined persisting HTTPS credentials");
-        return;
-    }
+    let selection = prompt::select(
+        "auth.save_https_credentials",
+        &question,
+        &labels,
+        labels.len() - 1,
+    )
+    .unwrap_or(labels.len() - 1);
 
     let mut cfg_owned = cfg.clone();
-    cfg_owned.auth.https.username = Some(username.to_string());
-    cfg_owned.auth.https.pat = Some(token.to_string());
+    let scope = match &targets[selection].1 {
+        SaveTarget::Account(pattern) => {
+            cfg_owned.set_https_account(pattern, username, token);
+            pattern.clone()
+        }
+        SaveTarget::Default => {
+            cfg_owned.auth.https.username = Some(username.to_string());
+            cfg_owned.auth.https.pat = Some(token.to_string());
+            "all hosts".to_string()
+        }
+        SaveTarget::Skip => {
+            debug!("User declined persisting HTTPS credentials");
+            return;
+        }
+    };
+
     if let Err(e) = cfg_owned.save_global() {
         debug!("Failed to persist HTTPS credentials: {:?}", e);
     } else {
         println!(
-            "Saved HTTPS username + token to global config for user '{}'.",
-            username
+            "Saved HTTPS username + token to global config for user '{}' ({}).",
+            username, scope
         );
         debug!("Persisted HTTPS credentials for user '{}'.", username);
     }
@@ -1,4 +1,3 @@
-use dialoguer::{Confirm, Select, theme::ColorfulTheme};
 use git2::{Cred, CredentialType, Error, ErrorClass, ErrorCode};
 use log::debug;
 use std::path::PathBuf;
@@ -7,10 +6,11 @@ use crate::auth::auth_utils::prompt_persist_preferred_auth;
 use crate::auth::ssh::{
     add_all_ssh_keys_with_auth, add_key_interactive_with_auth, agent_identities_count_with_auth,
     ensure_agent_ready, get_effective_ssh_auth, set_global_ssh_env_for_libgit2,
-    try_ssh_key_files_directly,
+    ssh_host_config_for_url, ssh_key_from_file, try_ssh_key_files_directly,
 };
 use crate::config::global::{BGitGlobalConfig, PreferredAuth};
 use crate::constants::MAX_AUTH_ATTEMPTS;
+use crate::prompt;
 
 pub fn ssh_authenticate_git(
     url: &str,
@@ -23,8 +23,16 @@ pub fn ssh_authenticate_git(
     debug!("Username from URL: {username_from_url:?}");
     debug!("Allowed credential types: {allowed_types:?}");
 
+    // IdentityFile entries from ~/.ssh/config get one attempt each before the agent is tried
+    let host_config = ssh_host_config_for_url(url);
+    let identity_files: Vec<&PathBuf> = host_config
+        .identity_files
+        .iter()
+        .filter(|path| path.exists())
+        .collect();
+
     // Prevent infinite loops
-    if attempt_count > MAX_AUTH_ATTEMPTS {
+    if attempt_count > MAX_AUTH_ATTEMPTS + identity_files.len() {
         debug!(
             "Too many authentication attempts ({attempt_count}), failing to prevent infinite loop"
         );
@@ -37,6 +45,25 @@ pub fn ssh_authenticate_git(
 
     if allowed_types.contains(CredentialType::SSH_KEY) {
         if let Some(username) = username_from_url {
+            if let Some(key_path) = attempt_count
+                .checked_sub(1)
+                .and_then(|index| identity_files.get(index))
+            {
+                match ssh_key_from_file(username, key_path) {
+                    Ok(cred) => return Ok(cred),
+                    Err(e) => debug!("Identity file {key_path:?} could not be used: {e}"),
+                }
+            }
+
+            if host_config.identities_only && !identity_files.is_empty() {
+                return Err(Error::new(
+                    ErrorCode::Auth,
+                    ErrorClass::Ssh,
+                    "IdentitiesOnly is set in ~/.ssh/config and none of the configured IdentityFile keys were accepted",
+                ));
+            }
+
+            let attempt_count = attempt_count.saturating_sub(identity_files.len()).max(1);
             debug!("SSH key authentication is allowed, trying SSH agent");
 
             // Before auth attempt 1, ensure an agent is available and has at least 1 identity.
@@ -154,12 +181,13 @@ fn offer_manual_key_addition(socket_path: Option<&str>) -> bool {
     }
     options.push("Skip manual key addition".to_string());
 
-    let selection = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("Would you like to add an SSH key to the agent?")
-        .items(&options)
-        .default(0)
-        .interact()
-        .unwrap_or(options.len() - 1); // Default to "skip" on error
+    let selection = prompt::select(
+        "auth.ssh_agent_key",
+        "Would you like to add an SSH key to the agent?",
+        &options,
+        0,
+    )
+    .unwrap_or(options.len() - 1); // Default to "skip" on error
 
     if selection >= available_keys.len() {
         debug!("User chose to skip manual key addition");
@@ -196,11 +224,7 @@ fn prompt_persist_key_file(cfg: &BGitGlobalConfig, path: &std::path::Path) {
         "Use '{}' as your default SSH key and save it to global config?",
         path_str
     );
-    let confirm = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt(question)
-        .default(true)
-        .interact()
-        .unwrap_or(false);
+    let confirm = prompt::confirm("auth.save_ssh_key_file", &question, true).unwrap_or(false);
     if !confirm {
         debug!("User declined persisting ssh key_file");
         return;
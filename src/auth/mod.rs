@@ -1,5 +1,7 @@
 pub mod auth_utils;
 pub mod git_auth;
+pub(crate) mod git_credential;
 mod git_http;
 mod git_ssh;
 pub mod ssh;
+mod tls;
@@ -0,0 +1,460 @@
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+use colored::Colorize;
+use git2::{CertificateCheckStatus, ErrorClass, ErrorCode, cert::Cert};
+use log::{debug, warn};
+use ring::{digest, hmac};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
+use crate::prompt;
+
+/// System-wide known hosts, only read and never written to
+const GLOBAL_KNOWN_HOSTS: &str = "/etc/ssh/ssh_known_hosts";
+
+/// Host key failure from the last certificate check, kept so callers can report it
+/// as an authentication error instead of the generic transport error libgit2 returns
+static HOST_KEY_ERROR: Mutex<Option<Box<BGitError>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Marker {
+    CertAuthority,
+    Revoked,
+}
+
+#[derive(Debug, Clone)]
+struct KnownHostEntry {
+    marker: Option<Marker>,
+    hosts: String,
+    key_type: String,
+    key: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum HostKeyMatch {
+    /// A known_hosts entry for this host has the presented key
+    Trusted,
+    /// The host is known with a different key of the same type
+    Changed,
+    /// The presented key is explicitly marked as `@revoked`
+    Revoked,
+    /// No entry for this host and key type
+    Unknown,
+}
+
+/// Take the host key error recorded by the last failed certificate check, if any
+pub fn take_host_key_error() -> Option<Box<BGitError>> {
+    HOST_KEY_ERROR
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+}
+
+fn record_host_key_error(name: &str, message: &str) -> git2::Error {
+    *HOST_KEY_ERROR.lock().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(BGitError::new(
+        name,
+        message,
+        BGitErrorWorkflowType::Authentication,
+        NO_STEP,
+        NO_EVENT,
+        NO_RULE,
+    )));
+    git2::Error::new(ErrorCode::Certificate, ErrorClass::Ssh, message)
+}
+
+/// Verify the SSH host key presented by `host` on `port` against `~/.ssh/known_hosts`
+/// and the system known hosts file, offering to trust unknown hosts on first use.
+pub(crate) fn verify_host_key(
+    cert: &Cert<'_>,
+    host: &str,
+    port: u16,
+) -> Result<CertificateCheckStatus, git2::Error> {
+    // The name ssh looks up and records, `[host]:port` off the default port
+    let host = &known_hosts_name(host, port);
+    let hostkey = cert
+        .as_hostkey()
+        .ok_or_else(|| git2::Error::from_str("Certificate is not an SSH host key"))?;
+
+    let (Some(key), Some(key_type)) = (hostkey.hostkey(), hostkey.hostkey_type()) else {
+        return Err(record_host_key_error(
+            "SSH host key unavailable",
+            &format!(
+                "The SSH transport did not provide the raw host key for {host}, so it cannot be verified"
+            ),
+        ));
+    };
+
+    let user_known_hosts = user_known_hosts_path();
+    let mut entries = Vec::new();
+    for path in user_known_hosts
+        .iter()
+        .map(PathBuf::as_path)
+        .chain([Path::new(GLOBAL_KNOWN_HOSTS)])
+    {
+        if let Ok(content) = fs::read_to_string(path) {
+            entries.extend(parse_known_hosts(&content));
+        }
+    }
+
+    let fingerprint = sha256_fingerprint(key);
+    match check_host_key(&entries, host, key_type.name(), key) {
+        HostKeyMatch::Trusted => {
+            debug!("Host key for {host} matches known_hosts ({fingerprint})");
+            Ok(CertificateCheckStatus::CertificateOk)
+        }
+        HostKeyMatch::Changed => Err(record_host_key_error(
+            "SSH host key changed",
+            &format!(
+                "The {} host key for '{host}' does not match the one in known_hosts (now {fingerprint}). \
+                 Someone could be intercepting the connection, or the host key was rotated. \
+                 Verify the new key with the host administrator and remove the old entry with `ssh-keygen -R {host}`.",
+                key_type.short_name()
+            ),
+        )),
+        HostKeyMatch::Revoked => Err(record_host_key_error(
+            "SSH host key revoked",
+            &format!(
+                "The host key presented by '{host}' ({fingerprint}) is marked as @revoked in known_hosts"
+            ),
+        )),
+        HostKeyMatch::Unknown => {
+            if !prompt_trust_on_first_use(host, key_type.short_name(), &fingerprint) {
+                return Err(record_host_key_error(
+                    "SSH host not trusted",
+                    &format!("Host key for '{host}' ({fingerprint}) was not accepted"),
+                ));
+            }
+
+            match &user_known_hosts {
+                Some(path) => match append_known_host(path, host, key_type.name(), key) {
+                    Ok(()) => println!("Permanently added '{host}' to {}", path.display()),
+                    Err(e) => warn!("Failed to add '{host}' to {}: {e}", path.display()),
+                },
+                None => warn!(
+                    "Could not resolve ~/.ssh/known_hosts, trusting '{host}' for this session only"
+                ),
+            }
+            Ok(CertificateCheckStatus::CertificateOk)
+        }
+    }
+}
+
+fn known_hosts_name(host: &str, port: u16) -> String {
+    if port == 22 {
+        host.to_string()
+    } else {
+        format!("[{host}]:{port}")
+    }
+}
+
+fn user_known_hosts_path() -> Option<PathBuf> {
+    home::home_dir().map(|home| home.join(".ssh").join("known_hosts"))
+}
+
+fn prompt_trust_on_first_use(host: &str, key_type: &str, fingerprint: &str) -> bool {
+    eprintln!(
+        "{}",
+        format!("The authenticity of host '{host}' can't be established.").yellow()
+    );
+    eprintln!("{key_type} key fingerprint is {fingerprint}.");
+
+    prompt::confirm(
+        "auth.trust_host_key",
+        "Trust this host and add it to known_hosts?",
+        false,
+    )
+    .unwrap_or(false)
+}
+
+fn parse_known_hosts(content: &str) -> Vec<KnownHostEntry> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+
+            let mut fields = line.split_whitespace();
+            let mut first = fields.next()?;
+            let marker = match first {
+                "@cert-authority" => Some(Marker::CertAuthority),
+                "@revoked" => Some(Marker::Revoked),
+                _ => None,
+            };
+            if marker.is_some() {
+                first = fields.next()?;
+            }
+
+            let key_type = fields.next()?.to_string();
+            let key = STANDARD.decode(fields.next()?).ok()?;
+            Some(KnownHostEntry {
+                marker,
+                hosts: first.to_string(),
+                key_type,
+                key,
+            })
+        })
+        .collect()
+}
+
+fn check_host_key(
+    entries: &[KnownHostEntry],
+    host: &str,
+    key_type: &str,
+    key: &[u8],
+) -> HostKeyMatch {
+    // A revoked key is rejected no matter which host lists it
+    if entries
+        .iter()
+        .any(|entry| entry.marker == Some(Marker::Revoked) && entry.key == key)
+    {
+        return HostKeyMatch::Revoked;
+    }
+
+    let host_entries: Vec<&KnownHostEntry> = entries
+        .iter()
+        .filter(|entry| entry.marker.is_none() && host_matches(&entry.hosts, host))
+        .collect();
+
+    if host_entries.iter().any(|entry| entry.key == key) {
+        HostKeyMatch::Trusted
+    } else if host_entries.iter().any(|entry| entry.key_type == key_type) {
+        HostKeyMatch::Changed
+    } else {
+        HostKeyMatch::Unknown
+    }
+}
+
+/// Match a comma separated known_hosts host field against `host`, as returned by
+/// `known_hosts_name`, honouring hashed entries, `*`/`?` wildcards and `!` negations
+fn host_matches(hosts_field: &str, host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    let mut matched = false;
+
+    for pattern in hosts_field.split(',') {
+        if let Some(hashed) = pattern.strip_prefix("|1|") {
+            if hashed_host_matches(hashed, &host) {
+                matched = true;
+            }
+            continue;
+        }
+
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        // `[host]:22` is how ssh records the default port explicitly
+        let pattern = pattern
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix("]:22"))
+            .unwrap_or(pattern)
+            .to_ascii_lowercase();
+
+        if wildcard_match(&pattern, &host) {
+            if negated {
+                return false;
+            }
+            matched = true;
+        }
+    }
+
+    matched
+}
+
+/// `|1|<base64 salt>|<base64 HMAC-SHA1(salt, host)>`, as written by `HashKnownHosts yes`
+fn hashed_host_matches(hashed: &str, host: &str) -> bool {
+    let Some((salt, expected)) = hashed.split_once('|') else {
+        return false;
+    };
+    let (Ok(salt), Ok(expected)) = (STANDARD.decode(salt), STANDARD.decode(expected)) else {
+        return false;
+    };
+
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, &salt);
+    hmac::verify(&key, host.as_bytes(), &expected).is_ok()
+}
+
+/// Match `text` against an OpenSSH pattern with `*` and `?` wildcards
+pub(super) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    fn matches(pattern: &[u8], text: &[u8]) -> bool {
+        match (pattern.first(), text.first()) {
+            (None, None) => true,
+            (Some(b'*'), _) => {
+                matches(&pattern[1..], text) || (!text.is_empty() && matches(pattern, &text[1..]))
+            }
+            (Some(b'?'), Some(_)) => matches(&pattern[1..], &text[1..]),
+            (Some(p), Some(t)) if p == t => matches(&pattern[1..], &text[1..]),
+            _ => false,
+        }
+    }
+    matches(pattern.as_bytes(), text.as_bytes())
+}
+
+fn append_known_host(path: &Path, host: &str, key_type: &str, key: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let needs_newline = fs::read(path)
+        .map(|content| !content.is_empty() && !content.ends_with(b"\n"))
+        .unwrap_or(false);
+
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    if needs_newline {
+        writeln!(file)?;
+    }
+    writeln!(file, "{host} {key_type} {}", STANDARD.encode(key))
+}
+
+/// OpenSSH style `SHA256:<base64>` fingerprint of a raw public key blob
+fn sha256_fingerprint(key: &[u8]) -> String {
+    let digest = digest::digest(&digest::SHA256, key);
+    format!("SHA256:{}", STANDARD_NO_PAD.encode(digest.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const ED25519_KEY: &str =
+        "AAAAC3NzaC1lZDI1NTE5AAAAICUmZzHRRRT+9yVfv7hz3Uihykag0lClrbCD5WiSBZxk";
+    const OTHER_ED25519_KEY: &str =
+        "AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl";
+
+    fn key(b64: &str) -> Vec<u8> {
+        STANDARD.decode(b64).unwrap()
+    }
+
+    #[test]
+    fn test_plain_and_hashed_entries() {
+        // Second line was produced by `ssh-keygen -H` from the first
+        let content = format!(
+            "github.com ssh-ed25519 {ED25519_KEY}\n\
+             |1|o3IMScXg3adcy0ZmSQuDma3bPTI=|dZpYFIUfvfQ8CTLMTKfxik5CEHs= ssh-ed25519 {ED25519_KEY}\n"
+        );
+        let entries = parse_known_hosts(&content);
+        assert_eq!(entries.len(), 2);
+
+        assert!(host_matches(&entries[0].hosts, "github.com"));
+        assert!(host_matches(&entries[1].hosts, "github.com"));
+        assert!(host_matches(&entries[1].hosts, "GitHub.com"));
+        assert!(!host_matches(&entries[1].hosts, "gitlab.com"));
+    }
+
+    #[test]
+    fn test_patterns() {
+        assert!(host_matches(
+            "*.example.com,!bad.example.com",
+            "git.example.com"
+        ));
+        assert!(!host_matches(
+            "*.example.com,!bad.example.com",
+            "bad.example.com"
+        ));
+        assert!(host_matches("git?.example.com", "git1.example.com"));
+        assert!(host_matches("[git.example.com]:22", "git.example.com"));
+        assert!(!host_matches("[git.example.com]:2222", "git.example.com"));
+        assert!(host_matches(
+            "[git.example.com]:2222",
+            &known_hosts_name("git.example.com", 2222)
+        ));
+        assert!(host_matches(
+            "[*.example.com]:2222",
+            &known_hosts_name("git.example.com", 2222)
+        ));
+        assert!(!host_matches(
+            "git.example.com,[git.example.com]:22",
+            &known_hosts_name("git.example.com", 2222)
+        ));
+    }
+
+    #[test]
+    fn test_check_host_key_outcomes() {
+        let content = format!(
+            "github.com ssh-ed25519 {ED25519_KEY}\n\
+             @revoked * ssh-ed25519 {OTHER_ED25519_KEY}\n"
+        );
+        let entries = parse_known_hosts(&content);
+
+        assert_eq!(
+            check_host_key(&entries, "github.com", "ssh-ed25519", &key(ED25519_KEY)),
+            HostKeyMatch::Trusted
+        );
+        assert_eq!(
+            check_host_key(
+                &entries,
+                "github.com",
+                "ssh-ed25519",
+                &key(OTHER_ED25519_KEY)
+            ),
+            HostKeyMatch::Revoked
+        );
+        assert_eq!(
+            check_host_key(&entries, "gitlab.com", "ssh-ed25519", &key(ED25519_KEY)),
+            HostKeyMatch::Unknown
+        );
+
+        let rotated = parse_known_hosts(&format!("github.com ssh-ed25519 {OTHER_ED25519_KEY}\n"));
+        assert_eq!(
+            check_host_key(&rotated, "github.com", "ssh-ed25519", &key(ED25519_KEY)),
+            HostKeyMatch::Changed
+        );
+        // A different key type for a known host is not a mismatch
+        assert_eq!(
+            check_host_key(&rotated, "github.com", "ssh-rsa", &key(ED25519_KEY)),
+            HostKeyMatch::Unknown
+        );
+    }
+
+    #[test]
+    fn test_append_known_host_and_fingerprint() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".ssh").join("known_hosts");
+
+        append_known_host(&path, "example.com", "ssh-ed25519", &key(ED25519_KEY)).unwrap();
+        let entries = parse_known_hosts(&fs::read_to_string(&path).unwrap());
+        assert_eq!(
+            check_host_key(&entries, "example.com", "ssh-ed25519", &key(ED25519_KEY)),
+            HostKeyMatch::Trusted
+        );
+
+        // Off port 22 ssh keys the entry by `[host]:port`, which must not vouch for port 22
+        let name = known_hosts_name("example.com", 2222);
+        append_known_host(&path, &name, "ssh-ed25519", &key(OTHER_ED25519_KEY)).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.ends_with(&format!(
+            "[example.com]:2222 ssh-ed25519 {OTHER_ED25519_KEY}\n"
+        )));
+        let entries = parse_known_hosts(&content);
+        assert_eq!(
+            check_host_key(&entries, &name, "ssh-ed25519", &key(OTHER_ED25519_KEY)),
+            HostKeyMatch::Trusted
+        );
+        assert_eq!(
+            check_host_key(&entries, &name, "ssh-ed25519", &key(ED25519_KEY)),
+            HostKeyMatch::Changed
+        );
+        assert_eq!(
+            check_host_key(&entries, "example.com", "ssh-ed25519", &key(ED25519_KEY)),
+            HostKeyMatch::Trusted
+        );
+
+        assert_eq!(
+            sha256_fingerprint(&key(ED25519_KEY)),
+            "SHA256:nzu8powkrmdj8ipKOWAfB5cgMqhx29Ftfezid2Idbkk"
+        );
+    }
+}
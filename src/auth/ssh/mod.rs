@@ -1,5 +1,7 @@
 // Shared utilities (platform-agnostic)
 mod agent;
+mod known_hosts;
+mod ssh_config;
 mod ssh_utils;
 
 // Platform-specific SSH implementations
@@ -29,8 +31,14 @@ pub mod platform {
 // Re-export common SSH functions from ssh_utils (platform-agnostic)
 pub use ssh_utils::{
     add_all_ssh_keys_with_auth, add_key_interactive_with_auth, agent_identities_count_with_auth,
-    get_effective_ssh_auth, set_global_ssh_env_for_libgit2, try_ssh_key_files_directly,
+    get_effective_ssh_auth, set_global_ssh_env_for_libgit2, ssh_key_from_file,
+    try_ssh_key_files_directly,
 };
 
+pub use known_hosts::take_host_key_error;
+pub(crate) use known_hosts::verify_host_key;
+pub use ssh_config::resolve_ssh_alias_url;
+pub(crate) use ssh_config::{ssh_host_config_for_url, ssh_port_for_url};
+
 // Re-export platform-specific functions
 pub use platform::ensure_agent_ready;
@@ -0,0 +1,427 @@
+use log::debug;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::known_hosts::wildcard_match;
+
+const GLOBAL_SSH_CONFIG: &str = "/etc/ssh/ssh_config";
+/// Include depth limit, same as OpenSSH's
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Host settings resolved for URLs rewritten by `resolve_ssh_alias_url`, keyed by the
+/// rewritten URL, so authentication still uses the alias' IdentityFile and not the real host's
+static RESOLVED_ALIASES: Mutex<Option<HashMap<String, SshHostConfig>>> = Mutex::new(None);
+
+/// Settings from `~/.ssh/config` that apply to one host alias
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SshHostConfig {
+    pub host_name: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+    pub identity_files: Vec<PathBuf>,
+    pub identities_only: bool,
+}
+
+/// Resolve `host` against the user's and the system's ssh_config
+pub(crate) fn resolve_ssh_host(host: &str) -> SshHostConfig {
+    let Some(home) = home::home_dir() else {
+        return SshHostConfig::default();
+    };
+
+    let mut resolver = Resolver::new(host, &home);
+    resolver.apply_file(&home.join(".ssh").join("config"), &home.join(".ssh"), 0);
+    resolver.apply_file(
+        Path::new(GLOBAL_SSH_CONFIG),
+        Path::new(GLOBAL_SSH_CONFIG)
+            .parent()
+            .unwrap_or(Path::new("/")),
+        0,
+    );
+    resolver.finish()
+}
+
+/// Settings for the host of an SSH remote URL, including aliases resolved earlier in this run
+pub(crate) fn ssh_host_config_for_url(url: &str) -> SshHostConfig {
+    if let Some(resolved) = RESOLVED_ALIASES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|aliases| aliases.get(url))
+    {
+        return resolved.clone();
+    }
+
+    match parse_ssh_url(url) {
+        Some(parts) => resolve_ssh_host(&parts.host),
+        None => SshHostConfig::default(),
+    }
+}
+
+/// Port of an SSH remote URL, 22 unless `ssh://host:port/...` names another one
+pub(crate) fn ssh_port_for_url(url: &str) -> u16 {
+    parse_ssh_url(url)
+        .and_then(|parts| parts.port)
+        .unwrap_or(22)
+}
+
+/// Rewrite an SSH remote whose host is an ssh_config alias (`git@gh-work:org/repo`) to the
+/// real HostName, User and Port, since libgit2 does not read ssh_config itself.
+/// Returns `None` for non-SSH URLs or when nothing changes.
+pub fn resolve_ssh_alias_url(url: &str) -> Option<String> {
+    let parts = parse_ssh_url(url)?;
+    let config = resolve_ssh_host(&parts.host);
+
+    let host = config
+        .host_name
+        .clone()
+        .unwrap_or_else(|| parts.host.clone());
+    let user = parts.user.clone().or_else(|| config.user.clone());
+    let port = parts.port.or(config.port);
+    if host == parts.host && user == parts.user && port == parts.port {
+        return None;
+    }
+
+    let user_prefix = user.map(|u| format!("{u}@")).unwrap_or_default();
+    let path = parts.path.trim_start_matches('/');
+    let rewritten = match port {
+        Some(port) if port != 22 => format!("ssh://{user_prefix}{host}:{port}/{path}"),
+        _ => format!("{user_prefix}{host}:{path}"),
+    };
+
+    debug!("Resolved SSH alias '{}' to {rewritten}", parts.host);
+    RESOLVED_ALIASES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .insert(rewritten.clone(), config);
+    Some(rewritten)
+}
+
+struct SshUrlParts {
+    user: Option<String>,
+    host: String,
+    port: Option<u16>,
+    path: String,
+}
+
+/// Parse `ssh://[user@]host[:port]/path` and scp-like `[user@]host:path`
+fn parse_ssh_url(url: &str) -> Option<SshUrlParts> {
+    let (authority, path, explicit_scheme) = if let Some(rest) = url.strip_prefix("ssh://") {
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        (authority, path, true)
+    } else if url.contains("://") {
+        return None;
+    } else {
+        let (authority, path) = url.split_once(':')?;
+        (authority, path, false)
+    };
+
+    let (user, host_port) = match authority.rsplit_once('@') {
+        Some((user, host)) => (Some(user.to_string()), host),
+        None => (None, authority),
+    };
+    let (host, port) = match host_port.rsplit_once(':') {
+        Some((host, port)) if explicit_scheme => (host, port.parse().ok()),
+        _ => (host_port, None),
+    };
+    if host.is_empty() {
+        return None;
+    }
+
+    Some(SshUrlParts {
+        user,
+        host: host.to_string(),
+        port,
+        path: path.to_string(),
+    })
+}
+
+/// Walks ssh_config files the way `ssh -G` does: the first value obtained for a keyword
+/// wins, except IdentityFile which accumulates
+struct Resolver<'h> {
+    host: String,
+    home: &'h Path,
+    config: SshHostConfig,
+    identities_only: Option<bool>,
+}
+
+impl<'h> Resolver<'h> {
+    fn new(host: &str, home: &'h Path) -> Self {
+        Resolver {
+            host: host.to_ascii_lowercase(),
+            home,
+            config: SshHostConfig::default(),
+            identities_only: None,
+        }
+    }
+
+    fn finish(mut self) -> SshHostConfig {
+        self.config.identities_only = self.identities_only.unwrap_or(false);
+        self.config
+    }
+
+    fn apply_file(&mut self, path: &Path, include_base: &Path, depth: usize) {
+        if depth > MAX_INCLUDE_DEPTH {
+            debug!("ssh_config Include nesting too deep at {}", path.display());
+            return;
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            return;
+        };
+
+        // Lines before the first Host/Match apply to every host
+        let mut active = true;
+        for line in content.lines() {
+            let Some((keyword, args)) = split_directive(line) else {
+                continue;
+            };
+
+            match keyword.as_str() {
+                "host" => active = self.host_matches(&args),
+                // Only `Match all` is understood; other criteria need ssh itself to evaluate
+                "match" => active = args.len() == 1 && args[0].eq_ignore_ascii_case("all"),
+                "include" if active => {
+                    for pattern in &args {
+                        for file in self.expand_include(pattern, include_base) {
+                            self.apply_file(&file, include_base, depth + 1);
+                        }
+                    }
+                }
+                _ if active => self.apply_directive(&keyword, &args),
+                _ => {}
+            }
+        }
+    }
+
+    fn apply_directive(&mut self, keyword: &str, args: &[String]) {
+        let Some(value) = args.first() else {
+            return;
+        };
+
+        match keyword {
+            "hostname" if self.config.host_name.is_none() => {
+                self.config.host_name = Some(value.replace("%h", &self.host));
+            }
+            "user" if self.config.user.is_none() => self.config.user = Some(value.clone()),
+            "port" if self.config.port.is_none() => self.config.port = value.parse().ok(),
+            "identitiesonly" if self.identities_only.is_none() => {
+                self.identities_only = Some(value.eq_ignore_ascii_case("yes"));
+            }
+            "identityfile" if !value.eq_ignore_ascii_case("none") => {
+                let path = self.expand_path(value);
+                if !self.config.identity_files.contains(&path) {
+                    self.config.identity_files.push(path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// A Host line matches if any pattern matches and no negated pattern does
+    fn host_matches(&self, patterns: &[String]) -> bool {
+        let mut matched = false;
+        for pattern in patterns {
+            let pattern = pattern.to_ascii_lowercase();
+            match pattern.strip_prefix('!') {
+                Some(negated) if wildcard_match(negated, &self.host) => return false,
+                Some(_) => {}
+                None => matched |= wildcard_match(&pattern, &self.host),
+            }
+        }
+        matched
+    }
+
+    /// Expand `~` and the `%d`, `%h`, `%r` and `%%` tokens
+    fn expand_path(&self, value: &str) -> PathBuf {
+        let home = self.home.to_string_lossy();
+        let host = self.config.host_name.as_deref().unwrap_or(&self.host);
+        let user = self.config.user.as_deref().unwrap_or("");
+
+        let mut expanded = String::new();
+        let mut chars = value.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, chars.peek()) {
+                ('%', Some('d')) => expanded.push_str(&home),
+                ('%', Some('h')) => expanded.push_str(host),
+                ('%', Some('r')) => expanded.push_str(user),
+                ('%', Some('%')) => expanded.push('%'),
+                _ => {
+                    expanded.push(c);
+                    continue;
+                }
+            }
+            chars.next();
+        }
+
+        match expanded.strip_prefix("~/") {
+            Some(rest) => self.home.join(rest),
+            None => PathBuf::from(expanded),
+        }
+    }
+
+    /// Relative Include paths are resolved against `~/.ssh` (or `/etc/ssh` for the system
+    /// config); `*` and `?` are allowed in the file name
+    fn expand_include(&self, pattern: &str, include_base: &Path) -> Vec<PathBuf> {
+        let path = self.expand_path(pattern);
+        let path = if path.is_absolute() {
+            path
+        } else {
+            include_base.join(path)
+        };
+
+        let file_pattern = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if !file_pattern.contains(['*', '?']) {
+            return vec![path];
+        }
+
+        let Some(dir) = path.parent() else {
+            return vec![];
+        };
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| {
+                        wildcard_match(&file_pattern, &entry.file_name().to_string_lossy())
+                    })
+                    .map(|entry| entry.path())
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
+}
+
+/// Split a config line into a lowercase keyword and its arguments (`Key value` or `Key=value`)
+fn split_directive(line: &str) -> Option<(String, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let split_at = line.find(|c: char| c.is_whitespace() || c == '=')?;
+    let (keyword, rest) = line.split_at(split_at);
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest).trim_start();
+
+    let mut args = Vec::new();
+    let mut chars = rest.chars().peekable();
+    while chars.peek().is_some() {
+        let mut arg = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            arg.extend(chars.by_ref().take_while(|&c| c != '"'));
+        } else {
+            arg.extend(chars.by_ref().take_while(|c| !c.is_whitespace()));
+        }
+        if !arg.is_empty() {
+            args.push(arg);
+        }
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+    }
+
+    Some((keyword.to_ascii_lowercase(), args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn resolve_with(config: &str, extra_files: &[(&str, &str)], host: &str) -> SshHostConfig {
+        let home = TempDir::new().unwrap();
+        let ssh_dir = home.path().join(".ssh");
+        fs::create_dir_all(ssh_dir.join("config.d")).unwrap();
+        fs::write(ssh_dir.join("config"), config).unwrap();
+        for (name, content) in extra_files {
+            fs::write(ssh_dir.join(name), content).unwrap();
+        }
+
+        let mut resolver = Resolver::new(host, home.path());
+        resolver.apply_file(&ssh_dir.join("config"), &ssh_dir, 0);
+        let mut resolved = resolver.finish();
+        // Make identity paths comparable across temp dirs
+        resolved.identity_files = resolved
+            .identity_files
+            .iter()
+            .map(|p| p.strip_prefix(home.path()).unwrap_or(p).to_path_buf())
+            .collect();
+        resolved
+    }
+
+    #[test]
+    fn test_alias_resolution_first_value_wins() {
+        let config = "\
+# Work account
+Host gh-work
+    HostName github.com
+    User git
+    IdentityFile ~/.ssh/id_work
+    IdentitiesOnly yes
+
+Host gh-* !gh-legacy
+    IdentityFile=%d/.ssh/id_%h
+    Port 2222
+
+Host *
+    User nobody
+    IdentityFile ~/.ssh/id_ed25519
+";
+        let resolved = resolve_with(config, &[], "gh-work");
+        assert_eq!(resolved.host_name.as_deref(), Some("github.com"));
+        assert_eq!(resolved.user.as_deref(), Some("git"));
+        assert_eq!(resolved.port, Some(2222));
+        assert!(resolved.identities_only);
+        assert_eq!(
+            resolved.identity_files,
+            vec![
+                PathBuf::from(".ssh/id_work"),
+                PathBuf::from(".ssh/id_github.com"),
+                PathBuf::from(".ssh/id_ed25519"),
+            ]
+        );
+
+        let legacy = resolve_with(config, &[], "gh-legacy");
+        assert_eq!(legacy.host_name, None);
+        assert_eq!(legacy.user.as_deref(), Some("nobody"));
+        assert_eq!(legacy.port, None);
+    }
+
+    #[test]
+    fn test_include_with_glob() {
+        let config = "Include config.d/*.conf\n\nHost *\n    User fallback\n";
+        let included = "Host gl\n    HostName gitlab.example.com\n    User git\n";
+        let resolved = resolve_with(config, &[("config.d/work.conf", included)], "gl");
+        assert_eq!(resolved.host_name.as_deref(), Some("gitlab.example.com"));
+        assert_eq!(resolved.user.as_deref(), Some("git"));
+    }
+
+    #[test]
+    fn test_parse_ssh_urls() {
+        let scp = parse_ssh_url("git@gh-work:org/repo.git").unwrap();
+        assert_eq!(scp.user.as_deref(), Some("git"));
+        assert_eq!(scp.host, "gh-work");
+        assert_eq!(scp.path, "org/repo.git");
+
+        let ssh = parse_ssh_url("ssh://git@example.com:2222/org/repo.git").unwrap();
+        assert_eq!(ssh.host, "example.com");
+        assert_eq!(ssh.port, Some(2222));
+        assert_eq!(ssh.path, "org/repo.git");
+
+        assert!(parse_ssh_url("https://github.com/org/repo.git").is_none());
+
+        assert_eq!(
+            ssh_port_for_url("ssh://git@example.com:2222/org/repo.git"),
+            2222
+        );
+        assert_eq!(ssh_port_for_url("git@example.com:org/repo.git"), 22);
+    }
+}
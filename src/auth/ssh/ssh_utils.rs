@@ -1,4 +1,4 @@
-use dialoguer::{Confirm, theme::ColorfulTheme};
+use base64::Engine;
 use git2::{Error, ErrorClass, ErrorCode};
 use log::debug;
 use std::path::{Path, PathBuf};
@@ -6,6 +6,7 @@ use std::process::{Command, Stdio};
 
 use crate::config::global::BGitGlobalConfig;
 use crate::constants::SSH_AGENT_SOCKET_BASENAME;
+use crate::prompt;
 
 /// Get the count of identities in SSH agent with socket
 /// ssh-add exit codes: 0=success, 1=command fails (includes no identities), 2=can't contact agent
@@ -79,20 +80,25 @@ pub fn add_key_interactive_with_auth(
 ) -> Result<bool, Error> {
     debug!("Trying interactive ssh-add for key: {key_name}");
 
+    // ssh-add reads the passphrase from the terminal, which a non-interactive run must not touch
+    if !prompt::is_interactive() && key_needs_passphrase(key_path) {
+        debug!("Skipping passphrase-protected key {key_name} in non-interactive mode");
+        return Ok(false);
+    }
+
     // Ask user if they want to add this key interactively
-    let should_add = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt(format!(
-            "Add SSH key '{key_name}' to ssh-agent? (you may be prompted for passphrase)"
-        ))
-        .default(true)
-        .interact()
-        .map_err(|e| {
-            Error::new(
-                ErrorCode::Auth,
-                ErrorClass::Net,
-                format!("Failed to get user confirmation: {e}"),
-            )
-        })?;
+    let should_add = prompt::confirm(
+        "auth.ssh_add_key",
+        &format!("Add SSH key '{key_name}' to ssh-agent? (you may be prompted for passphrase)"),
+        true,
+    )
+    .map_err(|e| {
+        Error::new(
+            ErrorCode::Auth,
+            ErrorClass::Net,
+            format!("Failed to get user confirmation: {e}"),
+        )
+    })?;
 
     if !should_add {
         debug!("User chose not to add key: {key_name}");
@@ -173,6 +179,64 @@ pub fn try_ssh_key_files_directly(username: &str) -> Result<git2::Cred, Error> {
     ))
 }
 
+/// Build credentials from a specific private key file (e.g. an ssh_config IdentityFile),
+/// asking for its passphrase when the key is encrypted
+pub fn ssh_key_from_file(username: &str, key_path: &Path) -> Result<git2::Cred, Error> {
+    debug!("Using SSH identity file {key_path:?} for user: {username}");
+
+    let public_key_path = PathBuf::from(format!("{}.pub", key_path.display()));
+    let public_key = public_key_path
+        .exists()
+        .then_some(public_key_path.as_path());
+
+    let passphrase = if key_needs_passphrase(key_path) {
+        let passphrase = prompt::password(
+            "auth.ssh_passphrase",
+            &format!("Enter passphrase for {}", key_path.display()),
+            true,
+        )
+        .map_err(|e| {
+            Error::new(
+                ErrorCode::Auth,
+                ErrorClass::Net,
+                format!("Failed to read passphrase: {e}"),
+            )
+        })?;
+        Some(passphrase)
+    } else {
+        None
+    };
+
+    git2::Cred::ssh_key(username, public_key, key_path, passphrase.as_deref())
+}
+
+/// Whether a private key file is passphrase protected (PEM or OpenSSH format)
+fn key_needs_passphrase(key_path: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(key_path) else {
+        return false;
+    };
+    if content.contains("ENCRYPTED") {
+        return true;
+    }
+
+    // openssh-key-v1 stores the cipher name right after the magic; "none" means unencrypted
+    let body: String = content
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .collect();
+    let Ok(decoded) = base64::engine::general_purpose::STANDARD.decode(body.trim()) else {
+        return false;
+    };
+    let Some(rest) = decoded.strip_prefix(b"openssh-key-v1\0") else {
+        return false;
+    };
+    let Some(len_bytes) = rest.get(..4) else {
+        return false;
+    };
+    let len = u32::from_be_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]) as usize;
+    rest.get(4..4 + len).is_some_and(|cipher| cipher != b"none")
+}
+
 /// Add all available SSH keys to the agent with socket
 pub fn add_all_ssh_keys_with_auth(
     cfg: &BGitGlobalConfig,
@@ -476,3 +540,31 @@ pub fn get_effective_ssh_auth() -> Option<String> {
     debug!("No SSH agent environment available");
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_key_needs_passphrase() {
+        if which::which("ssh-keygen").is_err() {
+            eprintln!("Skipping SSH key test: ssh-keygen not found");
+            return;
+        }
+
+        let dir = TempDir::new().unwrap();
+        for (name, passphrase) in [("plain", ""), ("protected", "hunter2")] {
+            let status = Command::new("ssh-keygen")
+                .args(["-q", "-t", "ed25519", "-N", passphrase, "-f"])
+                .arg(dir.path().join(name))
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+
+        assert!(!key_needs_passphrase(&dir.path().join("plain")));
+        assert!(key_needs_passphrase(&dir.path().join("protected")));
+        assert!(!key_needs_passphrase(&dir.path().join("missing")));
+    }
+}
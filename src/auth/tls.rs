@@ -0,0 +1,312 @@
+use git2::{CertificateCheckStatus, ErrorClass, ErrorCode, cert::Cert};
+use log::{debug, warn};
+use ring::digest;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config::global::{BGitGlobalConfig, TlsAuth};
+
+/// CA bundle currently registered with libgit2, so it is only applied once per path
+static APPLIED_CA_BUNDLE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Register the configured CA bundle with libgit2. Certificates from it are trusted in
+/// addition to the system store.
+pub(crate) fn apply_ca_bundle(global_config: &BGitGlobalConfig) {
+    let Some(path) = global_config.get_ca_bundle() else {
+        return;
+    };
+
+    let mut applied = APPLIED_CA_BUNDLE.lock().unwrap_or_else(|e| e.into_inner());
+    if applied.as_ref() == Some(&path) {
+        return;
+    }
+
+    if !path.exists() {
+        warn!(
+            "Configured CA bundle {} does not exist, falling back to the system trust store",
+            path.display()
+        );
+        return;
+    }
+
+    // SAFETY: these mutate libgit2 global options. bgit performs remote operations from a
+    // single thread and registers the bundle before opening any connection.
+    let result = unsafe {
+        if path.is_dir() {
+            git2::opts::set_ssl_cert_dir(path.as_path())
+        } else {
+            git2::opts::set_ssl_cert_file(path.as_path())
+        }
+    };
+
+    match result {
+        Ok(()) => {
+            debug!("Using additional CA bundle: {}", path.display());
+            *applied = Some(path);
+        }
+        Err(e) => warn!("Failed to load CA bundle {}: {}", path.display(), e),
+    }
+}
+
+/// Decide whether the certificate presented by `host` is acceptable.
+///
+/// Order of precedence:
+/// 1. `insecure_skip_verify` accepts anything, with a warning on every connection
+/// 2. Pinned fingerprints for the host must match, regardless of the chain of trust
+/// 3. Otherwise libgit2's own verification against the system store (and CA bundle) decides
+pub(crate) fn check_certificate(
+    cert: &Cert<'_>,
+    host: &str,
+    tls: &TlsAuth,
+) -> Result<CertificateCheckStatus, git2::Error> {
+    if tls.insecure_skip_verify {
+        warn!(
+            "INSECURE: TLS certificate verification is disabled (auth.tls.insecure_skip_verify), accepting certificate for {host}"
+        );
+        return Ok(CertificateCheckStatus::CertificateOk);
+    }
+
+    let Some(x509) = cert.as_x509() else {
+        return Ok(CertificateCheckStatus::CertificatePassthrough);
+    };
+
+    if let Some(pins) = pinned_fingerprints_for_host(tls, host) {
+        let fingerprint = sha256_fingerprint(x509.data());
+        let normalized = normalize_fingerprint(&fingerprint);
+        if pins
+            .iter()
+            .any(|pin| normalize_fingerprint(pin) == normalized)
+        {
+            debug!("Certificate for {host} matches pinned fingerprint {fingerprint}");
+            return Ok(CertificateCheckStatus::CertificateOk);
+        }
+
+        return Err(git2::Error::new(
+            ErrorCode::Certificate,
+            ErrorClass::Ssl,
+            format!(
+                "Certificate for {host} does not match any pinned fingerprint (presented SHA-256: {fingerprint})"
+            ),
+        ));
+    }
+
+    Ok(CertificateCheckStatus::CertificatePassthrough)
+}
+
+fn pinned_fingerprints_for_host<'t>(tls: &'t TlsAuth, host: &str) -> Option<&'t Vec<String>> {
+    tls.pinned_certs
+        .iter()
+        .find(|(pinned_host, pins)| pinned_host.eq_ignore_ascii_case(host) && !pins.is_empty())
+        .map(|(_, pins)| pins)
+}
+
+/// SHA-256 fingerprint of a DER encoded certificate as colon separated uppercase hex
+pub(crate) fn sha256_fingerprint(der: &[u8]) -> String {
+    digest::digest(&digest::SHA256, der)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn normalize_fingerprint(fingerprint: &str) -> String {
+    let fingerprint = fingerprint.trim();
+    let fingerprint = fingerprint
+        .strip_prefix("sha256:")
+        .or_else(|| fingerprint.strip_prefix("SHA256:"))
+        .unwrap_or(fingerprint);
+    fingerprint
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::git_auth::setup_auth_callbacks;
+    use git2::{Direction, Repository};
+    use std::net::{TcpListener, TcpStream};
+    use std::path::Path;
+    use std::process::{Child, Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// `openssl s_server` serving a fresh self-signed certificate for localhost
+    struct TestHttpsServer {
+        child: Child,
+        port: u16,
+        cert_path: PathBuf,
+        fingerprint: String,
+        _dir: TempDir,
+    }
+
+    impl Drop for TestHttpsServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    fn start_server() -> Option<TestHttpsServer> {
+        if which::which("openssl").is_err() {
+            eprintln!("Skipping TLS test: openssl not found");
+            return None;
+        }
+
+        let dir = TempDir::new().unwrap();
+        let cert_path = dir.path().join("cert.pem");
+        let key_path = dir.path().join("key.pem");
+        let generated = Command::new("openssl")
+            .args([
+                "req", "-x509", "-newkey", "rsa:2048", "-nodes", "-days", "1",
+            ])
+            .args([
+                "-subj",
+                "/CN=localhost",
+                "-addext",
+                "subjectAltName=DNS:localhost",
+            ])
+            .arg("-keyout")
+            .arg(&key_path)
+            .arg("-out")
+            .arg(&cert_path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .ok()?;
+        if !generated.success() {
+            eprintln!("Skipping TLS test: failed to generate a certificate");
+            return None;
+        }
+
+        let der = Command::new("openssl")
+            .args(["x509", "-outform", "DER", "-in"])
+            .arg(&cert_path)
+            .output()
+            .ok()?
+            .stdout;
+        let fingerprint = sha256_fingerprint(&der);
+
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let child = Command::new("openssl")
+            .args(["s_server", "-quiet", "-www", "-accept"])
+            .arg(format!("127.0.0.1:{port}"))
+            .arg("-cert")
+            .arg(&cert_path)
+            .arg("-key")
+            .arg(&key_path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let server = TestHttpsServer {
+            child,
+            port,
+            cert_path,
+            fingerprint,
+            _dir: dir,
+        };
+
+        for _ in 0..50 {
+            if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                return Some(server);
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        eprintln!("Skipping TLS test: openssl s_server did not start");
+        None
+    }
+
+    /// Connect to the server and report whether the TLS handshake was accepted.
+    /// `s_server -www` is not a git server, so an accepted handshake still fails later on.
+    fn handshake_accepted(server: &TestHttpsServer, global_config: &BGitGlobalConfig) -> bool {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let url = format!("https://localhost:{}/repo.git", server.port);
+        let mut remote = repo.remote_anonymous(&url).unwrap();
+
+        let result = remote.connect_auth(
+            Direction::Fetch,
+            Some(setup_auth_callbacks(global_config, &url)),
+            None,
+        );
+        match result {
+            Ok(_) => true,
+            Err(e) => e.code() != ErrorCode::Certificate && e.class() != ErrorClass::Ssl,
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_normalization() {
+        let fingerprint = sha256_fingerprint(b"certificate");
+        assert_eq!(fingerprint.len(), 32 * 3 - 1);
+        assert_eq!(
+            normalize_fingerprint(&fingerprint),
+            normalize_fingerprint(&format!(
+                "sha256:{}",
+                fingerprint.replace(':', "").to_lowercase()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_self_signed_rejected_by_default() {
+        let Some(server) = start_server() else {
+            return;
+        };
+        assert!(!handshake_accepted(&server, &BGitGlobalConfig::default()));
+    }
+
+    #[test]
+    fn test_pinned_fingerprint() {
+        let Some(server) = start_server() else {
+            return;
+        };
+
+        let mut config = BGitGlobalConfig::default();
+        config
+            .auth
+            .tls
+            .pinned_certs
+            .insert("localhost".to_string(), vec![server.fingerprint.clone()]);
+        assert!(handshake_accepted(&server, &config));
+
+        config.auth.tls.pinned_certs.insert(
+            "localhost".to_string(),
+            vec![sha256_fingerprint(b"some other certificate")],
+        );
+        assert!(!handshake_accepted(&server, &config));
+    }
+
+    #[test]
+    fn test_custom_ca_bundle() {
+        let Some(server) = start_server() else {
+            return;
+        };
+
+        let mut config = BGitGlobalConfig::default();
+        config.auth.tls.ca_bundle = Some(server.cert_path.clone());
+        assert!(Path::new(&server.cert_path).exists());
+        assert!(handshake_accepted(&server, &config));
+    }
+
+    #[test]
+    fn test_insecure_skip_verify() {
+        let Some(server) = start_server() else {
+            return;
+        };
+
+        let mut config = BGitGlobalConfig::default();
+        config.auth.tls.insecure_skip_verify = true;
+        assert!(handshake_accepted(&server, &config));
+    }
+}
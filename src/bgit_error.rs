@@ -1,7 +1,8 @@
 use colored::Colorize;
+use serde::Serialize;
 
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub(crate) struct BGitError {
     name: String,
     message: String,
@@ -12,7 +13,7 @@ pub(crate) struct BGitError {
 }
 
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub(crate) enum BGitErrorWorkflowType {
     Rules,
     AtomicEvent,
@@ -25,6 +26,24 @@ pub(crate) enum BGitErrorWorkflowType {
     Authentication,
 }
 
+impl BGitErrorWorkflowType {
+    /// Process exit code for errors of this type, see docs/USAGE.md. Stable, so scripts
+    /// and editors can rely on them.
+    pub(crate) fn exit_code(&self) -> i32 {
+        match self {
+            BGitErrorWorkflowType::Rules => 10,
+            BGitErrorWorkflowType::AtomicEvent => 11,
+            BGitErrorWorkflowType::RawExecutor => 12,
+            BGitErrorWorkflowType::HookExecutor => 13,
+            BGitErrorWorkflowType::Config => 14,
+            BGitErrorWorkflowType::WorkflowQueue => 15,
+            BGitErrorWorkflowType::ActionStep => 16,
+            BGitErrorWorkflowType::PromptStep => 17,
+            BGitErrorWorkflowType::Authentication => 18,
+        }
+    }
+}
+
 pub(crate) const EMPTY_STRING: &str = "";
 pub(crate) const NO_STEP: &str = EMPTY_STRING;
 pub(crate) const NO_EVENT: &str = EMPTY_STRING;
@@ -49,6 +68,10 @@ impl BGitError {
         }
     }
 
+    pub(crate) fn exit_code(&self) -> i32 {
+        self.workflow_type.exit_code()
+    }
+
     pub(crate) fn print_error(&self) {
         let mut context = format!("[{:?}", self.workflow_type);
         if self.step_name != NO_STEP {
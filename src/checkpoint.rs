@@ -0,0 +1,152 @@
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
+use crate::step::Task;
+use crate::util::bgit_state_dir;
+use crate::workflows::registry;
+
+const CHECKPOINT_FILE: &str = "checkpoint.json";
+
+/// Where a workflow failed, so `bgit --resume` can pick it up from the failing step
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Checkpoint {
+    pub workflow: String,
+    pub step: String,
+    /// What the step was handed by its predecessor, see `checkpoint_context`
+    pub context: Option<Value>,
+    /// Unix timestamp (seconds) the workflow failed at
+    pub saved_at: u64,
+}
+
+impl Checkpoint {
+    fn path() -> Option<PathBuf> {
+        bgit_state_dir().map(|dir| dir.join(CHECKPOINT_FILE))
+    }
+
+    /// Record that `workflow` failed while running `task`; best effort, as the workflow
+    /// is already failing
+    pub fn save(workflow: &str, task: &Task) -> Option<Self> {
+        let checkpoint = Checkpoint {
+            workflow: workflow.to_string(),
+            step: task.get_name().to_string(),
+            context: task.checkpoint_context(),
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        };
+        let path = Self::path()?;
+        let written = serde_json::to_string_pretty(&checkpoint)
+            .map_err(|e| e.to_string())
+            .and_then(|json| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                fs::write(&path, json).map_err(|e| e.to_string())
+            });
+        match written {
+            Ok(()) => {
+                debug!("Checkpoint saved to {}", path.display());
+                Some(checkpoint)
+            }
+            Err(e) => {
+                warn!("Failed to save checkpoint to {}: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    /// The checkpoint of the last failed workflow in this repository, if any
+    pub fn load() -> Result<Option<Self>, Box<BGitError>> {
+        let Some(path) = Self::path().filter(|path| path.exists()) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(&path)
+            .map_err(|e| to_checkpoint_error(&format!("Failed to read {}: {e}", path.display())))?;
+        serde_json::from_str(&content).map(Some).map_err(|e| {
+            to_checkpoint_error(&format!(
+                "Failed to parse {}: {e}. Delete it to start over.",
+                path.display()
+            ))
+        })
+    }
+
+    /// Forget the checkpoint once a workflow ran to completion
+    pub fn clear() {
+        if let Some(path) = Self::path().filter(|path| path.exists())
+            && let Err(e) = fs::remove_file(&path)
+        {
+            warn!("Failed to remove checkpoint {}: {e}", path.display());
+        }
+    }
+
+    /// Rebuild the step the workflow failed at, with the context it had
+    pub fn task(&self) -> Result<Task, Box<BGitError>> {
+        let mut task = registry::new_task(&self.step).ok_or_else(|| {
+            to_checkpoint_error(&format!(
+                "Checkpoint refers to unknown step '{}'",
+                self.step
+            ))
+        })?;
+        if let Some(context) = &self.context {
+            task.restore_context(context.clone()).map_err(|e| {
+                to_checkpoint_error(&format!(
+                    "Failed to restore the context of step '{}': {e}",
+                    self.step
+                ))
+            })?;
+        }
+        Ok(task)
+    }
+}
+
+fn to_checkpoint_error(message: &str) -> Box<BGitError> {
+    Box::new(BGitError::new(
+        "Checkpoint Error",
+        message,
+        BGitErrorWorkflowType::WorkflowQueue,
+        NO_STEP,
+        NO_EVENT,
+        NO_RULE,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::step::PromptStep;
+    use crate::workflows::check::prompt::pa04_ask_delete_stale_branches::AskDeleteStaleBranches;
+
+    #[test]
+    fn test_task_restores_step_context() {
+        let task = Task::PromptStepTask(Box::new(
+            AskDeleteStaleBranches::new().with_branches(vec![("old".to_string(), 120)]),
+        ));
+        let checkpoint = Checkpoint {
+            workflow: "check".to_string(),
+            step: task.get_name().to_string(),
+            context: task.checkpoint_context(),
+            saved_at: 0,
+        };
+        let json = serde_json::to_string(&checkpoint).unwrap();
+
+        let restored: Checkpoint = serde_json::from_str(&json).unwrap();
+        let restored_task = restored.task().unwrap();
+        assert_eq!(restored_task.get_name(), "ask_delete_stale_branches");
+        assert_eq!(
+            restored_task.checkpoint_context(),
+            Some(serde_json::json!({ "branches": [["old", 120]] }))
+        );
+
+        let unknown = Checkpoint {
+            step: "deploy".to_string(),
+            ..restored
+        };
+        assert!(unknown.task().is_err());
+    }
+}
@@ -2,9 +2,19 @@ pub(crate) mod check;
 pub(crate) mod default;
 pub(crate) mod init;
 pub(crate) mod log;
+pub(crate) mod resume;
+pub(crate) mod run;
+pub(crate) mod undo;
+pub(crate) mod workflow;
 
 use std::io;
+use std::path::PathBuf;
 
+use crate::cmd::log::LogArgs;
+use crate::cmd::run::RunArgs;
+use crate::cmd::undo::UndoArgs;
+use crate::cmd::workflow::WorkflowArgs;
+use crate::output::OutputFormat;
 use clap::{Command, CommandFactory, Parser, Subcommand};
 use clap_complete::{Generator, Shell, generate};
 use colored::Colorize;
@@ -23,18 +33,55 @@ pub struct Cli {
     /// Increase verbosity (-v, -vv, -vvv), 0 = WARN, 1 = INFO, 2 = DEBUG, 3 = TRACE
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub(crate) verbose: u8,
+
+    /// Never prompt: answer every question with its default, failing when there is none
+    #[arg(short = 'y', long, global = true)]
+    pub(crate) yes: bool,
+
+    /// Never prompt: read answers keyed by step name from a TOML or JSON file,
+    /// falling back to each question's default
+    #[arg(long, value_name = "FILE", global = true)]
+    pub(crate) answers: Option<PathBuf>,
+
+    /// Walk through the workflow without changing the repository, printing the git
+    /// command each step would have run
+    #[arg(long, global = true)]
+    pub(crate) dry_run: bool,
+
+    /// Print the JSON trace of the workflow run to stdout instead of saving it under
+    /// .git/bgit/traces
+    #[arg(long, global = true)]
+    pub(crate) trace: bool,
+
+    /// Continue the last failed workflow from the step it failed at
+    #[arg(long)]
+    pub(crate) resume: bool,
+
+    /// How to report the outcome: `json` prints a single JSON document on stdout and
+    /// moves progress output to stderr
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    pub(crate) output: OutputFormat,
 }
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// Print commit history
-    Log,
+    Log(LogArgs),
 
     /// Initialize bgit
     Init,
 
     /// Do maintenance tasks
     Check,
+
+    /// Run a custom workflow defined in .bgit/config.toml
+    Run(RunArgs),
+
+    /// Inspect workflows
+    Workflow(WorkflowArgs),
+
+    /// Undo the last changes bgit made to the repository
+    Undo(UndoArgs),
 }
 
 fn print_completions<G: Generator>(generator: G, cmd: &mut Command) {
@@ -47,6 +94,20 @@ fn print_completions<G: Generator>(generator: G, cmd: &mut Command) {
 }
 
 impl Cli {
+    /// Name of the command being run, as reported by `--output json`
+    pub(crate) fn command_name(&self) -> &'static str {
+        match &self.command {
+            Some(Commands::Log(_)) => "log",
+            Some(Commands::Init) => "init",
+            Some(Commands::Check) => "check",
+            Some(Commands::Run(_)) => "run",
+            Some(Commands::Workflow(_)) => "workflow",
+            Some(Commands::Undo(_)) => "undo",
+            None if self.resume => "resume",
+            None => "default",
+        }
+    }
+
     pub fn new() -> Option<Self> {
         let opt = Self::parse();
         if let Some(completions) = opt.completions {
@@ -1,5 +1,25 @@
-use crate::config::{global::BGitGlobalConfig, local::BGitConfig};
+use crate::bgit_error::BGitError;
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::BGitConfig;
+use crate::step::{ActionStep, Step, Task};
+use crate::workflow_queue::WorkflowQueue;
+use crate::workflows::check::action::ta01_check_repo_size::CheckRepoSize;
 
-pub(crate) fn check(_bgit_config: &BGitConfig, _global_config: &BGitGlobalConfig) {
-    unimplemented!("So, sorry! But `bgit check` hasn't been implemented yet!");
+pub(crate) fn check(
+    bgit_config: &BGitConfig,
+    global_config: &BGitGlobalConfig,
+) -> Result<(), Box<BGitError>> {
+    let check_workflow_rules_config = bgit_config.get_workflow_rules("check");
+    let check_workflow_config_flags = bgit_config.get_workflow_steps("check");
+
+    let workflow_queue = WorkflowQueue::new(
+        "check",
+        Step::Start(Task::ActionStepTask(Box::new(CheckRepoSize::new()))),
+    );
+    workflow_queue.execute(
+        check_workflow_config_flags,
+        check_workflow_rules_config,
+        global_config,
+    )?;
+    Ok(())
 }
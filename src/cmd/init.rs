@@ -1,5 +1,253 @@
+use std::fs;
+use std::path::Path;
+
+use colored::Colorize;
+
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
 use crate::config::{global::BGitGlobalConfig, local::BGitConfig};
+use crate::constants::{
+    DEFAULT_MAX_LARGE_FILE_SIZE_IN_BYTES, DEFAULT_MAX_LOOSE_OBJECTS, DEFAULT_STALE_BRANCH_DAYS,
+    DEFAULT_STALE_STASH_DAYS,
+};
+use crate::flags::config_flag;
+use crate::rules::a01_git_install::IsGitInstalledLocally;
+use crate::rules::a02_git_name_email_setup::GitNameEmailSetup;
+use crate::rules::a10_git_default_config::GitDefaultConfig;
+use crate::rules::a14_big_repo_size::IsRepoSizeTooBig;
+use crate::rules::{Rule, RuleLevel, all_rules};
+
+struct KnownStepFlag {
+    workflow: &'static str,
+    step: &'static str,
+    flag: &'static str,
+    /// Default value rendered as a TOML literal
+    default_value: String,
+    description: &'static str,
+}
+
+fn known_step_flags() -> Vec<KnownStepFlag> {
+    vec![
+        KnownStepFlag {
+            workflow: "default",
+            step: "is_sole_contributor",
+            flag: config_flag::workflows::default::is_sole_contributor::OVERRIDE_CHECK_FOR_AUTHORS,
+            default_value: "[]".to_string(),
+            description: "Author emails allowed to commit directly even when other contributors exist",
+        },
+        KnownStepFlag {
+            workflow: "check",
+            step: "check_loose_objects",
+            flag: config_flag::workflows::check::check_loose_objects::MAX_LOOSE_OBJECTS,
+            default_value: DEFAULT_MAX_LOOSE_OBJECTS.to_string(),
+            description: "Loose object count above which garbage collection is suggested",
+        },
+        KnownStepFlag {
+            workflow: "check",
+            step: "check_stale_branches",
+            flag: config_flag::workflows::check::check_stale_branches::STALE_AFTER_DAYS,
+            default_value: DEFAULT_STALE_BRANCH_DAYS.to_string(),
+            description: "Days without commits after which an unmerged branch is considered stale",
+        },
+        KnownStepFlag {
+            workflow: "check",
+            step: "check_stashes",
+            flag: config_flag::workflows::check::check_stashes::STALE_AFTER_DAYS,
+            default_value: DEFAULT_STALE_STASH_DAYS.to_string(),
+            description: "Days after which a stash is considered forgotten",
+        },
+        KnownStepFlag {
+            workflow: "check",
+            step: "check_large_blobs",
+            flag: config_flag::workflows::check::check_large_blobs::MAX_BLOB_SIZE_BYTES,
+            default_value: DEFAULT_MAX_LARGE_FILE_SIZE_IN_BYTES.to_string(),
+            description: "Blob size in bytes above which a file in history is reported",
+        },
+    ]
+}
+
+const PRE_GIT_COMMIT_SAMPLE: &str = r#"#!/usr/bin/env bash
+# Runs before bgit creates a commit. A non-zero exit code aborts the commit.
+#
+# Examples:
+# cargo fmt --all -- --check
+# npm run lint
+"#;
+
+const POST_GIT_COMMIT_SAMPLE: &str = r#"#!/usr/bin/env bash
+# Runs after bgit has created a commit.
+#
+# Examples:
+# git log -1 --stat
+# echo "Committed $(git rev-parse --short HEAD)"
+"#;
+
+pub(crate) fn init(
+    bgit_config: &BGitConfig,
+    global_config: &BGitGlobalConfig,
+) -> Result<(), Box<BGitError>> {
+    scaffold_bgit_dir()?;
+
+    println!("\nChecking your environment...");
+    let workflow_rules_config = bgit_config.get_workflow_rules("default");
+    let environment_rules: Vec<Box<dyn Rule + Send + Sync>> = vec![
+        Box::new(IsGitInstalledLocally::new(workflow_rules_config)),
+        Box::new(GitNameEmailSetup::new(workflow_rules_config)),
+        Box::new(GitDefaultConfig::new(workflow_rules_config)),
+    ];
+
+    // Every rule runs so all problems show up in one go. The first failure is returned,
+    // and printed by the caller, so `bgit init` still exits with its code.
+    let mut first_failure = None;
+    for rule in environment_rules {
+        match rule.execute() {
+            Ok(_) => println!("{} {}", "✓".green(), rule.get_description()),
+            Err(err) if first_failure.is_none() => first_failure = Some(err),
+            Err(err) => err.print_error(),
+        }
+    }
+    match global_config.migrate_plaintext_secrets() {
+        Ok(_) => {}
+        Err(err) if first_failure.is_none() => first_failure = Some(err),
+        Err(err) => err.print_error(),
+    }
+
+    match first_failure {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+fn scaffold_bgit_dir() -> Result<(), Box<BGitError>> {
+    let config_path = BGitConfig::find_config_path()?;
+    let bgit_dir = config_path
+        .parent()
+        .ok_or_else(|| to_init_error("Could not determine the .bgit directory"))?;
+    let hooks_dir = bgit_dir.join("hooks");
+
+    fs::create_dir_all(&hooks_dir)
+        .map_err(|e| to_init_error(&format!("Failed to create {}: {e}", hooks_dir.display())))?;
+
+    write_if_missing(&config_path, &render_config_template())?;
+    write_if_missing(
+        &hooks_dir.join("pre_git_commit.sample"),
+        PRE_GIT_COMMIT_SAMPLE,
+    )?;
+    write_if_missing(
+        &hooks_dir.join("post_git_commit.sample"),
+        POST_GIT_COMMIT_SAMPLE,
+    )?;
+
+    Ok(())
+}
+
+fn write_if_missing(path: &Path, contents: &str) -> Result<(), Box<BGitError>> {
+    if path.exists() {
+        println!("{} {} (already exists)", "Skipped".yellow(), path.display());
+        return Ok(());
+    }
+
+    fs::write(path, contents)
+        .map_err(|e| to_init_error(&format!("Failed to write {}: {e}", path.display())))?;
+    println!("{} {}", "Created".green(), path.display());
+    Ok(())
+}
+
+/// Render `.bgit/config.toml` listing every rule and step flag at its default value
+fn render_config_template() -> String {
+    let mut template = String::from(
+        "# bgit project configuration, see docs/config/REPO.md\n\
+         # Rule levels: \"Skip\", \"Warning\" or \"Error\"\n",
+    );
+
+    template.push_str("\n[rules.default]\n");
+    for rule in all_rules(None) {
+        push_rule(&mut template, rule.as_ref());
+    }
+    template.push_str(
+        "\n# Team baseline checked by GitDefaultConfig, \"\" stops checking a key\n\
+         # [rules.default.params.GitDefaultConfig]\n\
+         # \"init.defaultBranch\" = \"main\"\n\
+         # \"pull.rebase\" = true\n\
+         # Remote scheme required by GitRemoteHttpSsh, \"ssh\" or \"https\"\n\
+         # [rules.default.params.GitRemoteHttpSsh]\n\
+         # require = \"ssh\"\n",
+    );
+
+    // `bgit check` only runs rules guarding the events it triggers
+    template.push_str("\n[rules.check]\n");
+    push_rule(&mut template, &IsGitInstalledLocally::new(None));
+    push_rule(&mut template, &IsRepoSizeTooBig::new(None));
+
+    for known in known_step_flags() {
+        template.push_str(&format!(
+            "\n[workflow.{}.{}]\n# {}\n{} = {}\n",
+            known.workflow, known.step, known.description, known.flag, known.default_value
+        ));
+    }
+
+    template.push_str(
+        "\n# Custom workflows run with `bgit run <name>`, e.g.\n\
+         # [custom_workflows.hotfix]\n\
+         # description = \"Commit and push on the current branch\"\n\
+         # start = \"ask_add_mode\"\n\
+         # [custom_workflows.hotfix.steps.has_uncommitted]\n\
+         # on.is_branch_main = \"ask_human_commit_message\"\n",
+    );
+
+    template
+}
+
+fn push_rule(template: &mut String, rule: &dyn Rule) {
+    let level = match rule.get_level() {
+        RuleLevel::Skip => "Skip",
+        RuleLevel::Warning => "Warning",
+        RuleLevel::Error => "Error",
+    };
+    template.push_str(&format!(
+        "# {}\n{} = \"{}\"\n",
+        rule.get_description(),
+        rule.get_name(),
+        level
+    ));
+}
+
+fn to_init_error(message: &str) -> Box<BGitError> {
+    Box::new(BGitError::new(
+        "Failed to initialize bgit",
+        message,
+        BGitErrorWorkflowType::Config,
+        NO_STEP,
+        NO_EVENT,
+        NO_RULE,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_template_parses_with_default_levels() {
+        let config: BGitConfig = toml::from_str(&render_config_template()).unwrap();
+
+        let default_rules = config.get_workflow_rules("default").unwrap();
+        for rule in all_rules(None) {
+            assert_eq!(
+                default_rules.get_rule_level(rule.get_name()),
+                Some(&rule.get_level()),
+                "rule {} missing from template",
+                rule.get_name()
+            );
+        }
 
-pub(crate) fn init(_bgit_config: &BGitConfig, _global_config: &BGitGlobalConfig) {
-    unimplemented!("So, sorry! But `bgit init` hasn't been implemented yet!");
+        let stale_days = config
+            .get_workflow_steps("check")
+            .and_then(|steps| steps.get_step_flags("check_stale_branches"))
+            .and_then(|flags| {
+                flags.get_flag::<u64>(
+                    config_flag::workflows::check::check_stale_branches::STALE_AFTER_DAYS,
+                )
+            });
+        assert_eq!(stale_days, Some(DEFAULT_STALE_BRANCH_DAYS));
+    }
 }
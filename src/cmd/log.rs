@@ -1,5 +1,227 @@
+use std::io::IsTerminal;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::Args;
+use colored::Colorize;
+use dialoguer::{Select, theme::ColorfulTheme};
+
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
 use crate::config::{global::BGitGlobalConfig, local::BGitConfig};
+use crate::events::git_log::{CommitSummary, GitLog, LogFilter};
+use crate::prompt;
+use crate::util::days_from_civil;
+
+#[derive(Debug, Args)]
+pub struct LogArgs {
+    /// Only show commits whose author name or email contains this text
+    #[arg(long)]
+    author: Option<String>,
+
+    /// Only show commits on or after this date (YYYY-MM-DD, or relative like 7d / 2w)
+    #[arg(long, value_parser = parse_since)]
+    since: Option<i64>,
+
+    /// Only show commits on or before this date (YYYY-MM-DD, or relative like 7d / 2w)
+    #[arg(long, value_parser = parse_until)]
+    until: Option<i64>,
+
+    /// Only show commits touching this file or directory
+    #[arg(long)]
+    path: Option<String>,
+
+    /// Only show commits of this Conventional Commit type (feat, fix, docs, ...)
+    #[arg(long = "type", value_name = "TYPE")]
+    commit_type: Option<String>,
+
+    /// Maximum number of commits to load
+    #[arg(short = 'n', long)]
+    limit: Option<usize>,
+
+    /// Number of commits shown per page
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u16).range(1..))]
+    page_size: u16,
+}
+
+enum PageAction {
+    ShowCommit(usize),
+    NextPage,
+    PreviousPage,
+    Quit,
+}
+
+pub(crate) fn log(
+    _bgit_config: &BGitConfig,
+    global_config: &BGitGlobalConfig,
+    args: &LogArgs,
+) -> Result<(), Box<BGitError>> {
+    let filter = LogFilter {
+        author: args.author.clone(),
+        since: args.since,
+        until: args.until,
+        path: args.path.clone(),
+        commit_type: args.commit_type.clone(),
+        limit: args.limit,
+    };
+    let git_log = GitLog::list_commits(global_config, filter);
+    let commits = git_log.get_commits()?;
+
+    if commits.is_empty() {
+        println!("{}", "No commits match the given filters.".yellow());
+        return Ok(());
+    }
+
+    // Nothing to page through when piped or non-interactive, print the whole list instead
+    if !std::io::stdout().is_terminal()
+        || !std::io::stdin().is_terminal()
+        || !prompt::is_interactive()
+    {
+        for commit in &commits {
+            println!("{}", format_commit_line(commit));
+        }
+        return Ok(());
+    }
+
+    let page_size = args.page_size as usize;
+    let total_pages = commits.len().div_ceil(page_size);
+    let mut page = 0;
+
+    loop {
+        let start = page * page_size;
+        let end = (start + page_size).min(commits.len());
+        let page_commits = &commits[start..end];
+
+        let mut items: Vec<String> = page_commits.iter().map(format_commit_line).collect();
+        let mut actions: Vec<PageAction> = (start..end).map(PageAction::ShowCommit).collect();
+        if page + 1 < total_pages {
+            items.push("Next page ->".bright_blue().to_string());
+            actions.push(PageAction::NextPage);
+        }
+        if page > 0 {
+            items.push("<- Previous page".bright_blue().to_string());
+            actions.push(PageAction::PreviousPage);
+        }
+        items.push("Quit".bright_blue().to_string());
+        actions.push(PageAction::Quit);
+
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "Commits {}-{} of {} (page {}/{}) - select one to view its diff",
+                start + 1,
+                end,
+                commits.len(),
+                page + 1,
+                total_pages
+            ))
+            .items(&items)
+            .default(0)
+            .max_length(page_size + 3)
+            .interact_opt()
+            .map_err(|e| {
+                Box::new(BGitError::new(
+                    "Input Error",
+                    &e.to_string(),
+                    BGitErrorWorkflowType::PromptStep,
+                    NO_STEP,
+                    NO_EVENT,
+                    NO_RULE,
+                ))
+            })?;
+
+        match selection.map(|index| &actions[index]) {
+            Some(PageAction::ShowCommit(index)) => {
+                let commit = &commits[*index];
+                print_commit_details(commit);
+                print_colored_diff(&git_log.get_commit_diff(commit.id)?);
+            }
+            Some(PageAction::NextPage) => page += 1,
+            Some(PageAction::PreviousPage) => page -= 1,
+            Some(PageAction::Quit) | None => return Ok(()),
+        }
+    }
+}
+
+fn format_commit_line(commit: &CommitSummary) -> String {
+    format!(
+        "{} {} {} {}",
+        commit.short_id().yellow(),
+        commit.formatted_date().green(),
+        commit.author_name.cyan(),
+        commit.summary
+    )
+}
+
+fn print_commit_details(commit: &CommitSummary) {
+    println!("\n{} {}", "commit".yellow(), commit.id.to_string().yellow());
+    println!("Author: {} <{}>", commit.author_name, commit.author_email);
+    println!("Date:   {}", commit.formatted_date());
+    println!("\n    {}\n", commit.summary);
+}
+
+fn print_colored_diff(patch: &str) {
+    for line in patch.lines() {
+        if line.starts_with("diff --git") || line.starts_with("+++") || line.starts_with("---") {
+            println!("{}", line.bold());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else {
+            println!("{line}");
+        }
+    }
+    println!();
+}
+
+/// Parse `YYYY-MM-DD` (UTC midnight) or a relative `<n>d` / `<n>w` into a unix timestamp
+fn parse_date(value: &str) -> Result<i64, String> {
+    let value = value.trim();
+    if let Some(count) = value
+        .strip_suffix('d')
+        .or_else(|| value.strip_suffix('w'))
+        .and_then(|n| n.parse::<i64>().ok())
+    {
+        let days = if value.ends_with('w') {
+            count * 7
+        } else {
+            count
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_secs() as i64;
+        return Ok(now - days * 86400);
+    }
+
+    let parts: Vec<&str> = value.split('-').collect();
+    if let [year, month, day] = parts.as_slice()
+        && let (Ok(year), Ok(month), Ok(day)) = (
+            year.parse::<i64>(),
+            month.parse::<u32>(),
+            day.parse::<u32>(),
+        )
+        && (1..=12).contains(&month)
+        && (1..=31).contains(&day)
+    {
+        return Ok(days_from_civil(year, month, day) * 86400);
+    }
+
+    Err(format!(
+        "invalid date '{value}', expected YYYY-MM-DD or a relative value like 7d / 2w"
+    ))
+}
+
+fn parse_since(value: &str) -> Result<i64, String> {
+    parse_date(value)
+}
 
-pub(crate) fn log(_bgit_config: &BGitConfig, _global_config: &BGitGlobalConfig) {
-    unimplemented!("So, sorry! But `bgit log` hasn't been implemented yet!");
+fn parse_until(value: &str) -> Result<i64, String> {
+    // A plain date should include the whole day
+    let timestamp = parse_date(value)?;
+    if value.contains('-') {
+        Ok(timestamp + 86399)
+    } else {
+        Ok(timestamp)
+    }
 }
@@ -0,0 +1,58 @@
+use colored::Colorize;
+
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
+use crate::checkpoint::Checkpoint;
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::BGitConfig;
+use crate::step::Step;
+use crate::workflow_queue::WorkflowQueue;
+use crate::workflows::custom::WorkflowGraph;
+use crate::workflows::registry;
+
+pub(crate) fn resume(
+    bgit_config: &BGitConfig,
+    global_config: &BGitGlobalConfig,
+) -> Result<(), Box<BGitError>> {
+    let Some(checkpoint) = Checkpoint::load()? else {
+        println!(
+            "{}",
+            "Nothing to resume, the last workflow did not fail here".yellow()
+        );
+        return Ok(());
+    };
+    let name = checkpoint.workflow.as_str();
+    let task = checkpoint.task()?;
+
+    let workflow_queue = if registry::BUILT_IN_WORKFLOWS
+        .iter()
+        .any(|(workflow, _)| *workflow == name)
+    {
+        WorkflowQueue::new(name, Step::Start(task))
+    } else {
+        let workflow = bgit_config.get_custom_workflow(name).ok_or_else(|| {
+            Box::new(BGitError::new(
+                "Unknown workflow",
+                &format!(
+                    "The checkpoint belongs to workflow '{name}', which is no longer in [custom_workflows]"
+                ),
+                BGitErrorWorkflowType::Config,
+                NO_STEP,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
+        WorkflowQueue::from_graph(WorkflowGraph::build(name, workflow)?).resume_at(task)
+    };
+
+    eprintln!(
+        "Resuming workflow {} at step {}",
+        name.cyan().bold(),
+        checkpoint.step.cyan().bold()
+    );
+    workflow_queue.execute(
+        bgit_config.get_workflow_steps(name),
+        bgit_config.get_workflow_rules(name),
+        global_config,
+    )?;
+    Ok(())
+}
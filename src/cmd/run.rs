@@ -0,0 +1,85 @@
+use clap::Args;
+use colored::Colorize;
+
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::BGitConfig;
+use crate::workflow_queue::WorkflowQueue;
+use crate::workflows::custom::WorkflowGraph;
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    /// Name of a workflow defined under [custom_workflows] in .bgit/config.toml;
+    /// lists the available workflows when omitted
+    workflow: Option<String>,
+}
+
+pub(crate) fn run(
+    bgit_config: &BGitConfig,
+    global_config: &BGitGlobalConfig,
+    args: &RunArgs,
+) -> Result<(), Box<BGitError>> {
+    let Some(name) = &args.workflow else {
+        print_workflows(bgit_config);
+        return Ok(());
+    };
+
+    let workflow = bgit_config.get_custom_workflow(name).ok_or_else(|| {
+        let mut names = workflow_names(bgit_config);
+        if names.is_empty() {
+            names.push("none defined".to_string());
+        }
+        Box::new(BGitError::new(
+            "Unknown workflow",
+            &format!(
+                "No workflow named '{name}' in [custom_workflows] (available: {})",
+                names.join(", ")
+            ),
+            BGitErrorWorkflowType::Config,
+            NO_STEP,
+            NO_EVENT,
+            NO_RULE,
+        ))
+    })?;
+    let graph = WorkflowGraph::build(name, workflow)?;
+
+    eprintln!("Running workflow {}", graph.name().cyan().bold());
+    let workflow_queue = WorkflowQueue::from_graph(graph);
+    workflow_queue.execute(
+        bgit_config.get_workflow_steps(name),
+        bgit_config.get_workflow_rules(name),
+        global_config,
+    )?;
+    Ok(())
+}
+
+fn workflow_names(bgit_config: &BGitConfig) -> Vec<String> {
+    let mut names: Vec<String> = bgit_config
+        .custom_workflows
+        .workflows
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+fn print_workflows(bgit_config: &BGitConfig) {
+    let names = workflow_names(bgit_config);
+    if names.is_empty() {
+        println!(
+            "{}",
+            "No custom workflows defined. Add one under [custom_workflows.<name>] in .bgit/config.toml"
+                .yellow()
+        );
+        return;
+    }
+
+    for name in names {
+        let description = bgit_config
+            .get_custom_workflow(&name)
+            .and_then(|workflow| workflow.description.as_deref())
+            .unwrap_or("");
+        println!("{} {}", name.cyan().bold(), description);
+    }
+}
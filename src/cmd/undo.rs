@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use clap::Args;
+use colored::Colorize;
+use git2::Repository;
+
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
+use crate::events::is_dry_run;
+use crate::oplog;
+use crate::prompt;
+
+#[derive(Debug, Args)]
+pub struct UndoArgs {
+    /// Number of operations to undo, newest first
+    #[arg(default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    count: u16,
+
+    /// List the operations that can be undone instead
+    #[arg(long)]
+    list: bool,
+}
+
+pub(crate) fn undo(args: &UndoArgs) -> Result<(), Box<BGitError>> {
+    let mut operations = oplog::load().map_err(|e| to_undo_error(&e))?;
+    if operations.is_empty() {
+        println!(
+            "{}",
+            "Nothing to undo, bgit has not changed this repository yet".yellow()
+        );
+        return Ok(());
+    }
+
+    if args.list {
+        for (position, operation) in operations.iter().rev().enumerate() {
+            println!(
+                "{:>3}  {}  {}",
+                position + 1,
+                operation.event.cyan(),
+                operation.describe()
+            );
+        }
+        return Ok(());
+    }
+
+    let count = (args.count as usize).min(operations.len());
+    let to_undo = operations.split_off(operations.len() - count);
+    eprintln!("Undoing, newest first:");
+    for operation in to_undo.iter().rev() {
+        eprintln!("  - {}", operation.describe());
+    }
+    if is_dry_run() {
+        eprintln!("{}", "Dry run: nothing was undone".yellow());
+        return Ok(());
+    }
+    let confirmed = prompt::confirm("undo", &format!("Undo {count} operation(s)?"), true)
+        .map_err(|e| to_undo_error(&e.to_string()))?;
+    if !confirmed {
+        return Ok(());
+    }
+
+    let mut repo = Repository::discover(Path::new("."))
+        .map_err(|e| to_undo_error(&format!("Failed to open repository: {e}")))?;
+    let mut undone = 0;
+    let mut failure = None;
+    for operation in to_undo.iter().rev() {
+        match operation.undo(&mut repo) {
+            Ok(()) => undone += 1,
+            Err(e) => {
+                failure = Some(format!("Cannot undo `{}`: {e}", operation.describe()));
+                break;
+            }
+        }
+    }
+
+    // Keep whatever could not be undone in the log
+    operations.extend(to_undo.into_iter().take(count - undone));
+    oplog::save(&operations).map_err(|e| to_undo_error(&e))?;
+
+    match failure {
+        Some(message) if undone == 0 => Err(to_undo_error(&message)),
+        Some(message) => Err(to_undo_error(&format!(
+            "{message}. Undid {undone} of {count} operation(s)."
+        ))),
+        None => {
+            println!("{}", format!("Undid {undone} operation(s)").green());
+            Ok(())
+        }
+    }
+}
+
+fn to_undo_error(message: &str) -> Box<BGitError> {
+    Box::new(BGitError::new(
+        "Undo failed",
+        message,
+        BGitErrorWorkflowType::RawExecutor,
+        NO_STEP,
+        NO_EVENT,
+        NO_RULE,
+    ))
+}
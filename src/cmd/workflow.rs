@@ -0,0 +1,73 @@
+use clap::{Args, Subcommand, ValueEnum};
+
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
+use crate::config::local::BGitConfig;
+use crate::workflows::custom::WorkflowGraph;
+use crate::workflows::diagram::StepDiagram;
+
+#[derive(Debug, Args)]
+pub struct WorkflowArgs {
+    #[command(subcommand)]
+    command: WorkflowCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum WorkflowCommand {
+    /// Print the step graph of a workflow, as bgit walks it
+    Graph(GraphArgs),
+}
+
+#[derive(Debug, Args)]
+struct GraphArgs {
+    /// `default`, `check`, or a workflow defined under [custom_workflows]
+    #[arg(default_value = "default")]
+    workflow: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = GraphFormat::Mermaid)]
+    format: GraphFormat,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GraphFormat {
+    /// Graphviz DOT, render with `dot -Tsvg`
+    Dot,
+    /// Mermaid flowchart, rendered by GitHub in Markdown
+    Mermaid,
+}
+
+pub(crate) fn workflow(
+    bgit_config: &BGitConfig,
+    args: &WorkflowArgs,
+) -> Result<(), Box<BGitError>> {
+    match &args.command {
+        WorkflowCommand::Graph(graph_args) => print_graph(bgit_config, graph_args),
+    }
+}
+
+fn print_graph(bgit_config: &BGitConfig, args: &GraphArgs) -> Result<(), Box<BGitError>> {
+    let diagram = match StepDiagram::built_in(&args.workflow) {
+        Some(diagram) => diagram,
+        None => {
+            let workflow = bgit_config
+                .get_custom_workflow(&args.workflow)
+                .ok_or_else(|| {
+                    Box::new(BGitError::new(
+                        "Unknown workflow",
+                        &format!("No built-in or custom workflow named '{}'", args.workflow),
+                        BGitErrorWorkflowType::Config,
+                        NO_STEP,
+                        NO_EVENT,
+                        NO_RULE,
+                    ))
+                })?;
+            StepDiagram::custom(&WorkflowGraph::build(&args.workflow, workflow)?)
+        }
+    };
+
+    match args.format {
+        GraphFormat::Dot => print!("{}", diagram.to_dot()),
+        GraphFormat::Mermaid => print!("{}", diagram.to_mermaid()),
+    }
+    Ok(())
+}
@@ -1,11 +1,17 @@
 #![allow(unused)]
 use crate::bgit_error::BGitError;
+use crate::config::vault;
 use base64::Engine;
-use log::debug;
+use log::{debug, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+/// Vault secret names used when a plain text secret is saved for the first time
+const HTTPS_PAT_SECRET: &str = "auth.https.pat";
+const GOOGLE_API_KEY_SECRET: &str = "integrations.google_api_key";
+
 /// Global, per-user configuration stored under the user's config directory
 /// (e.g. Linux/macOS: ~/.config/bgit/config.toml, Windows: %APPDATA%/bgit/config.toml).
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
@@ -40,6 +46,15 @@ pub struct GlobalAuth {
     /// SSH settings (optional)
     #[serde(default)]
     pub ssh: SshAuth,
+    /// TLS certificate verification settings for HTTPS remotes (optional)
+    #[serde(default)]
+    pub tls: TlsAuth,
+    /// HTTPS accounts for specific hosts or owners, preferred over `https` when they match
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub https_accounts: Vec<HttpsAccount>,
+    /// Self-hosted Git servers whose URLs may be rewritten between SSH and HTTPS
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<GitHost>,
 }
 
 impl Default for GlobalAuth {
@@ -48,35 +63,103 @@ impl Default for GlobalAuth {
             preferred: PreferredAuth::RepositoryURLBased,
             https: HttpsAuth::default(),
             ssh: SshAuth::default(),
+            tls: TlsAuth::default(),
+            https_accounts: Vec::new(),
+            hosts: Vec::new(),
         }
     }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct GlobalIntegrations {
-    /// Optional Google API key stored as base64 in config and decoded on load.
-    /// TOML path: [integrations] google_api_key = "...base64..."
-    #[serde(
-        default,
-        deserialize_with = "deserialize_b64_opt",
-        serialize_with = "serialize_b64_opt",
-        skip_serializing_if = "Option::is_none"
-    )]
+    /// Google API key in plain text. Only read from legacy base64 configs or set at runtime,
+    /// it is moved into the secret vault on save and never written back.
+    #[serde(default, deserialize_with = "deserialize_b64_opt", skip_serializing)]
     pub google_api_key: Option<String>,
+    /// Name of the vault secret holding the Google API key.
+    /// TOML path: [integrations] google_api_key_secret = "integrations.google_api_key"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub google_api_key_secret: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct HttpsAuth {
     /// Username for HTTPS auth
     pub username: Option<String>,
-    /// Personal Access Token (base64-encoded in config, decoded on load)
-    #[serde(
-        default,
-        deserialize_with = "deserialize_b64_opt",
-        serialize_with = "serialize_b64_opt",
-        skip_serializing_if = "Option::is_none"
-    )]
+    /// Personal Access Token in plain text. Only read from legacy base64 configs or set at
+    /// runtime, it is moved into the secret vault on save and never written back.
+    #[serde(default, deserialize_with = "deserialize_b64_opt", skip_serializing)]
     pub pat: Option<String>,
+    /// Name of the vault secret holding the Personal Access Token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pat_secret: Option<String>,
+}
+
+/// A Git server (e.g. self-hosted GitLab, Gitea or Forgejo) bgit may rewrite URLs for.
+/// TOML path: [[auth.hosts]] host = "git.example.com"
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct GitHost {
+    /// Host name as it appears in remote URLs, with `:port` when HTTPS is not on 443
+    pub host: String,
+    /// User for SSH URLs (default: "git")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_user: Option<String>,
+    /// SSH port when the server does not listen on 22
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_port: Option<u16>,
+    /// Path prefix of repositories over HTTPS, e.g. "/gitlab" for https://host/gitlab/group/repo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub https_base_path: Option<String>,
+    /// Overrides `auth.preferred` for remotes on this host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferred: Option<PreferredAuth>,
+}
+
+/// HTTPS credentials used for remotes matching `host`.
+/// TOML path: [[auth.https_accounts]] host = "github.com/our-org/*"
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct HttpsAccount {
+    /// Host, optionally followed by a path prefix, e.g. "gitlab.example.com" or "github.com/our-org/*"
+    pub host: String,
+    pub username: Option<String>,
+    /// Personal Access Token in plain text, moved into the secret vault on save
+    #[serde(default, deserialize_with = "deserialize_b64_opt", skip_serializing)]
+    pub pat: Option<String>,
+    /// Name of the vault secret holding the Personal Access Token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pat_secret: Option<String>,
+}
+
+impl HttpsAccount {
+    /// How specifically this account matches `host` and `path`: `None` if it does not match,
+    /// otherwise the length of the matched path prefix (0 for a host-only entry)
+    fn match_score(&self, host: &str, path: &str) -> Option<usize> {
+        let pattern = self.pattern();
+        let (pattern_host, prefix) = pattern.split_once('/').unwrap_or((pattern, ""));
+        if !pattern_host.eq_ignore_ascii_case(host) {
+            return None;
+        }
+        if prefix.is_empty() {
+            return Some(0);
+        }
+
+        let path = path.trim_start_matches('/');
+        // Match whole path segments so "our-org" does not cover "our-org-archive"
+        let matches = path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+        matches.then_some(prefix.len())
+    }
+
+    /// Vault secret name for this account's token
+    fn default_secret_name(&self) -> String {
+        format!("{HTTPS_PAT_SECRET}.{}", self.pattern())
+    }
+
+    /// `host` without surrounding whitespace and a trailing `/*`
+    fn pattern(&self) -> &str {
+        self.host.trim().trim_end_matches('*').trim_end_matches('/')
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
@@ -85,6 +168,19 @@ pub struct SshAuth {
     pub key_file: Option<std::path::PathBuf>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct TlsAuth {
+    /// PEM CA bundle file (or directory of hashed certs) trusted in addition to the system store
+    pub ca_bundle: Option<PathBuf>,
+    /// Per-host SHA-256 certificate fingerprints (hex, colons optional).
+    /// A matching fingerprint is accepted even if the chain is not trusted.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub pinned_certs: HashMap<String, Vec<String>>,
+    /// Disable certificate verification for every host. INSECURE, only for debugging.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure_skip_verify: bool,
+}
+
 // Custom deserializer to decode optional base64 strings (generic messages)
 fn deserialize_b64_opt<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
@@ -110,20 +206,6 @@ where
     }
 }
 
-// Custom serializer to encode optional strings as base64 when saving
-fn serialize_b64_opt<S>(val: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: serde::Serializer,
-{
-    match val {
-        Some(s) if !s.is_empty() => {
-            let enc = base64::engine::general_purpose::STANDARD.encode(s.as_bytes());
-            serializer.serialize_str(&enc)
-        }
-        _ => serializer.serialize_none(),
-    }
-}
-
 impl BGitGlobalConfig {
     /// Load global per-user config from the platform's config directory.
     /// If file is missing or invalid, returns defaults.
@@ -150,7 +232,7 @@ impl BGitGlobalConfig {
             ))
         })?;
 
-        let config: BGitGlobalConfig = toml::from_str(&config_content).map_err(|e| {
+        let mut config: BGitGlobalConfig = toml::from_str(&config_content).map_err(|e| {
             Box::new(BGitError::new(
                 "Failed to parse global config file",
                 &format!("Invalid TOML in {}: {}", path.display(), e),
@@ -166,6 +248,14 @@ impl BGitGlobalConfig {
             config.auth.preferred
         );
 
+        // Moving them needs the vault, which may prompt, so only `bgit init` does it
+        if config.has_plaintext_secrets() {
+            warn!(
+                "{} holds base64-encoded secrets, run `bgit init` to move them into the encrypted vault",
+                path.display()
+            );
+        }
+
         Ok(config)
     }
 
@@ -207,8 +297,41 @@ impl BGitGlobalConfig {
     }
 
     /// Save global configuration to the platform's config file path.
-    /// Secrets are serialized as base64 to match loader expectations.
+    /// Plain text secrets are encrypted into the vault and only their names are written.
     pub fn save_global(&self) -> Result<(), Box<BGitError>> {
+        let mut config = self.clone();
+        let mut secrets = Vec::new();
+        if let Some(pat) = config.auth.https.pat.take().filter(|p| !p.is_empty()) {
+            let name = config
+                .auth
+                .https
+                .pat_secret
+                .get_or_insert_with(|| HTTPS_PAT_SECRET.to_string());
+            secrets.push((name.clone(), pat));
+        }
+        for account in &mut config.auth.https_accounts {
+            if let Some(pat) = account.pat.take().filter(|p| !p.is_empty()) {
+                let default_name = account.default_secret_name();
+                let name = account.pat_secret.get_or_insert(default_name);
+                secrets.push((name.clone(), pat));
+            }
+        }
+        if let Some(key) = config
+            .integrations
+            .google_api_key
+            .take()
+            .filter(|k| !k.is_empty())
+        {
+            let name = config
+                .integrations
+                .google_api_key_secret
+                .get_or_insert_with(|| GOOGLE_API_KEY_SECRET.to_string());
+            secrets.push((name.clone(), key));
+        }
+        if !secrets.is_empty() {
+            vault::store_secrets(&secrets)?;
+        }
+
         let path = BGitGlobalConfig::find_global_config_path();
         if let Some(parent) = path.parent()
             && let Err(e) = fs::create_dir_all(parent)
@@ -223,7 +346,7 @@ impl BGitGlobalConfig {
             )));
         }
 
-        let toml_content = toml::to_string_pretty(self).map_err(|e| {
+        let toml_content = toml::to_string_pretty(&config).map_err(|e| {
             Box::new(BGitError::new(
                 "Failed to serialize global config",
                 &format!("TOML serialization error: {}", e),
@@ -245,19 +368,120 @@ impl BGitGlobalConfig {
             ))
         })
     }
-    /// Helper to fetch Google API key from new location.
-    pub fn get_google_api_key(&self) -> Option<&str> {
-        self.integrations.google_api_key.as_deref()
+    /// Helper to fetch Google API key, unlocking the vault if it is stored there
+    pub fn get_google_api_key(&self) -> Option<String> {
+        resolve_secret(
+            self.integrations.google_api_key.as_deref(),
+            self.integrations.google_api_key_secret.as_deref(),
+        )
     }
 
     /// Helper to fetch HTTPS credentials if configured (username, pat)
-    pub fn get_https_credentials(&self) -> Option<(&str, &str)> {
-        match (&self.auth.https.username, &self.auth.https.pat) {
-            (Some(u), Some(t)) if !u.is_empty() && !t.is_empty() => Some((u.as_str(), t.as_str())),
-            _ => None,
+    pub fn get_https_credentials(&self) -> Option<(String, String)> {
+        let username = self
+            .auth
+            .https
+            .username
+            .as_deref()
+            .filter(|u| !u.is_empty())?;
+        let pat = resolve_secret(
+            self.auth.https.pat.as_deref(),
+            self.auth.https.pat_secret.as_deref(),
+        )?;
+        Some((username.to_string(), pat))
+    }
+
+    /// HTTPS credentials for a remote on `host` at `path` (e.g. "our-org/repo.git").
+    /// The most specific matching account wins; the `[auth.https]` default is the fallback.
+    pub fn get_https_credentials_for(&self, host: &str, path: &str) -> Option<(String, String)> {
+        match self.find_https_account(host, path) {
+            Some(account) => {
+                let username = account.username.as_deref().filter(|u| !u.is_empty())?;
+                let pat = resolve_secret(account.pat.as_deref(), account.pat_secret.as_deref())?;
+                Some((username.to_string(), pat))
+            }
+            None => self.get_https_credentials(),
         }
     }
 
+    /// Most specific `[[auth.https_accounts]]` entry matching `host` and `path`
+    pub fn find_https_account(&self, host: &str, path: &str) -> Option<&HttpsAccount> {
+        self.auth
+            .https_accounts
+            .iter()
+            .filter_map(|account| Some((account.match_score(host, path)?, account)))
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, account)| account)
+    }
+
+    /// Add or update the HTTPS account for `host` (same pattern syntax as `HttpsAccount::host`)
+    pub fn set_https_account(&mut self, host: &str, username: &str, pat: &str) {
+        let index = match self
+            .auth
+            .https_accounts
+            .iter()
+            .position(|account| account.host.eq_ignore_ascii_case(host))
+        {
+            Some(index) => index,
+            None => {
+                self.auth.https_accounts.push(HttpsAccount {
+                    host: host.to_string(),
+                    ..HttpsAccount::default()
+                });
+                self.auth.https_accounts.len() - 1
+            }
+        };
+        let account = &mut self.auth.https_accounts[index];
+        account.username = Some(username.to_string());
+        account.pat = Some(pat.to_string());
+    }
+
+    /// Move secrets loaded from legacy base64 fields into the vault and rewrite the config
+    /// without them. Returns whether there was anything to move.
+    pub fn migrate_plaintext_secrets(&self) -> Result<bool, Box<BGitError>> {
+        if !self.has_plaintext_secrets() {
+            return Ok(false);
+        }
+        self.save_global()?;
+        println!(
+            "Moved base64-encoded secrets from {} into the encrypted vault {}",
+            BGitGlobalConfig::find_global_config_path().display(),
+            vault::vault_path().display()
+        );
+        Ok(true)
+    }
+
+    /// Whether secrets were loaded from legacy base64 fields and still need moving to the vault
+    fn has_plaintext_secrets(&self) -> bool {
+        self.auth
+            .https
+            .pat
+            .as_deref()
+            .is_some_and(|p| !p.is_empty())
+            || self
+                .auth
+                .https_accounts
+                .iter()
+                .any(|account| account.pat.as_deref().is_some_and(|p| !p.is_empty()))
+            || self
+                .integrations
+                .google_api_key
+                .as_deref()
+                .is_some_and(|k| !k.is_empty())
+    }
+
+    /// Helper to fetch the custom CA bundle path if configured, expanding ~ if present
+    pub fn get_ca_bundle(&self) -> Option<PathBuf> {
+        let p = self.auth.tls.ca_bundle.as_ref()?;
+        let s = p.to_string_lossy();
+        if let Some(rest) = s.strip_prefix("~/")
+            && let Some(home) = home::home_dir()
+        {
+            return Some(home.join(rest));
+        }
+        Some(p.clone())
+    }
+
     /// Helper to fetch preferred SSH key file path if configured, expanding ~ if present
     pub fn get_ssh_key_file(&self) -> Option<std::path::PathBuf> {
         let p = self.auth.ssh.key_file.as_ref()?;
@@ -271,6 +495,21 @@ impl BGitGlobalConfig {
     }
 }
 
+/// Plain text value if present, otherwise the named secret from the vault
+fn resolve_secret(plaintext: Option<&str>, secret_name: Option<&str>) -> Option<String> {
+    if let Some(value) = plaintext.filter(|v| !v.is_empty()) {
+        return Some(value.to_string());
+    }
+
+    match vault::get_secret(secret_name?) {
+        Ok(value) => value.filter(|v| !v.is_empty()),
+        Err(err) => {
+            err.print_error();
+            None
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,7 +525,7 @@ mod tests {
         let cfg: BGitGlobalConfig = toml::from_str(&content).unwrap();
         assert_eq!(cfg.integrations.google_api_key.as_deref(), Some(key_plain));
         // Accessor fallback should also return it
-        assert_eq!(cfg.get_google_api_key(), Some(key_plain));
+        assert_eq!(cfg.get_google_api_key().as_deref(), Some(key_plain));
     }
 
     #[test]
@@ -342,4 +581,100 @@ preferred = "https"
         assert_eq!(creds.0, user);
         assert_eq!(creds.1, pat_plain);
     }
+
+    #[test]
+    fn test_global_secrets_serialized_by_name_only() {
+        let mut cfg: BGitGlobalConfig =
+            toml::from_str("[auth.https]\nusername = \"alice\"\npat_secret = \"auth.https.pat\"\n")
+                .unwrap();
+        assert!(!cfg.has_plaintext_secrets());
+
+        cfg.auth.https.pat = Some("tok_123".to_string());
+        assert!(cfg.has_plaintext_secrets());
+
+        let serialized = toml::to_string_pretty(&cfg).unwrap();
+        assert!(serialized.contains("pat_secret = \"auth.https.pat\""));
+        assert!(!serialized.contains("tok_123"));
+        assert!(!serialized.contains("pat = "));
+    }
+
+    #[test]
+    fn test_global_https_accounts_by_host_and_owner() {
+        let content = r#"[auth.https]
+username = "me"
+pat = "ZGVmYXVsdA=="
+
+[[auth.https_accounts]]
+host = "github.com"
+username = "personal"
+pat = "cGVyc29uYWw="
+
+[[auth.https_accounts]]
+host = "github.com/our-org/*"
+username = "work"
+pat = "d29yaw=="
+
+[[auth.https_accounts]]
+host = "gitlab.example.com"
+username = "selfhosted"
+pat = "Z2l0bGFi"
+"#;
+        let cfg: BGitGlobalConfig = toml::from_str(content).unwrap();
+        let user = |host: &str, path: &str| cfg.get_https_credentials_for(host, path).map(|c| c.0);
+
+        assert_eq!(
+            user("github.com", "our-org/repo.git").as_deref(),
+            Some("work")
+        );
+        assert_eq!(
+            user("github.com", "our-org-archive/repo").as_deref(),
+            Some("personal")
+        );
+        assert_eq!(
+            user("GitHub.com", "someone/repo").as_deref(),
+            Some("personal")
+        );
+        assert_eq!(
+            user("gitlab.example.com", "a/b").as_deref(),
+            Some("selfhosted")
+        );
+        assert_eq!(user("bitbucket.org", "a/b").as_deref(), Some("me"));
+    }
+
+    #[test]
+    fn test_global_set_https_account_updates_existing() {
+        let mut cfg = BGitGlobalConfig::default();
+        cfg.set_https_account("github.com/our-org/*", "work", "tok_1");
+        cfg.set_https_account("github.com/our-org/*", "work", "tok_2");
+        assert_eq!(cfg.auth.https_accounts.len(), 1);
+        assert_eq!(
+            cfg.get_https_credentials_for("github.com", "our-org/repo"),
+            Some(("work".to_string(), "tok_2".to_string()))
+        );
+        assert_eq!(
+            cfg.auth.https_accounts[0].default_secret_name(),
+            "auth.https.pat.github.com/our-org"
+        );
+    }
+
+    #[test]
+    fn test_global_tls_settings() {
+        let content = r#"[auth.tls]
+ca_bundle = "/etc/ssl/corp-ca.pem"
+insecure_skip_verify = false
+
+[auth.tls.pinned_certs]
+"git.example.com" = ["AB:CD:EF"]
+"#;
+        let cfg: BGitGlobalConfig = toml::from_str(content).unwrap();
+        assert_eq!(
+            cfg.get_ca_bundle(),
+            Some(PathBuf::from("/etc/ssl/corp-ca.pem"))
+        );
+        assert_eq!(
+            cfg.auth.tls.pinned_certs.get("git.example.com"),
+            Some(&vec!["AB:CD:EF".to_string()])
+        );
+        assert!(!cfg.auth.tls.insecure_skip_verify);
+    }
 }
@@ -14,6 +14,8 @@ pub struct BGitConfig {
     pub rules: RuleConfig,
     #[serde(default)]
     pub workflow: WorkflowConfig,
+    #[serde(default)]
+    pub custom_workflows: CustomWorkflowConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
@@ -25,11 +27,21 @@ pub struct RuleConfig {
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct WorkflowRules {
+    /// Rule parameters, from `[rules.<workflow>.params.<rule>]` - maps rule name to them
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub params: HashMap<String, RuleParams>,
     /// Rule settings for a specific workflow - maps rule name to its level
     #[serde(flatten)]
     pub rule_levels: HashMap<String, RuleLevel>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct RuleParams {
+    /// Parameter overrides - maps parameter name to its value
+    #[serde(flatten)]
+    pub params: HashMap<String, serde_json::Value>,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct WorkflowConfig {
     /// Workflow configurations - maps workflow name to its configuration
@@ -44,6 +56,36 @@ pub struct WorkflowSteps {
     pub steps: HashMap<String, StepFlags>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct CustomWorkflowConfig {
+    /// Team-defined workflows run with `bgit run <name>` - maps workflow name to its graph
+    #[serde(flatten)]
+    pub workflows: HashMap<String, CustomWorkflow>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct CustomWorkflow {
+    /// Shown when listing workflows
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Name of the registered step the workflow starts with
+    pub start: String,
+    /// Transition overrides - maps step name to where it goes next
+    #[serde(default)]
+    pub steps: HashMap<String, StepTransitions>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct StepTransitions {
+    /// Conditional transitions - maps the step the built-in flow would continue with
+    /// (or `stop`) to the step to run instead
+    #[serde(default)]
+    pub on: HashMap<String, String>,
+    /// Where to go when no `on` entry matches; the built-in successor if unset
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct StepFlags {
     /// Flag overrides - maps flag name to its value
@@ -98,7 +140,7 @@ impl BGitConfig {
     }
 
     /// Find the config file path, looking for .bgit/config.toml at repository root
-    fn find_config_path() -> Result<PathBuf, Box<BGitError>> {
+    pub(crate) fn find_config_path() -> Result<PathBuf, Box<BGitError>> {
         let cwd = env::current_dir().map_err(|e| {
             Box::new(BGitError::new(
                 "Failed to get current directory",
@@ -152,6 +194,11 @@ impl BGitConfig {
     pub fn get_workflow_steps(&self, workflow_name: &str) -> Option<&WorkflowSteps> {
         self.workflow.workflows.get(workflow_name)
     }
+
+    /// Get a custom workflow defined under `[custom_workflows.<name>]`
+    pub fn get_custom_workflow(&self, workflow_name: &str) -> Option<&CustomWorkflow> {
+        self.custom_workflows.workflows.get(workflow_name)
+    }
 }
 
 impl WorkflowRules {
@@ -159,6 +206,24 @@ impl WorkflowRules {
     pub fn get_rule_level(&self, rule_name: &str) -> Option<&RuleLevel> {
         self.rule_levels.get(rule_name)
     }
+
+    /// Get the parameters a specific rule is configured with
+    pub fn get_rule_params(&self, rule_name: &str) -> Option<&RuleParams> {
+        self.params.get(rule_name)
+    }
+}
+
+#[allow(dead_code)]
+impl RuleParams {
+    /// Get parameter value for a specific parameter
+    pub fn get_param<T>(&self, param_name: &str) -> Option<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.params
+            .get(param_name)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
 }
 
 impl WorkflowSteps {
@@ -218,6 +283,9 @@ IsGitInstalledLocally = "Error"
 GitNameEmailSetup = "Error"
 NoSecretsStaged = "Error"
 
+[rules.default.params.GitDefaultConfig]
+"init.defaultBranch" = "trunk"
+
 [workflow.default.is_sole_contributor]
 overrideCheckForAuthors = ["Lab Rat <dev.frolics@gmail.com>"]
 skipAddAll = true
@@ -250,6 +318,13 @@ timeout = 30
             Some(&RuleLevel::Error)
         );
 
+        let params = default_rules.get_rule_params("GitDefaultConfig").unwrap();
+        assert_eq!(
+            params.get_param::<String>("init.defaultBranch"),
+            Some("trunk".to_string())
+        );
+        assert!(default_rules.get_rule_level("params").is_none());
+
         // Test fallback to default workflow (no git_commit workflow in TOML)
 
         // Test workflow steps access
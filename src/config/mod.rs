@@ -1,2 +1,3 @@
 pub mod global;
 pub mod local;
+pub mod vault;
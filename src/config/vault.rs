@@ -0,0 +1,443 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use log::debug;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
+use crate::config::global::BGitGlobalConfig;
+use crate::constants::DEFAULT_VAULT_PBKDF2_ITERATIONS;
+use crate::prompt;
+
+/// Environment variable holding the vault passphrase, for non-interactive use
+pub(crate) const VAULT_PASSPHRASE_ENV: &str = "BGIT_VAULT_PASSPHRASE";
+
+const VAULT_VERSION: u32 = 1;
+const CHECK_NAME: &str = "bgit-vault-check";
+const CHECK_VALUE: &[u8] = b"bgit-vault";
+
+/// Vault unlocked during this run, so the passphrase is asked at most once
+static UNLOCKED_VAULT: Mutex<Option<SecretVault>> = Mutex::new(None);
+
+/// On-disk layout of `secrets.vault`. Every value is `base64(nonce || AES-256-GCM ciphertext)`
+/// with the secret name as associated data, so entries cannot be swapped between names.
+#[derive(Debug, Deserialize, Serialize)]
+struct VaultFile {
+    version: u32,
+    /// PBKDF2-HMAC-SHA256 salt and iteration count used to derive the key
+    salt: String,
+    iterations: u32,
+    /// Known value encrypted with the key, to tell a wrong passphrase apart from corruption
+    check: String,
+    #[serde(default)]
+    secrets: BTreeMap<String, String>,
+}
+
+pub(crate) struct SecretVault {
+    path: PathBuf,
+    file: VaultFile,
+    key: LessSafeKey,
+}
+
+impl SecretVault {
+    /// Create an empty vault keyed by `key_material` (a passphrase or unlock file contents)
+    pub(crate) fn create(
+        path: &Path,
+        key_material: &[u8],
+        iterations: u32,
+    ) -> Result<Self, Box<BGitError>> {
+        let mut salt = [0u8; 16];
+        SystemRandom::new()
+            .fill(&mut salt)
+            .map_err(|_| to_vault_error("Failed to generate a random salt"))?;
+
+        let key = derive_key(key_material, &salt, iterations)?;
+        let check = seal(&key, CHECK_NAME, CHECK_VALUE)?;
+        Ok(SecretVault {
+            path: path.to_path_buf(),
+            file: VaultFile {
+                version: VAULT_VERSION,
+                salt: STANDARD.encode(salt),
+                iterations,
+                check,
+                secrets: BTreeMap::new(),
+            },
+            key,
+        })
+    }
+
+    /// Open an existing vault, failing if `key_material` does not unlock it
+    pub(crate) fn open(path: &Path, key_material: &[u8]) -> Result<Self, Box<BGitError>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| to_vault_error(&format!("Could not read {}: {e}", path.display())))?;
+        let file: VaultFile = toml::from_str(&content)
+            .map_err(|e| to_vault_error(&format!("Invalid vault file {}: {e}", path.display())))?;
+        if file.version != VAULT_VERSION {
+            return Err(to_vault_error(&format!(
+                "Unsupported vault version {} in {}",
+                file.version,
+                path.display()
+            )));
+        }
+
+        let salt = STANDARD
+            .decode(&file.salt)
+            .map_err(|e| to_vault_error(&format!("Invalid vault salt: {e}")))?;
+        let key = derive_key(key_material, &salt, file.iterations)?;
+        if open_sealed(&key, CHECK_NAME, &file.check).as_deref() != Ok(CHECK_VALUE) {
+            return Err(to_vault_error(
+                "Wrong passphrase or unlock file for the secret vault",
+            ));
+        }
+
+        Ok(SecretVault {
+            path: path.to_path_buf(),
+            file,
+            key,
+        })
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Result<Option<String>, Box<BGitError>> {
+        let Some(sealed) = self.file.secrets.get(name) else {
+            return Ok(None);
+        };
+        let plaintext = open_sealed(&self.key, name, sealed)
+            .map_err(|_| to_vault_error(&format!("Secret '{name}' could not be decrypted")))?;
+        String::from_utf8(plaintext)
+            .map(Some)
+            .map_err(|e| to_vault_error(&format!("Secret '{name}' is not valid UTF-8: {e}")))
+    }
+
+    pub(crate) fn set(&mut self, name: &str, value: &str) -> Result<(), Box<BGitError>> {
+        let sealed = seal(&self.key, name, value.as_bytes())?;
+        self.file.secrets.insert(name.to_string(), sealed);
+        Ok(())
+    }
+
+    /// Write the vault, readable by the owner only
+    pub(crate) fn save(&self) -> Result<(), Box<BGitError>> {
+        let content = toml::to_string_pretty(&self.file)
+            .map_err(|e| to_vault_error(&format!("Failed to serialize vault: {e}")))?;
+        write_private_file(&self.path, content.as_bytes())
+    }
+}
+
+/// Look up a secret by name, unlocking the vault on first use
+pub(crate) fn get_secret(name: &str) -> Result<Option<String>, Box<BGitError>> {
+    with_unlocked_vault(false, |vault| vault.get(name))
+}
+
+/// Encrypt and persist `(name, value)` pairs, creating the vault if it does not exist yet
+pub(crate) fn store_secrets(secrets: &[(String, String)]) -> Result<(), Box<BGitError>> {
+    with_unlocked_vault(true, |vault| {
+        for (name, value) in secrets {
+            vault.set(name, value)?;
+        }
+        vault.save()
+    })
+}
+
+/// Path of the vault file, next to the global config
+pub(crate) fn vault_path() -> PathBuf {
+    BGitGlobalConfig::find_global_config_path().with_file_name("secrets.vault")
+}
+
+/// Path of the optional unlock file used instead of a passphrase
+pub(crate) fn unlock_file_path() -> PathBuf {
+    BGitGlobalConfig::find_global_config_path().with_file_name("vault.key")
+}
+
+fn with_unlocked_vault<T>(
+    create_if_missing: bool,
+    f: impl FnOnce(&mut SecretVault) -> Result<T, Box<BGitError>>,
+) -> Result<T, Box<BGitError>> {
+    let mut unlocked = UNLOCKED_VAULT.lock().unwrap_or_else(|e| e.into_inner());
+    if unlocked.is_none() {
+        let path = vault_path();
+        let vault = if path.exists() {
+            SecretVault::open(&path, &resolve_key_material(false)?)?
+        } else if create_if_missing {
+            debug!("Creating secret vault at {}", path.display());
+            SecretVault::create(
+                &path,
+                &resolve_key_material(true)?,
+                DEFAULT_VAULT_PBKDF2_ITERATIONS,
+            )?
+        } else {
+            return Err(to_vault_error(&format!(
+                "Config references a secret but no vault exists at {}",
+                path.display()
+            )));
+        };
+        *unlocked = Some(vault);
+    }
+
+    match unlocked.as_mut() {
+        Some(vault) => f(vault),
+        None => Err(to_vault_error("Secret vault is not unlocked")),
+    }
+}
+
+/// Unlock file if present, then `BGIT_VAULT_PASSPHRASE`, then an interactive prompt
+fn resolve_key_material(creating: bool) -> Result<Vec<u8>, Box<BGitError>> {
+    let unlock_file = unlock_file_path();
+    if unlock_file.exists() {
+        return read_unlock_file(&unlock_file);
+    }
+
+    if let Ok(passphrase) = std::env::var(VAULT_PASSPHRASE_ENV)
+        && !passphrase.is_empty()
+    {
+        return Ok(passphrase.into_bytes());
+    }
+
+    if !creating {
+        return prompt_passphrase("Enter the passphrase for the bgit secret vault", false);
+    }
+
+    let choice = prompt::select(
+        "vault.protection",
+        "How should bgit protect saved secrets?",
+        &[
+            "With a passphrase (asked once per run)".to_string(),
+            format!("With an unlock file at {}", unlock_file.display()),
+        ],
+        0,
+    )
+    .map_err(|e| to_vault_error(&format!("Failed to read selection: {e}")))?;
+
+    if choice == 0 {
+        prompt_passphrase("Choose a passphrase for the bgit secret vault", true)
+    } else {
+        create_unlock_file(&unlock_file)
+    }
+}
+
+fn prompt_passphrase(prompt: &str, confirm: bool) -> Result<Vec<u8>, Box<BGitError>> {
+    let confirmation = confirm.then_some(("Repeat the passphrase", "Passphrases do not match"));
+    let passphrase = prompt::new_password("vault.passphrase", prompt, false, confirmation)
+        .map_err(|e| to_vault_error(&format!("Failed to read passphrase: {e}")))?;
+    if passphrase.is_empty() {
+        return Err(to_vault_error("The vault passphrase cannot be empty"));
+    }
+    Ok(passphrase.into_bytes())
+}
+
+fn read_unlock_file(path: &Path) -> Result<Vec<u8>, Box<BGitError>> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)
+            .map_err(|e| to_vault_error(&format!("Could not stat {}: {e}", path.display())))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            return Err(to_vault_error(&format!(
+                "Unlock file {} is accessible by other users (mode {:o}). Run `chmod 600 {}`.",
+                path.display(),
+                mode & 0o777,
+                path.display()
+            )));
+        }
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|e| to_vault_error(&format!("Could not read {}: {e}", path.display())))?;
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(to_vault_error(&format!(
+            "Unlock file {} is empty",
+            path.display()
+        )));
+    }
+    Ok(content.as_bytes().to_vec())
+}
+
+fn create_unlock_file(path: &Path) -> Result<Vec<u8>, Box<BGitError>> {
+    let mut random = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut random)
+        .map_err(|_| to_vault_error("Failed to generate an unlock key"))?;
+    let content = STANDARD.encode(random);
+    write_private_file(path, format!("{content}\n").as_bytes())?;
+    println!("Created unlock file {}", path.display());
+    Ok(content.into_bytes())
+}
+
+fn write_private_file(path: &Path, content: &[u8]) -> Result<(), Box<BGitError>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| to_vault_error(&format!("Could not create {}: {e}", parent.display())))?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.create(true).write(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options
+        .open(path)
+        .map_err(|e| to_vault_error(&format!("Could not write {}: {e}", path.display())))?;
+    // `mode` only applies to new files, so tighten one that already existed too
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| to_vault_error(&format!("Could not restrict {}: {e}", path.display())))?;
+    }
+    file.write_all(content)
+        .map_err(|e| to_vault_error(&format!("Could not write {}: {e}", path.display())))
+}
+
+fn derive_key(
+    key_material: &[u8],
+    salt: &[u8],
+    iterations: u32,
+) -> Result<LessSafeKey, Box<BGitError>> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or_else(|| to_vault_error("Vault iteration count must be positive"))?;
+    let mut key = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        key_material,
+        &mut key,
+    );
+    let unbound = UnboundKey::new(&AES_256_GCM, &key)
+        .map_err(|_| to_vault_error("Failed to initialize the vault cipher"))?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+fn seal(key: &LessSafeKey, name: &str, plaintext: &[u8]) -> Result<String, Box<BGitError>> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| to_vault_error("Failed to generate a nonce"))?;
+
+    let mut in_out = plaintext.to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::from(name.as_bytes()),
+        &mut in_out,
+    )
+    .map_err(|_| to_vault_error(&format!("Failed to encrypt secret '{name}'")))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&in_out);
+    Ok(STANDARD.encode(sealed))
+}
+
+fn open_sealed(key: &LessSafeKey, name: &str, sealed: &str) -> Result<Vec<u8>, ()> {
+    let sealed = STANDARD.decode(sealed).map_err(|_| ())?;
+    if sealed.len() < NONCE_LEN {
+        return Err(());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| ())?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::from(name.as_bytes()), &mut in_out)
+        .map_err(|_| ())?;
+    Ok(plaintext.to_vec())
+}
+
+fn to_vault_error(message: &str) -> Box<BGitError> {
+    Box::new(BGitError::new(
+        "Secret vault error",
+        message,
+        BGitErrorWorkflowType::Config,
+        NO_STEP,
+        NO_EVENT,
+        NO_RULE,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    // Keep key derivation cheap in tests
+    const TEST_ITERATIONS: u32 = 1_000;
+
+    #[test]
+    fn test_vault_roundtrip_and_wrong_passphrase() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("secrets.vault");
+
+        let mut vault = SecretVault::create(&path, b"correct horse", TEST_ITERATIONS).unwrap();
+        vault.set("auth.https.pat", "tok_123").unwrap();
+        vault.save().unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("tok_123"));
+
+        let reopened = SecretVault::open(&path, b"correct horse").unwrap();
+        assert_eq!(
+            reopened.get("auth.https.pat").unwrap().as_deref(),
+            Some("tok_123")
+        );
+        assert_eq!(reopened.get("missing").unwrap(), None);
+
+        assert!(SecretVault::open(&path, b"wrong horse").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private_file_restricts_an_existing_file() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("vault.key");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private_file(&path, b"new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_secrets_are_bound_to_their_name() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("secrets.vault");
+
+        let mut vault = SecretVault::create(&path, b"passphrase", TEST_ITERATIONS).unwrap();
+        vault.set("a", "value").unwrap();
+        let sealed = vault.file.secrets["a"].clone();
+        vault.file.secrets.insert("b".to_string(), sealed);
+
+        assert!(vault.get("b").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unlock_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("vault.key");
+        let material = create_unlock_file(&path).unwrap();
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+        assert_eq!(read_unlock_file(&path).unwrap(), material);
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(read_unlock_file(&path).is_err());
+    }
+}
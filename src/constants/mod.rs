@@ -2,8 +2,14 @@ pub(crate) const DEFAULT_MAX_LARGE_FILE_SIZE_IN_BYTES: u64 = 2 * 1024 * 1024; //
 pub(crate) const DEFAULT_MAX_REPO_SIZE_IN_MIB: u64 = 128; // 128 MiB
 pub(crate) const DEFAULT_MAX_CUMMULATIVE_STAGED_FILE_SIZE_IN_BYTES: u64 = 32 * 1024 * 1024; // 32 MiB
 
+// Maintenance (bgit check) defaults
+pub(crate) const DEFAULT_MAX_LOOSE_OBJECTS: usize = 6700; // Same as git's gc.auto
+pub(crate) const DEFAULT_STALE_BRANCH_DAYS: u64 = 90;
+pub(crate) const DEFAULT_STALE_STASH_DAYS: u64 = 30;
+
 // Authentication related defaults
 pub(crate) const MAX_AUTH_ATTEMPTS: usize = 3;
+pub(crate) const DEFAULT_VAULT_PBKDF2_ITERATIONS: u32 = 600_000; // OWASP recommendation for PBKDF2-HMAC-SHA256
 
 // SSH agent socket basename.
 // On Unix we bind ssh-agent to $HOME/.ssh/bgit_ssh_agent.sock.
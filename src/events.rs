@@ -2,12 +2,15 @@ use colored::Colorize;
 use git2::{Config, Repository};
 use std::env;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::{
     bgit_error::{BGitError, BGitErrorWorkflowType, NO_RULE, NO_STEP},
     config::global::BGitGlobalConfig,
     hook_executor::execute_hook_util,
-    rules::Rule,
+    oplog::{self, Snapshot, UndoScope},
+    rules::{Rule, RuleLevel, RuleOutput},
+    trace::{self, RuleOutcome},
     util::find_hook_with_extension,
 };
 pub mod git_add;
@@ -17,7 +20,8 @@ mod git_clean;
 pub mod git_clone;
 pub mod git_commit;
 pub mod git_config;
-mod git_filter_repo;
+pub mod git_filter_repo;
+pub mod git_gc;
 pub mod git_init;
 pub mod git_log;
 pub mod git_pull;
@@ -28,6 +32,37 @@ pub mod git_status;
 
 const PENGUIN_EMOJI: &str = "🐧";
 
+/// Set by `--dry-run`: events that would change the repository only report what they'd do
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_dry_run(enabled: bool) {
+    DRY_RUN.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Render `git <args>` the way one would type it, quoting arguments the shell would
+/// otherwise split or expand
+pub(crate) fn git_command_line<S: AsRef<str>>(args: &[S]) -> String {
+    let mut line = String::from("git");
+    for arg in args {
+        let arg = arg.as_ref();
+        line.push(' ');
+        let is_plain = !arg.is_empty()
+            && arg
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_./=:@+%,".contains(c));
+        if is_plain {
+            line.push_str(arg);
+        } else {
+            line.push_str(&format!("'{}'", arg.replace('\'', r"'\''")));
+        }
+    }
+    line
+}
+
 pub(crate) enum HookType {
     PreEvent,
     PostEvent,
@@ -53,6 +88,17 @@ pub(crate) trait AtomicEvent<'a> {
     // Plain execute the event, without any checks and hook
     fn raw_execute(&self) -> Result<bool, Box<BGitError>>;
 
+    /// The git command equivalent to this event, printed instead of running it under
+    /// `--dry-run`. `None` for read-only events, which still run since steps branch on
+    /// what they report.
+    fn dry_run_command(&self) -> Option<String>;
+
+    /// What `bgit undo` has to snapshot to walk this event back, `None` for events it
+    /// does not undo
+    fn undo_scope(&self) -> Option<UndoScope> {
+        None
+    }
+
     // Hooks
     fn pre_execute_hook(&self) -> Result<bool, Box<BGitError>> {
         let event_hook_file_name: String = format!("pre_{}", self.get_name());
@@ -210,6 +256,23 @@ pub(crate) trait AtomicEvent<'a> {
             self.get_name().cyan().bold()
         );
         for rule in rules.iter() {
+            // Fixing a rule may change the repository, so a dry run only reports it
+            if is_dry_run() {
+                if rule.get_level() == RuleLevel::Skip {
+                    rule.record(RuleOutcome::Skipped, "");
+                } else if let RuleOutput::Exception(exception) = rule.check()? {
+                    eprintln!(
+                        "{} [dry-run] Rule {} would need fixing: {}",
+                        PENGUIN_EMOJI,
+                        rule.get_name().yellow(),
+                        exception
+                    );
+                    rule.record(RuleOutcome::NeedsFix, &exception);
+                } else {
+                    rule.record(RuleOutcome::Passed, "");
+                }
+                continue;
+            }
             let rule_passed = rule.execute()?;
             if !rule_passed {
                 return Err(Box::new(BGitError::new(
@@ -226,11 +289,31 @@ pub(crate) trait AtomicEvent<'a> {
     }
 
     fn execute(&self) -> Result<bool, Box<BGitError>> {
+        let simulated = is_dry_run().then(|| self.dry_run_command()).flatten();
+        trace::event_started(self.get_name(), simulated.as_deref());
+        let result = self.execute_with_checks(simulated);
+        trace::event_finished(&result);
+        result
+    }
+
+    /// Rules, hooks and the executor in order; `simulated` replaces the last two in a dry run
+    fn execute_with_checks(&self, simulated: Option<String>) -> Result<bool, Box<BGitError>> {
         eprintln!("Running event: {}", self.get_name());
         let rule_check_status = self.check_rules()?;
         if !rule_check_status {
             return Ok(false);
         }
+        if let Some(command) = simulated {
+            eprintln!(
+                "{} [dry-run] Would run: {}",
+                PENGUIN_EMOJI,
+                command.yellow()
+            );
+            return Ok(true);
+        }
+        if is_dry_run() {
+            return self.raw_execute();
+        }
         let event_hook_status = self.pre_execute_hook()?;
         if !event_hook_status {
             return Err(Box::new(BGitError::new(
@@ -248,7 +331,12 @@ pub(crate) trait AtomicEvent<'a> {
             PENGUIN_EMOJI,
             self.get_name().cyan().bold()
         );
+        let undo_scope = self.undo_scope();
+        let before = undo_scope.and_then(Snapshot::take);
         let raw_executor_status = self.raw_execute()?;
+        if let (Some(scope), Some(before)) = (undo_scope, before) {
+            oplog::append(self.get_name(), self.dry_run_command(), scope, before);
+        }
 
         let post_event_hook_status = self.post_execute_hook()?;
         if !post_event_hook_status {
@@ -275,3 +363,21 @@ pub(crate) trait AtomicEvent<'a> {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_command_line_quotes_shell_words() {
+        assert_eq!(git_command_line(&["add", "-A"]), "git add -A");
+        assert_eq!(
+            git_command_line(&["commit", "-m", "fix: don't panic"]),
+            r"git commit -m 'fix: don'\''t panic'"
+        );
+        assert_eq!(
+            git_command_line(&["stash", "pop", "stash@{1}"]),
+            "git stash pop 'stash@{1}'"
+        );
+    }
+}
@@ -1,6 +1,8 @@
-use super::AtomicEvent;
+use super::{AtomicEvent, git_command_line};
+use crate::oplog::UndoScope;
+use crate::rollback::{self, Compensation};
 use crate::{bgit_error::BGitError, config::global::BGitGlobalConfig, rules::Rule};
-use git2::{IndexAddOption, Repository};
+use git2::{Index, IndexAddOption, Oid, Repository};
 use std::path::Path;
 
 pub(crate) struct GitAdd<'a> {
@@ -52,11 +54,9 @@ impl<'a> AtomicEvent<'a> for GitAdd<'a> {
                 if selected_files.is_empty() {
                     return Err(self.to_bgit_error("No files selected for staging."));
                 }
-                self.add_specific_files(selected_files.iter().map(|s| s.as_str()).collect())?;
-                println!(
-                    "Successfully added {} file(s) to staging area.",
-                    selected_files.len()
-                );
+                let added =
+                    self.add_specific_files(selected_files.iter().map(|s| s.as_str()).collect())?;
+                println!("Successfully added {added} file(s) to staging area.");
                 Ok(true)
             }
             None => {
@@ -64,6 +64,21 @@ impl<'a> AtomicEvent<'a> for GitAdd<'a> {
             }
         }
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        match &self.add_mode {
+            Some(AddMode::Selective(files)) => {
+                let mut args = vec!["add", "--"];
+                args.extend(files.iter().map(String::as_str));
+                Some(git_command_line(&args))
+            }
+            _ => Some(git_command_line(&["add", "-A"])),
+        }
+    }
+
+    fn undo_scope(&self) -> Option<UndoScope> {
+        Some(UndoScope::Index)
+    }
 }
 
 impl<'a> GitAdd<'a> {
@@ -83,6 +98,8 @@ impl<'a> GitAdd<'a> {
             .index()
             .map_err(|e| self.to_bgit_error(&format!("Failed to get repository index: {e}")))?;
 
+        let staged_before = index.write_tree().ok();
+
         // Using ["."], which indicates the current directory recursively.
         index
             .add_all(["."], IndexAddOption::DEFAULT, None)
@@ -92,12 +109,13 @@ impl<'a> GitAdd<'a> {
         index
             .write()
             .map_err(|e| self.to_bgit_error(&format!("Failed to write index: {e}")))?;
+        Self::journal_staged(&mut index, staged_before);
 
         Ok(true)
     }
 
-    /// Add specific files to the staging area
-    fn add_specific_files(&self, file_paths: Vec<&str>) -> Result<(), Box<BGitError>> {
+    /// Add specific files to the staging area, returning how many were added
+    fn add_specific_files(&self, file_paths: Vec<&str>) -> Result<usize, Box<BGitError>> {
         // Open the repository at the current directory
         let repo = Repository::discover(Path::new("."))
             .map_err(|e| self.to_bgit_error(&format!("Failed to open repository: {e}")))?;
@@ -106,16 +124,36 @@ impl<'a> GitAdd<'a> {
             .index()
             .map_err(|e| self.to_bgit_error(&format!("Failed to get repository index: {e}")))?;
 
+        let staged_before = index.write_tree().ok();
+
+        let mut added = 0;
         for file_path in file_paths {
-            index.add_path(Path::new(file_path)).map_err(|e| {
+            // Like `git add` without `-f`, untracked files .gitignore covers stay out
+            let path = Path::new(file_path);
+            if index.get_path(path, 0).is_none() && repo.is_path_ignored(path).unwrap_or(false) {
+                println!("Skipped '{file_path}', it is ignored by .gitignore");
+                continue;
+            }
+            index.add_path(path).map_err(|e| {
                 self.to_bgit_error(&format!("Failed to add file '{file_path}' to index: {e}"))
             })?;
+            added += 1;
         }
 
         index
             .write()
             .map_err(|e| self.to_bgit_error(&format!("Failed to write index: {e}")))?;
+        Self::journal_staged(&mut index, staged_before);
 
-        Ok(())
+        Ok(added)
+    }
+
+    /// Let a failed workflow unstage what was added, when anything changed
+    fn journal_staged(index: &mut Index, staged_before: Option<Oid>) {
+        if let Some(tree) = staged_before
+            && index.write_tree().is_ok_and(|staged| staged != tree)
+        {
+            rollback::record(Compensation::RestoreIndex { tree });
+        }
     }
 }
@@ -1,4 +1,6 @@
-use super::AtomicEvent;
+use super::{AtomicEvent, git_command_line};
+use crate::oplog::UndoScope;
+use crate::rollback::{self, Compensation};
 use crate::{bgit_error::BGitError, config::global::BGitGlobalConfig, rules::Rule};
 use git2::{BranchType, Repository, StashApplyOptions, StashFlags, build::CheckoutBuilder};
 use std::path::Path;
@@ -7,6 +9,7 @@ use std::path::Path;
 pub(crate) enum BranchOperation {
     CheckCurrentBranch,
     MoveChanges,
+    Delete,
 }
 
 pub(crate) struct GitBranch<'a> {
@@ -15,6 +18,7 @@ pub(crate) struct GitBranch<'a> {
     operation: Option<BranchOperation>,
     target_branch_name: Option<String>,
     stash_message: Option<String>,
+    branches_to_delete: Vec<String>,
     _global_config: &'a BGitGlobalConfig,
 }
 
@@ -26,6 +30,7 @@ impl<'a> GitBranch<'a> {
             operation: Some(BranchOperation::CheckCurrentBranch),
             target_branch_name: None,
             stash_message: None,
+            branches_to_delete: vec![],
             _global_config,
         }
     }
@@ -41,6 +46,22 @@ impl<'a> GitBranch<'a> {
             operation: Some(BranchOperation::MoveChanges),
             target_branch_name: Some(target_branch_name),
             stash_message: Some("Moving changes to new branch".to_string()),
+            branches_to_delete: vec![],
+            _global_config,
+        }
+    }
+
+    pub fn delete_branches(
+        _global_config: &'a BGitGlobalConfig,
+        branch_names: Vec<String>,
+    ) -> Self {
+        GitBranch {
+            name: "git_branch".to_owned(),
+            pre_check_rules: vec![],
+            operation: Some(BranchOperation::Delete),
+            target_branch_name: None,
+            stash_message: None,
+            branches_to_delete: branch_names,
             _global_config,
         }
     }
@@ -61,6 +82,7 @@ impl<'a> AtomicEvent<'a> for GitBranch<'a> {
             operation: None,
             target_branch_name: None,
             stash_message: None,
+            branches_to_delete: vec![],
             _global_config,
         }
     }
@@ -75,6 +97,7 @@ impl<'a> AtomicEvent<'a> for GitBranch<'a> {
                 "Check if current branch is master, main, or dev"
             }
             Some(BranchOperation::MoveChanges) => "Move current changes to a new branch",
+            Some(BranchOperation::Delete) => "Delete local branches",
             None => "Branch operation (no operation specified)",
         }
     }
@@ -95,9 +118,41 @@ impl<'a> AtomicEvent<'a> for GitBranch<'a> {
         match &self.operation {
             Some(BranchOperation::CheckCurrentBranch) => self.check_current_branch_impl(&repo),
             Some(BranchOperation::MoveChanges) => self.move_changes_impl(&mut repo),
+            Some(BranchOperation::Delete) => self.delete_branches_impl(&repo),
             None => Err(self.to_bgit_error("No branch operation specified")),
         }
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        match &self.operation {
+            Some(BranchOperation::MoveChanges) => {
+                let message = self
+                    .stash_message
+                    .as_deref()
+                    .unwrap_or("Moving changes to new branch");
+                let branch = self.target_branch_name.as_deref().unwrap_or_default();
+                Some(format!(
+                    "{} && {} && {}",
+                    git_command_line(&["stash", "push", "--include-untracked", "-m", message]),
+                    git_command_line(&["switch", "-c", branch]),
+                    git_command_line(&["stash", "pop", "--index"])
+                ))
+            }
+            Some(BranchOperation::Delete) => {
+                let mut args = vec!["branch", "-D"];
+                args.extend(self.branches_to_delete.iter().map(String::as_str));
+                Some(git_command_line(&args))
+            }
+            Some(BranchOperation::CheckCurrentBranch) | None => None,
+        }
+    }
+
+    fn undo_scope(&self) -> Option<UndoScope> {
+        match &self.operation {
+            Some(BranchOperation::MoveChanges) => Some(UndoScope::WorkingTree),
+            _ => None,
+        }
+    }
 }
 
 impl<'a> GitBranch<'a> {
@@ -190,12 +245,20 @@ impl<'a> GitBranch<'a> {
             return Err(self.to_bgit_error("No changes found to move to new branch"));
         }
 
+        let original_branch = repo
+            .head()
+            .ok()
+            .filter(|head| head.is_branch())
+            .and_then(|head| head.shorthand().map(str::to_string));
+
         // Step 1: Save current changes to stash with index
         let stash_message = self
             .stash_message
             .as_deref()
             .unwrap_or("Moving changes to new branch");
-        let _stash_id = self.save_changes_to_stash(repo, stash_message)?;
+        let stash_id = self.save_changes_to_stash(repo, stash_message)?;
+        let stashed = Compensation::PopStash { stash: stash_id };
+        rollback::record(stashed.clone());
 
         // Step 2: Create new branch from current HEAD
         let branch_ref_name = {
@@ -238,6 +301,12 @@ impl<'a> GitBranch<'a> {
 
         repo.checkout_head(Some(CheckoutBuilder::default().force()))
             .map_err(|e| self.to_bgit_error(&format!("Failed to checkout new branch: {e}")))?;
+        if let Some(branch) = original_branch {
+            rollback::record(Compensation::SwitchBack {
+                branch,
+                created: target_branch_name.clone(),
+            });
+        }
 
         // Step 4: Pop the stash with checkout strategy to preserve staging
         let mut apply_options = StashApplyOptions::default();
@@ -247,9 +316,36 @@ impl<'a> GitBranch<'a> {
 
         repo.stash_pop(0, Some(&mut apply_options))
             .map_err(|e| self.to_bgit_error(&format!("Failed to apply stashed changes: {e}")))?;
+        rollback::forget(&stashed);
+
+        Ok(true)
+    }
+    fn delete_branches_impl(&self, repo: &Repository) -> Result<bool, Box<BGitError>> {
+        if self.branches_to_delete.is_empty() {
+            return Err(self.to_bgit_error("No branches provided for delete operation"));
+        }
+
+        for branch_name in &self.branches_to_delete {
+            let mut branch = repo
+                .find_branch(branch_name, BranchType::Local)
+                .map_err(|e| {
+                    self.to_bgit_error(&format!("Failed to find branch '{branch_name}': {e}"))
+                })?;
+
+            if branch.is_head() {
+                return Err(self.to_bgit_error(&format!(
+                    "Refusing to delete '{branch_name}' as it is the currently checked out branch"
+                )));
+            }
+
+            branch.delete().map_err(|e| {
+                self.to_bgit_error(&format!("Failed to delete branch '{branch_name}': {e}"))
+            })?;
+        }
 
         Ok(true)
     }
+
     // Helper method to save changes to stash
     fn save_changes_to_stash(
         &self,
@@ -294,3 +390,37 @@ impl<'a> GitBranch<'a> {
         Ok(false)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::commit_all;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_delete_branches_removes_only_the_named_branches() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        std::fs::write(temp_dir.path().join("a.txt"), "a").unwrap();
+        let head = repo.find_commit(commit_all(&repo, "base")).unwrap();
+        let current = repo.head().unwrap().shorthand().unwrap().to_string();
+        for branch in ["done", "also-done", "keep"] {
+            repo.branch(branch, &head, false).unwrap();
+        }
+
+        let global_config = BGitGlobalConfig::default();
+        let delete = GitBranch::delete_branches(
+            &global_config,
+            vec!["done".to_string(), "also-done".to_string()],
+        );
+        assert!(delete.delete_branches_impl(&repo).unwrap());
+        assert!(repo.find_branch("done", BranchType::Local).is_err());
+        assert!(repo.find_branch("also-done", BranchType::Local).is_err());
+        assert!(repo.find_branch("keep", BranchType::Local).is_ok());
+
+        // The checked out branch is never deleted
+        let delete = GitBranch::delete_branches(&global_config, vec![current.clone()]);
+        assert!(delete.delete_branches_impl(&repo).is_err());
+        assert!(repo.find_branch(&current, BranchType::Local).is_ok());
+    }
+}
@@ -1,6 +1,8 @@
-use super::AtomicEvent;
-use crate::auth::auth_utils::transform_url_for_preference;
+use super::{AtomicEvent, git_command_line};
+use crate::auth::auth_utils::resolve_remote_url;
 use crate::auth::git_auth::setup_auth_callbacks;
+use crate::auth::git_credential::{approve_pending_credential, reject_pending_credential};
+use crate::auth::ssh::take_host_key_error;
 use crate::bgit_error::BGitError;
 use crate::config::global::BGitGlobalConfig;
 use crate::rules::Rule;
@@ -47,9 +49,7 @@ impl<'a> AtomicEvent<'a> for GitClone<'a> {
         if self.url.is_empty() {
             return Err(self.to_bgit_error("Repository URL is not set"));
         }
-        let url = if let Some(new_url) =
-            transform_url_for_preference(&self.url, self.global_config.auth.preferred)
-        {
+        let url = if let Some(new_url) = resolve_remote_url(&self.url, &self.global_config.auth) {
             let preferred = self.global_config.auth.preferred;
             info!(
                 "Using preferred auth ({:?}) URL: {} -> {}",
@@ -67,20 +67,30 @@ impl<'a> AtomicEvent<'a> for GitClone<'a> {
         };
 
         // Create fetch options with authentication
-        let fetch_options = self.create_fetch_options();
+        let fetch_options = self.create_fetch_options(&url);
 
         // Clone repository with authentication options
         let mut builder = git2::build::RepoBuilder::new();
         builder.fetch_options(fetch_options);
 
-        builder.clone(&url, Path::new(repo_name)).map_err(|e| {
-            self.to_bgit_error(&format!("Failed to clone repository: {e}. Please check your SSH keys or authentication setup."))
-        })?;
+        builder
+            .clone(&url, Path::new(repo_name))
+            .inspect(|_| approve_pending_credential())
+            .inspect_err(reject_pending_credential)
+            .map_err(|e| {
+                take_host_key_error().unwrap_or_else(|| {
+                    self.to_bgit_error(&format!("Failed to clone repository: {e}. Please check your SSH keys or authentication setup."))
+                })
+            })?;
 
         self.update_cwd_path()?;
 
         Ok(true)
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        Some(git_command_line(&["clone", &self.url]))
+    }
 }
 
 impl<'a> GitClone<'a> {
@@ -104,9 +114,9 @@ impl<'a> GitClone<'a> {
     }
 
     /// Create fetch options with authentication
-    fn create_fetch_options(&'a self) -> git2::FetchOptions<'a> {
+    fn create_fetch_options(&'a self, url: &str) -> git2::FetchOptions<'a> {
         let mut fetch_options = git2::FetchOptions::new();
-        fetch_options.remote_callbacks(setup_auth_callbacks(self.global_config));
+        fetch_options.remote_callbacks(setup_auth_callbacks(self.global_config, url));
         fetch_options
     }
 }
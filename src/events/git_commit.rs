@@ -1,6 +1,9 @@
-use super::AtomicEvent;
+use super::{AtomicEvent, git_command_line};
+use crate::oplog::UndoScope;
+use crate::rollback::{self, Compensation};
+use crate::signing::CommitSigner;
 use crate::{bgit_error::BGitError, config::global::BGitGlobalConfig, rules::Rule};
-use git2::{Commit, Repository};
+use git2::{Commit, Oid, Repository, Signature, Tree};
 use std::path::Path;
 
 pub(crate) struct GitCommit<'a> {
@@ -56,6 +59,15 @@ impl<'a> AtomicEvent<'a> for GitCommit<'a> {
 
         self.commit_changes(&message)
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        let message = self.commit_message.as_deref().unwrap_or_default();
+        Some(git_command_line(&["commit", "-m", message]))
+    }
+
+    fn undo_scope(&self) -> Option<UndoScope> {
+        Some(UndoScope::Index)
+    }
 }
 
 impl<'a> GitCommit<'a> {
@@ -109,16 +121,82 @@ impl<'a> GitCommit<'a> {
 
         let parents: Vec<&Commit> = parent_commit.iter().collect();
 
-        repo.commit(
-            Some("HEAD"),
-            &signature,
-            &signature,
-            message,
-            &tree,
-            &parents,
-        )
-        .map_err(|e| self.to_bgit_error(&format!("Failed to create commit: {e}")))?;
+        let signer = repo
+            .config()
+            .map_err(|e| e.to_string())
+            .and_then(|config| CommitSigner::for_commits(&config))
+            .map_err(|e| self.to_bgit_error(&format!("Cannot sign the commit: {e}")))?;
+        let commit = match signer {
+            Some(signer) => {
+                self.commit_signed(&repo, &signer, &signature, message, &tree, &parents)?
+            }
+            None => repo
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    message,
+                    &tree,
+                    &parents,
+                )
+                .map_err(|e| self.to_bgit_error(&format!("Failed to create commit: {e}")))?,
+        };
+        // A root commit has nothing to go back to, so it stays
+        if let Some(parent) = &parent_commit {
+            rollback::record(Compensation::UndoCommit {
+                commit,
+                parent: parent.id(),
+            });
+        }
 
         Ok(true)
     }
+
+    /// Create the commit with a signature from `signer` and move HEAD to it, as
+    /// `git commit` does when `commit.gpgSign` is set
+    fn commit_signed(
+        &self,
+        repo: &Repository,
+        signer: &CommitSigner,
+        signature: &Signature,
+        message: &str,
+        tree: &Tree,
+        parents: &[&Commit],
+    ) -> Result<Oid, Box<BGitError>> {
+        let buffer = repo
+            .commit_create_buffer(signature, signature, message, tree, parents)
+            .map_err(|e| self.to_bgit_error(&format!("Failed to create commit: {e}")))?;
+        let payload = buffer
+            .as_str()
+            .ok_or_else(|| self.to_bgit_error("Commit content is not valid UTF-8"))?;
+        let committer = format!(
+            "{} <{}>",
+            signature.name().unwrap_or_default(),
+            signature.email().unwrap_or_default()
+        );
+        let armored = signer
+            .sign(payload, &committer)
+            .map_err(|e| self.to_bgit_error(&e))?;
+        let commit = repo
+            .commit_signed(payload, &armored, None)
+            .map_err(|e| self.to_bgit_error(&format!("Failed to create signed commit: {e}")))?;
+
+        let summary = message.lines().next().unwrap_or_default();
+        let reflog_message = if parents.is_empty() {
+            format!("commit (initial): {summary}")
+        } else {
+            format!("commit: {summary}")
+        };
+        let head = repo
+            .find_reference("HEAD")
+            .map_err(|e| self.to_bgit_error(&format!("Failed to get HEAD reference: {e}")))?;
+        match head.symbolic_target() {
+            Some(branch) => repo
+                .reference(branch, commit, true, &reflog_message)
+                .map(|_| ()),
+            None => repo.set_head_detached(commit),
+        }
+        .map_err(|e| self.to_bgit_error(&format!("Failed to update HEAD: {e}")))?;
+        Ok(commit)
+    }
 }
@@ -1,11 +1,12 @@
-use super::AtomicEvent;
+use super::{AtomicEvent, git_command_line};
 use crate::{bgit_error::BGitError, config::global::BGitGlobalConfig, rules::Rule};
-use git2::{Config, Repository};
+use git2::{Config, ConfigLevel, Repository};
 use std::path::Path;
 
 #[derive(Debug, Clone)]
 pub(crate) enum ConfigOperation {
     Get,
+    Set,
 }
 
 #[allow(dead_code)]
@@ -22,6 +23,7 @@ pub(crate) struct GitConfig<'a> {
     operation: Option<ConfigOperation>,
     scope: ConfigScope,
     key: Option<String>,
+    value: Option<String>,
     _global_config: &'a BGitGlobalConfig,
 }
 
@@ -31,11 +33,21 @@ impl<'a> GitConfig<'a> {
         self
     }
 
+    pub fn with_value(mut self, value: String) -> Self {
+        self.value = Some(value);
+        self
+    }
+
     pub fn with_operation(mut self, operation: ConfigOperation) -> Self {
         self.operation = Some(operation);
         self
     }
 
+    pub fn with_scope(mut self, scope: ConfigScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
     // Use this method to get the scope of the configuration
     pub fn get_value(&self) -> Result<String, Box<BGitError>> {
         let config = self.get_config_object()?;
@@ -62,6 +74,7 @@ impl<'a> AtomicEvent<'a> for GitConfig<'a> {
             operation: None,
             scope: ConfigScope::Local,
             key: None,
+            value: None,
             _global_config,
         }
     }
@@ -73,6 +86,7 @@ impl<'a> AtomicEvent<'a> for GitConfig<'a> {
     fn get_action_description(&self) -> &str {
         match &self.operation {
             Some(ConfigOperation::Get) => "Get git configuration value",
+            Some(ConfigOperation::Set) => "Set git configuration value",
             None => "Git configuration operation (no operation specified)",
         }
     }
@@ -88,12 +102,65 @@ impl<'a> AtomicEvent<'a> for GitConfig<'a> {
     fn raw_execute(&self) -> Result<bool, Box<BGitError>> {
         match &self.operation {
             Some(ConfigOperation::Get) => Ok(self.get_value().is_ok()),
+            Some(ConfigOperation::Set) => self.set_value().map(|_| true),
             None => Err(self.to_bgit_error("No config operation specified")),
         }
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        let Some(ConfigOperation::Set) = self.operation else {
+            return None;
+        };
+        let mut args = vec!["config"];
+        match self.scope {
+            ConfigScope::Local => {}
+            ConfigScope::Global => args.push("--global"),
+            ConfigScope::System => args.push("--system"),
+        }
+        args.push(self.key.as_deref().unwrap_or_default());
+        args.push(self.value.as_deref().unwrap_or_default());
+        Some(git_command_line(&args))
+    }
 }
 
 impl<'a> GitConfig<'a> {
+    fn set_value(&self) -> Result<(), Box<BGitError>> {
+        let (key, value) = self.key.as_ref().zip(self.value.as_ref()).ok_or_else(|| {
+            self.to_bgit_error("Config key and value must be provided for set operation")
+        })?;
+        self.get_writable_config()?
+            .set_str(key, value)
+            .map_err(|e| self.to_bgit_error(&format!("Failed to set '{key}': {e}")))
+    }
+
+    /// The single config file of `scope`, so writes never land in another level
+    fn get_writable_config(&self) -> Result<Config, Box<BGitError>> {
+        match self.scope {
+            ConfigScope::Local => self
+                .get_config_object()?
+                .open_level(ConfigLevel::Local)
+                .map_err(|e| self.to_bgit_error(&format!("Failed to get local config: {e}"))),
+            ConfigScope::Global => {
+                // Like `git config --global`, create ~/.gitconfig when there is none yet
+                let path = Config::find_global()
+                    .ok()
+                    .or_else(|| home::home_dir().map(|home| home.join(".gitconfig")))
+                    .ok_or_else(|| self.to_bgit_error("Could not locate the global config"))?;
+                Config::open(&path).map_err(|e| {
+                    self.to_bgit_error(&format!("Failed to open {}: {e}", path.display()))
+                })
+            }
+            ConfigScope::System => {
+                let path = Config::find_system().map_err(|e| {
+                    self.to_bgit_error(&format!("Failed to locate system config: {e}"))
+                })?;
+                Config::open(&path).map_err(|e| {
+                    self.to_bgit_error(&format!("Failed to open {}: {e}", path.display()))
+                })
+            }
+        }
+    }
+
     fn get_config_object(&self) -> Result<Config, Box<BGitError>> {
         match self.scope {
             ConfigScope::Local => {
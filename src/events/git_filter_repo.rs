@@ -1 +1,151 @@
+use super::{AtomicEvent, git_command_line};
+use crate::{bgit_error::BGitError, config::global::BGitGlobalConfig, rules::Rule};
+use git2::Repository;
+use std::path::Path;
+use std::process::Command;
 
+#[derive(Debug, Clone)]
+pub(crate) enum FilterRepoOperation {
+    RemovePaths,
+}
+
+/// Rewrites history through `git filter-repo`, which has to be installed separately
+pub(crate) struct GitFilterRepo<'a> {
+    name: String,
+    pre_check_rules: Vec<Box<dyn Rule + Send + Sync>>,
+    operation: Option<FilterRepoOperation>,
+    paths: Vec<String>,
+    _global_config: &'a BGitGlobalConfig,
+}
+
+impl<'a> GitFilterRepo<'a> {
+    pub fn remove_paths(_global_config: &'a BGitGlobalConfig, paths: Vec<String>) -> Self {
+        GitFilterRepo {
+            name: "git_filter_repo".to_owned(),
+            pre_check_rules: vec![],
+            operation: Some(FilterRepoOperation::RemovePaths),
+            paths,
+            _global_config,
+        }
+    }
+}
+
+impl<'a> AtomicEvent<'a> for GitFilterRepo<'a> {
+    fn new(_global_config: &'a BGitGlobalConfig) -> Self
+    where
+        Self: Sized,
+    {
+        GitFilterRepo {
+            name: "git_filter_repo".to_owned(),
+            pre_check_rules: vec![],
+            operation: None,
+            paths: vec![],
+            _global_config,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_action_description(&self) -> &str {
+        match &self.operation {
+            Some(FilterRepoOperation::RemovePaths) => "Remove paths from the entire history",
+            None => "No filter-repo operation specified",
+        }
+    }
+
+    fn add_pre_check_rule(&mut self, rule: Box<dyn Rule + Send + Sync>) {
+        self.pre_check_rules.push(rule);
+    }
+
+    fn get_pre_check_rule(&self) -> &Vec<Box<dyn Rule + Send + Sync>> {
+        &self.pre_check_rules
+    }
+
+    fn raw_execute(&self) -> Result<bool, Box<BGitError>> {
+        let repo = Repository::discover(Path::new("."))
+            .map_err(|e| self.to_bgit_error(&format!("Failed to open repository: {e}")))?;
+
+        match &self.operation {
+            Some(FilterRepoOperation::RemovePaths) => self.remove_paths_impl(&repo),
+            None => Err(self.to_bgit_error("No operation specified for GitFilterRepo")),
+        }
+    }
+
+    fn dry_run_command(&self) -> Option<String> {
+        Some(git_command_line(&self.filter_repo_args()))
+    }
+}
+
+impl<'a> GitFilterRepo<'a> {
+    /// Arguments to `git` that drop every one of `paths` from all commits
+    fn filter_repo_args(&self) -> Vec<&str> {
+        let mut args = vec!["filter-repo", "--force", "--invert-paths"];
+        for path in &self.paths {
+            args.extend(["--path", path.as_str()]);
+        }
+        args
+    }
+
+    fn remove_paths_impl(&self, repo: &Repository) -> Result<bool, Box<BGitError>> {
+        if self.paths.is_empty() {
+            return Err(self.to_bgit_error("No paths provided to remove from history"));
+        }
+
+        if which::which("git-filter-repo").is_err() {
+            return Err(self.to_bgit_error(
+                "git-filter-repo is not installed. Install it from https://github.com/newren/git-filter-repo and retry.",
+            ));
+        }
+
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| self.to_bgit_error("Cannot rewrite history of a bare repository"))?;
+
+        let output = Command::new("git")
+            .current_dir(workdir)
+            .args(self.filter_repo_args())
+            .output()
+            .map_err(|e| self.to_bgit_error(&format!("Failed to run git filter-repo: {e}")))?;
+
+        if !output.status.success() {
+            return Err(self.to_bgit_error(&format!(
+                "git filter-repo failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_repo_args_invert_every_path() {
+        let global_config = BGitGlobalConfig::default();
+        let filter_repo = GitFilterRepo::remove_paths(
+            &global_config,
+            vec!["assets/big.bin".to_string(), "my dump.sql".to_string()],
+        );
+        assert_eq!(
+            filter_repo.filter_repo_args(),
+            vec![
+                "filter-repo",
+                "--force",
+                "--invert-paths",
+                "--path",
+                "assets/big.bin",
+                "--path",
+                "my dump.sql",
+            ]
+        );
+        assert_eq!(
+            filter_repo.dry_run_command().unwrap(),
+            git_command_line(&filter_repo.filter_repo_args())
+        );
+    }
+}
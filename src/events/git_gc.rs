@@ -0,0 +1,181 @@
+use super::{AtomicEvent, git_command_line};
+use crate::{bgit_error::BGitError, config::global::BGitGlobalConfig, rules::Rule};
+use git2::Repository;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Loose object statistics of the object database
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct LooseObjectStats {
+    pub count: usize,
+    pub size_bytes: u64,
+}
+
+/// Count the objects in the `xx/` fan-out directories of `objects_dir`
+fn loose_object_stats(objects_dir: &Path) -> std::io::Result<LooseObjectStats> {
+    let mut stats = LooseObjectStats::default();
+    for entry in fs::read_dir(objects_dir)?.flatten() {
+        let is_fanout_dir = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.len() == 2 && name.chars().all(|c| c.is_ascii_hexdigit()));
+        if !is_fanout_dir {
+            continue;
+        }
+
+        if let Ok(objects) = fs::read_dir(entry.path()) {
+            for object in objects.flatten() {
+                stats.count += 1;
+                stats.size_bytes += object.metadata().map(|m| m.len()).unwrap_or(0);
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+pub(crate) struct GitGc<'a> {
+    name: String,
+    pre_check_rules: Vec<Box<dyn Rule + Send + Sync>>,
+    aggressive: bool,
+    _global_config: &'a BGitGlobalConfig,
+}
+
+impl<'a> GitGc<'a> {
+    /// Repack more thoroughly at the cost of a much slower run
+    pub fn with_aggressive(mut self, aggressive: bool) -> Self {
+        self.aggressive = aggressive;
+        self
+    }
+
+    /// Count loose (unpacked) objects by scanning `objects/xx/` fan-out directories
+    pub fn get_loose_object_stats(&self) -> Result<LooseObjectStats, Box<BGitError>> {
+        let repo = Repository::discover(Path::new("."))
+            .map_err(|e| self.to_bgit_error(&format!("Failed to open repository: {e}")))?;
+
+        let objects_dir = repo.path().join("objects");
+        loose_object_stats(&objects_dir).map_err(|e| {
+            self.to_bgit_error(&format!("Failed to read {}: {e}", objects_dir.display()))
+        })
+    }
+
+    /// Count objects not reachable from any ref or reflog, i.e. what `gc --prune=now` removes
+    pub fn get_unreachable_object_count(&self) -> Result<usize, Box<BGitError>> {
+        let repo = Repository::discover(Path::new("."))
+            .map_err(|e| self.to_bgit_error(&format!("Failed to open repository: {e}")))?;
+
+        let output = Command::new("git")
+            .arg("--git-dir")
+            .arg(repo.path())
+            .args(["fsck", "--unreachable", "--no-progress"])
+            .output()
+            .map_err(|e| self.to_bgit_error(&format!("Failed to run git fsck: {e}")))?;
+
+        if !output.status.success() {
+            return Err(self.to_bgit_error(&format!(
+                "git fsck failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| line.starts_with("unreachable "))
+            .count())
+    }
+}
+
+impl<'a> AtomicEvent<'a> for GitGc<'a> {
+    fn new(_global_config: &'a BGitGlobalConfig) -> Self
+    where
+        Self: Sized,
+    {
+        GitGc {
+            name: "git_gc".to_owned(),
+            pre_check_rules: vec![],
+            aggressive: false,
+            _global_config,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_action_description(&self) -> &str {
+        if self.aggressive {
+            "Aggressively repack and prune unreachable objects"
+        } else {
+            "Pack loose objects and prune unreachable objects"
+        }
+    }
+
+    fn add_pre_check_rule(&mut self, rule: Box<dyn Rule + Send + Sync>) {
+        self.pre_check_rules.push(rule);
+    }
+
+    fn get_pre_check_rule(&self) -> &Vec<Box<dyn Rule + Send + Sync>> {
+        &self.pre_check_rules
+    }
+
+    fn raw_execute(&self) -> Result<bool, Box<BGitError>> {
+        let repo = Repository::discover(Path::new("."))
+            .map_err(|e| self.to_bgit_error(&format!("Failed to open repository: {e}")))?;
+
+        // libgit2 has no gc/repack support, so defer to the git binary
+        let mut command = Command::new("git");
+        command
+            .arg("--git-dir")
+            .arg(repo.path())
+            .args(["gc", "--prune=now", "--quiet"]);
+        if self.aggressive {
+            command.arg("--aggressive");
+        }
+
+        let output = command
+            .output()
+            .map_err(|e| self.to_bgit_error(&format!("Failed to run git gc: {e}")))?;
+
+        if !output.status.success() {
+            return Err(self.to_bgit_error(&format!(
+                "git gc failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(true)
+    }
+
+    fn dry_run_command(&self) -> Option<String> {
+        let mut args = vec!["gc", "--prune=now"];
+        if self.aggressive {
+            args.push("--aggressive");
+        }
+        Some(git_command_line(&args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_loose_object_stats_counts_only_fanout_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        for content in ["one", "two", "three"] {
+            repo.blob(content.as_bytes()).unwrap();
+        }
+        // Packs and other bookkeeping next to the fan-out directories are not loose objects
+        let objects_dir = repo.path().join("objects");
+        fs::write(objects_dir.join("pack/pack-0.pack"), "packed").unwrap();
+        fs::create_dir_all(objects_dir.join("xy")).unwrap();
+        fs::write(objects_dir.join("xy/not-an-object"), "junk").unwrap();
+
+        let stats = loose_object_stats(&objects_dir).unwrap();
+        assert_eq!(stats.count, 3);
+        assert!(stats.size_bytes > 0);
+    }
+}
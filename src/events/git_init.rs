@@ -1,4 +1,4 @@
-use super::AtomicEvent;
+use super::{AtomicEvent, git_command_line};
 use crate::{bgit_error::BGitError, config::global::BGitGlobalConfig, rules::Rule};
 use git2::{Repository, RepositoryInitOptions};
 use std::{env, path::Path};
@@ -69,4 +69,8 @@ impl<'a> AtomicEvent<'a> for GitInit<'a> {
 
         Ok(true)
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        Some(git_command_line(&["init", "-b", "main", &self.path]))
+    }
 }
@@ -1,18 +1,61 @@
 use super::AtomicEvent;
+use crate::util::format_epoch_with_offset;
 use crate::{bgit_error::BGitError, config::global::BGitGlobalConfig, rules::Rule};
-use git2::Repository;
+use git2::{Commit, DiffFormat, DiffOptions, Oid, Repository};
+use regex::Regex;
 use std::collections::HashSet;
 use std::path::Path;
 
 #[derive(Debug, Clone)]
 pub(crate) enum LogOperation {
     CheckSoleContributor,
+    ListCommits,
+}
+
+/// Filters applied while walking history for `LogOperation::ListCommits`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LogFilter {
+    /// Case-insensitive substring matched against author name or email
+    pub author: Option<String>,
+    /// Only commits authored at or after this unix timestamp
+    pub since: Option<i64>,
+    /// Only commits authored at or before this unix timestamp
+    pub until: Option<i64>,
+    /// Only commits touching this path (file or directory prefix)
+    pub path: Option<String>,
+    /// Conventional Commit type, e.g. `feat` or `fix`
+    pub commit_type: Option<String>,
+    /// Stop after this many matching commits
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CommitSummary {
+    pub id: Oid,
+    pub author_name: String,
+    pub author_email: String,
+    /// Author time as unix timestamp
+    pub time: i64,
+    /// Author timezone offset in minutes
+    pub offset_minutes: i32,
+    pub summary: String,
+}
+
+impl CommitSummary {
+    pub fn short_id(&self) -> String {
+        self.id.to_string().chars().take(7).collect()
+    }
+
+    pub fn formatted_date(&self) -> String {
+        format_epoch_with_offset(self.time, self.offset_minutes)
+    }
 }
 
 pub(crate) struct GitLog<'a> {
     name: String,
     pre_check_rules: Vec<Box<dyn Rule + Send + Sync>>,
     operation: Option<LogOperation>,
+    filter: LogFilter,
     _global_config: &'a BGitGlobalConfig,
 }
 
@@ -22,9 +65,34 @@ impl<'a> GitLog<'a> {
             name: "git_log".to_owned(),
             pre_check_rules: vec![],
             operation: Some(LogOperation::CheckSoleContributor),
+            filter: LogFilter::default(),
             _global_config,
         }
     }
+
+    pub fn list_commits(_global_config: &'a BGitGlobalConfig, filter: LogFilter) -> Self {
+        GitLog {
+            name: "git_log".to_owned(),
+            pre_check_rules: vec![],
+            operation: Some(LogOperation::ListCommits),
+            filter,
+            _global_config,
+        }
+    }
+
+    /// Walk history from HEAD and return the commits matching the configured filter
+    pub fn get_commits(&self) -> Result<Vec<CommitSummary>, Box<BGitError>> {
+        let repo = Repository::discover(Path::new("."))
+            .map_err(|e| self.to_bgit_error(&format!("Failed to open repository: {e}")))?;
+        self.list_commits_impl(&repo)
+    }
+
+    /// Render the patch introduced by a commit (against its first parent)
+    pub fn get_commit_diff(&self, commit_id: Oid) -> Result<String, Box<BGitError>> {
+        let repo = Repository::discover(Path::new("."))
+            .map_err(|e| self.to_bgit_error(&format!("Failed to open repository: {e}")))?;
+        self.commit_diff_impl(&repo, commit_id)
+    }
 }
 
 impl<'a> AtomicEvent<'a> for GitLog<'a> {
@@ -36,6 +104,7 @@ impl<'a> AtomicEvent<'a> for GitLog<'a> {
             name: "git_log".to_owned(),
             pre_check_rules: vec![],
             operation: None,
+            filter: LogFilter::default(),
             _global_config,
         }
     }
@@ -49,6 +118,7 @@ impl<'a> AtomicEvent<'a> for GitLog<'a> {
             Some(LogOperation::CheckSoleContributor) => {
                 "Check if current author is the sole contributor"
             }
+            Some(LogOperation::ListCommits) => "List commit history",
             None => "No operation specified",
         }
     }
@@ -67,9 +137,16 @@ impl<'a> AtomicEvent<'a> for GitLog<'a> {
 
         match &self.operation {
             Some(LogOperation::CheckSoleContributor) => self.check_sole_contributor_impl(&repo),
+            Some(LogOperation::ListCommits) => self
+                .list_commits_impl(&repo)
+                .map(|commits| !commits.is_empty()),
             None => Err(self.to_bgit_error("No operation specified for GitLog")),
         }
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        None
+    }
 }
 
 impl<'a> GitLog<'a> {
@@ -144,4 +221,300 @@ impl<'a> GitLog<'a> {
 
         Ok(is_sole_author)
     }
+
+    fn list_commits_impl(&self, repo: &Repository) -> Result<Vec<CommitSummary>, Box<BGitError>> {
+        let mut revwalk = repo
+            .revwalk()
+            .map_err(|e| self.to_bgit_error(&format!("Failed to create revwalk: {e}")))?;
+
+        match revwalk.push_head() {
+            Ok(()) => {}
+            Err(e)
+                if e.code() == git2::ErrorCode::UnbornBranch
+                    || e.code() == git2::ErrorCode::NotFound =>
+            {
+                // No commits yet, so there is no history to show
+                return Ok(vec![]);
+            }
+            Err(e) => {
+                return Err(self.to_bgit_error(&format!("Failed to push HEAD to revwalk: {e}")));
+            }
+        }
+
+        revwalk
+            .set_sorting(git2::Sort::TIME)
+            .map_err(|e| self.to_bgit_error(&format!("Failed to sort revwalk: {e}")))?;
+
+        let type_regex = Regex::new(r"^([a-zA-Z]+)(\([^)]*\))?!?:")
+            .map_err(|e| self.to_bgit_error(&format!("Failed to compile type regex: {e}")))?;
+        let author_filter = self.filter.author.as_ref().map(|a| a.to_lowercase());
+
+        let mut commits = Vec::new();
+        for oid_result in revwalk {
+            if let Some(limit) = self.filter.limit
+                && commits.len() >= limit
+            {
+                break;
+            }
+
+            let oid = oid_result
+                .map_err(|e| self.to_bgit_error(&format!("Failed to get commit OID: {e}")))?;
+            let commit = repo
+                .find_commit(oid)
+                .map_err(|e| self.to_bgit_error(&format!("Failed to find commit: {e}")))?;
+
+            let author = commit.author();
+            let author_name = author.name().unwrap_or_default().to_string();
+            let author_email = author.email().unwrap_or_default().to_string();
+            let time = author.when();
+
+            if let Some(since) = self.filter.since
+                && time.seconds() < since
+            {
+                continue;
+            }
+            if let Some(until) = self.filter.until
+                && time.seconds() > until
+            {
+                continue;
+            }
+
+            if let Some(needle) = &author_filter
+                && !author_name.to_lowercase().contains(needle)
+                && !author_email.to_lowercase().contains(needle)
+            {
+                continue;
+            }
+
+            let summary = commit.summary().unwrap_or_default().to_string();
+            if let Some(wanted_type) = &self.filter.commit_type {
+                let matches_type = type_regex
+                    .captures(&summary)
+                    .and_then(|caps| caps.get(1))
+                    .is_some_and(|t| t.as_str().eq_ignore_ascii_case(wanted_type));
+                if !matches_type {
+                    continue;
+                }
+            }
+
+            if let Some(path) = &self.filter.path
+                && !self.commit_touches_path(repo, &commit, path)?
+            {
+                continue;
+            }
+
+            commits.push(CommitSummary {
+                id: oid,
+                author_name,
+                author_email,
+                time: time.seconds(),
+                offset_minutes: time.offset_minutes(),
+                summary,
+            });
+        }
+
+        Ok(commits)
+    }
+
+    fn diff_against_first_parent<'r>(
+        &self,
+        repo: &'r Repository,
+        commit: &Commit,
+        diff_options: Option<&mut DiffOptions>,
+    ) -> Result<git2::Diff<'r>, Box<BGitError>> {
+        let tree = commit
+            .tree()
+            .map_err(|e| self.to_bgit_error(&format!("Failed to get commit tree: {e}")))?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(
+                parent
+                    .tree()
+                    .map_err(|e| self.to_bgit_error(&format!("Failed to get parent tree: {e}")))?,
+            ),
+            // Root commit, diff against the empty tree
+            Err(_) => None,
+        };
+
+        repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), diff_options)
+            .map_err(|e| self.to_bgit_error(&format!("Failed to diff commit: {e}")))
+    }
+
+    fn commit_touches_path(
+        &self,
+        repo: &Repository,
+        commit: &Commit,
+        path: &str,
+    ) -> Result<bool, Box<BGitError>> {
+        let mut diff_options = DiffOptions::new();
+        diff_options.pathspec(path.trim_end_matches('/'));
+        let diff = self.diff_against_first_parent(repo, commit, Some(&mut diff_options))?;
+        Ok(diff.deltas().len() > 0)
+    }
+
+    fn commit_diff_impl(
+        &self,
+        repo: &Repository,
+        commit_id: Oid,
+    ) -> Result<String, Box<BGitError>> {
+        let commit = repo
+            .find_commit(commit_id)
+            .map_err(|e| self.to_bgit_error(&format!("Failed to find commit: {e}")))?;
+
+        let mut diff_options = DiffOptions::new();
+        if let Some(path) = &self.filter.path {
+            diff_options.pathspec(path.trim_end_matches('/'));
+        }
+        let diff = self.diff_against_first_parent(repo, &commit, Some(&mut diff_options))?;
+
+        let mut patch = String::new();
+        diff.print(DiffFormat::Patch, |_delta, _hunk, line| {
+            let content = String::from_utf8_lossy(line.content());
+            match line.origin() {
+                '+' | '-' | ' ' => patch.push(line.origin()),
+                _ => {}
+            }
+            patch.push_str(&content);
+            true
+        })
+        .map_err(|e| self.to_bgit_error(&format!("Failed to render diff: {e}")))?;
+
+        Ok(patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Signature, Time};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn commit_file(repo: &Repository, file: &str, message: &str, author: &str, epoch: i64) {
+        let workdir = repo.workdir().unwrap();
+        let full_path = workdir.join(file);
+        if let Some(parent) = full_path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(&full_path, message).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+        let signature = Signature::new(
+            author,
+            &format!("{author}@example.com"),
+            &Time::new(epoch, 0),
+        )
+        .unwrap();
+        let parents = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit().unwrap()],
+            Err(_) => vec![],
+        };
+        let parent_refs: Vec<&Commit> = parents.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parent_refs,
+        )
+        .unwrap();
+    }
+
+    fn setup_history() -> (TempDir, Repository) {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        commit_file(
+            &repo,
+            "README.md",
+            "docs: add readme",
+            "alice",
+            1_700_000_000,
+        );
+        commit_file(
+            &repo,
+            "src/lib.rs",
+            "feat(core): add lib",
+            "bob",
+            1_700_100_000,
+        );
+        commit_file(
+            &repo,
+            "src/lib.rs",
+            "fix: handle empty input",
+            "alice",
+            1_700_200_000,
+        );
+        (temp_dir, repo)
+    }
+
+    fn list_with(repo: &Repository, filter: LogFilter) -> Vec<String> {
+        let global_config = BGitGlobalConfig::default();
+        GitLog::list_commits(&global_config, filter)
+            .list_commits_impl(repo)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.summary)
+            .collect()
+    }
+
+    #[test]
+    fn test_list_commits_newest_first() {
+        let (_dir, repo) = setup_history();
+        let summaries = list_with(&repo, LogFilter::default());
+        assert_eq!(
+            summaries,
+            vec![
+                "fix: handle empty input",
+                "feat(core): add lib",
+                "docs: add readme"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_list_commits_filters() {
+        let (_dir, repo) = setup_history();
+
+        let by_author = LogFilter {
+            author: Some("ALICE".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(list_with(&repo, by_author).len(), 2);
+
+        let by_type = LogFilter {
+            commit_type: Some("feat".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(list_with(&repo, by_type), vec!["feat(core): add lib"]);
+
+        let by_path = LogFilter {
+            path: Some("src/".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(list_with(&repo, by_path), vec!["fix: handle empty input"]);
+
+        let by_date = LogFilter {
+            since: Some(1_700_050_000),
+            until: Some(1_700_150_000),
+            ..Default::default()
+        };
+        assert_eq!(list_with(&repo, by_date), vec!["feat(core): add lib"]);
+    }
+
+    #[test]
+    fn test_commit_diff_contains_changes() {
+        let (_dir, repo) = setup_history();
+        let global_config = BGitGlobalConfig::default();
+        let git_log = GitLog::list_commits(&global_config, LogFilter::default());
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+
+        let diff = git_log.commit_diff_impl(&repo, head.id()).unwrap();
+        assert!(diff.contains("+fix: handle empty input"));
+        assert!(diff.contains("-feat(core): add lib"));
+    }
 }
@@ -1,10 +1,13 @@
 use std::path::Path;
 
-use super::AtomicEvent;
-use crate::auth::auth_utils::transform_url_for_preference;
+use super::{AtomicEvent, git_command_line};
+use crate::auth::auth_utils::resolve_remote_url;
 use crate::auth::git_auth::setup_auth_callbacks;
+use crate::auth::git_credential::{approve_pending_credential, reject_pending_credential};
+use crate::auth::ssh::take_host_key_error;
 use crate::bgit_error::BGitError;
 use crate::config::global::BGitGlobalConfig;
+use crate::oplog::UndoScope;
 use crate::rules::Rule;
 use git2::Repository;
 use log::info;
@@ -75,8 +78,7 @@ impl<'a> AtomicEvent<'a> for GitPull<'a> {
         };
 
         if let Some(url) = remote.url()
-            && let Some(new_url) =
-                transform_url_for_preference(url, self.global_config.auth.preferred)
+            && let Some(new_url) = resolve_remote_url(url, &self.global_config.auth)
         {
             let preferred = self.global_config.auth.preferred;
             info!(
@@ -89,12 +91,18 @@ impl<'a> AtomicEvent<'a> for GitPull<'a> {
         }
 
         // Set up fetch options with authentication
-        let mut fetch_options = self.create_fetch_options();
+        let mut fetch_options = self.create_fetch_options(remote.url().unwrap_or_default());
 
         // Fetch all references to ensure we have the latest remote state
-        remote.fetch(&[&"refs/heads/*:refs/remotes/origin/*".to_string()], Some(&mut fetch_options), None).map_err(|e| {
-            self.to_bgit_error(&format!("Failed to fetch from remote: {e}. Please check your SSH keys or authentication setup."))
-        })?;
+        remote
+            .fetch(&[&"refs/heads/*:refs/remotes/origin/*".to_string()], Some(&mut fetch_options), None)
+            .inspect(|_| approve_pending_credential())
+            .inspect_err(reject_pending_credential)
+            .map_err(|e| {
+                take_host_key_error().unwrap_or_else(|| {
+                    self.to_bgit_error(&format!("Failed to fetch from remote: {e}. Please check your SSH keys or authentication setup."))
+                })
+            })?;
 
         // Try to find the remote reference with better error handling
         let remote_branch_name = format!("refs/remotes/origin/{branch_name}");
@@ -146,6 +154,19 @@ impl<'a> AtomicEvent<'a> for GitPull<'a> {
 
         Ok(true)
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        let mode = if self.rebase {
+            "--rebase"
+        } else {
+            "--no-rebase"
+        };
+        Some(git_command_line(&["pull", mode, "origin"]))
+    }
+
+    fn undo_scope(&self) -> Option<UndoScope> {
+        Some(UndoScope::WorkingTree)
+    }
 }
 
 impl<'a> GitPull<'a> {
@@ -321,10 +342,10 @@ impl<'a> GitPull<'a> {
     }
 
     /// Create fetch options with authentication
-    fn create_fetch_options(&'a self) -> git2::FetchOptions<'a> {
+    fn create_fetch_options(&'a self, url: &str) -> git2::FetchOptions<'a> {
         let mut fetch_options = git2::FetchOptions::new();
         // Use centralized auth callbacks which ensure ssh-agent readiness and auto-add keys
-        fetch_options.remote_callbacks(setup_auth_callbacks(self.global_config));
+        fetch_options.remote_callbacks(setup_auth_callbacks(self.global_config, url));
         fetch_options
     }
 
@@ -341,13 +362,28 @@ impl<'a> GitPull<'a> {
             }
         };
 
+        if let Some(url) = remote.url()
+            && let Some(new_url) = resolve_remote_url(url, &self.global_config.auth)
+        {
+            info!("Using resolved remote URL: {} -> {}", url, new_url);
+            if let Ok(temp) = repo.remote_anonymous(new_url.as_str()) {
+                remote = temp;
+            }
+        }
+
         // Set up fetch options with authentication
-        let mut fetch_options = self.create_fetch_options();
+        let mut fetch_options = self.create_fetch_options(remote.url().unwrap_or_default());
 
         // Fetch all references to update remote tracking branches
-        remote.fetch(&[&"refs/heads/*:refs/remotes/origin/*".to_string()], Some(&mut fetch_options), None).map_err(|e| {
-            self.to_bgit_error(&format!("Failed to fetch from remote: {e}. Please check your SSH keys or authentication setup."))
-        })?;
+        remote
+            .fetch(&[&"refs/heads/*:refs/remotes/origin/*".to_string()], Some(&mut fetch_options), None)
+            .inspect(|_| approve_pending_credential())
+            .inspect_err(reject_pending_credential)
+            .map_err(|e| {
+                take_host_key_error().unwrap_or_else(|| {
+                    self.to_bgit_error(&format!("Failed to fetch from remote: {e}. Please check your SSH keys or authentication setup."))
+                })
+            })?;
 
         println!(
             "Successfully fetched from remote (no merge/rebase performed - repository has no commits yet)"
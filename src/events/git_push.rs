@@ -1,6 +1,8 @@
-use super::AtomicEvent;
-use crate::auth::auth_utils::transform_url_for_preference;
+use super::{AtomicEvent, git_command_line};
+use crate::auth::auth_utils::resolve_remote_url;
 use crate::auth::git_auth::setup_auth_callbacks;
+use crate::auth::git_credential::{approve_pending_credential, reject_pending_credential};
+use crate::auth::ssh::take_host_key_error;
 use crate::bgit_error::BGitError;
 use crate::config::global::BGitGlobalConfig;
 use crate::rules::Rule;
@@ -77,8 +79,7 @@ impl<'a> AtomicEvent<'a> for GitPush<'a> {
         })?;
 
         if let Some(url) = remote.url()
-            && let Some(new_url) =
-                transform_url_for_preference(url, self.global_config.auth.preferred)
+            && let Some(new_url) = resolve_remote_url(url, &self.global_config.auth)
         {
             let preferred = self.global_config.auth.preferred;
             log::info!(
@@ -93,7 +94,7 @@ impl<'a> AtomicEvent<'a> for GitPush<'a> {
         }
 
         // Prepare push options with authentication and callbacks
-        let mut push_options = self.create_push_options();
+        let mut push_options = self.create_push_options(remote.url().unwrap_or_default());
 
         if self.force_with_lease {
             // Best-effort native force-with-lease emulation with libgit2:
@@ -105,14 +106,23 @@ impl<'a> AtomicEvent<'a> for GitPush<'a> {
 
             // 2) Fetch latest state for the branch to update tracking ref
             let mut fetch_opts = git2::FetchOptions::new();
-            fetch_opts.remote_callbacks(setup_auth_callbacks(self.global_config));
+            fetch_opts.remote_callbacks(setup_auth_callbacks(
+                self.global_config,
+                remote.url().unwrap_or_default(),
+            ));
             let fetch_refspec = format!(
                 "refs/heads/{0}:refs/remotes/{1}/{0}",
                 branch_name, remote_name
             );
             remote
                 .fetch(&[fetch_refspec], Some(&mut fetch_opts), None)
-                .map_err(|e| self.to_bgit_error(&format!("Failed to fetch from remote: {e}")))?;
+                .inspect(|_| approve_pending_credential())
+                .inspect_err(reject_pending_credential)
+                .map_err(|e| {
+                    take_host_key_error().unwrap_or_else(|| {
+                        self.to_bgit_error(&format!("Failed to fetch from remote: {e}"))
+                    })
+                })?;
 
             // 3) Compare actual vs expected; if diverged, abort
             let actual_remote_oid = repo
@@ -131,12 +141,18 @@ impl<'a> AtomicEvent<'a> for GitPush<'a> {
                 format!("+refs/heads/{branch_name}")
             };
 
-            remote.push(&[refspec], Some(&mut push_options)).map_err(|e| {
-                let transport_hint = self.transport_hint(remote.url());
-                self.to_bgit_error(&format!(
-                    "Failed to push to remote {transport_hint} (force-with-lease): {e}. If authentication is required, ensure your credentials are set up."
-                ))
-            })?;
+            remote
+                .push(&[refspec], Some(&mut push_options))
+                .inspect(|_| approve_pending_credential())
+                .inspect_err(reject_pending_credential)
+                .map_err(|e| {
+                    take_host_key_error().unwrap_or_else(|| {
+                        let transport_hint = self.transport_hint(remote.url());
+                        self.to_bgit_error(&format!(
+                            "Failed to push to remote {transport_hint} (force-with-lease): {e}. If authentication is required, ensure your credentials are set up."
+                        ))
+                    })
+                })?;
         } else {
             // Pre-flight safety check for regular push
             self.validate_push_safety(&repo, &head, &branch_name)?;
@@ -147,12 +163,18 @@ impl<'a> AtomicEvent<'a> for GitPush<'a> {
                 format!("refs/heads/{branch_name}")
             };
 
-            remote.push(&[refspec], Some(&mut push_options)).map_err(|e| {
-                let transport_hint = self.transport_hint(remote.url());
-                self.to_bgit_error(&format!(
-                    "Failed to push to remote {transport_hint}: {e}. If authentication is required, ensure your credentials are set up."
-                ))
-            })?;
+            remote
+                .push(&[refspec], Some(&mut push_options))
+                .inspect(|_| approve_pending_credential())
+                .inspect_err(reject_pending_credential)
+                .map_err(|e| {
+                    take_host_key_error().unwrap_or_else(|| {
+                        let transport_hint = self.transport_hint(remote.url());
+                        self.to_bgit_error(&format!(
+                            "Failed to push to remote {transport_hint}: {e}. If authentication is required, ensure your credentials are set up."
+                        ))
+                    })
+                })?;
         }
 
         // Set upstream if requested or if there is no upstream yet
@@ -163,6 +185,31 @@ impl<'a> AtomicEvent<'a> for GitPush<'a> {
 
         Ok(true)
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        let mut args = vec!["push".to_string()];
+        if self.force_with_lease {
+            args.push("--force-with-lease".to_string());
+        }
+        if self.set_upstream {
+            args.push("--set-upstream".to_string());
+        }
+        // Name the same remote and refspec the real push would use, when they can be resolved
+        let target = Repository::discover(Path::new(".")).ok().and_then(|repo| {
+            let branch_name = repo.head().ok()?.shorthand()?.to_string();
+            let remote_name = self.determine_remote_name(&repo, &branch_name).ok()?;
+            Some((remote_name, branch_name))
+        });
+        if let Some((remote_name, branch_name)) = target {
+            args.push(remote_name);
+            args.push(if self.set_upstream {
+                format!("refs/heads/{branch_name}:refs/heads/{branch_name}")
+            } else {
+                format!("refs/heads/{branch_name}")
+            });
+        }
+        Some(git_command_line(&args))
+    }
 }
 
 impl<'a> GitPush<'a> {
@@ -284,9 +331,9 @@ impl<'a> GitPush<'a> {
     }
 
     /// Create push options with authentication
-    fn create_push_options(&'a self) -> git2::PushOptions<'a> {
+    fn create_push_options(&'a self, url: &str) -> git2::PushOptions<'a> {
         let mut push_options = git2::PushOptions::new();
-        let mut callbacks = setup_auth_callbacks(self.global_config);
+        let mut callbacks = setup_auth_callbacks(self.global_config, url);
         // Surface ref update errors clearly during push
         callbacks.push_update_reference(|refname, status| match status {
             Some(msg) => {
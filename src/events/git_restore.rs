@@ -1,4 +1,5 @@
-use super::AtomicEvent;
+use super::{AtomicEvent, git_command_line};
+use crate::oplog::UndoScope;
 use crate::{bgit_error::BGitError, config::global::BGitGlobalConfig, rules::Rule};
 use git2::{Repository, ResetType, build::CheckoutBuilder};
 use std::path::Path;
@@ -56,6 +57,17 @@ impl<'a> AtomicEvent<'a> for GitRestore<'a> {
             RestoreMode::UnstageAll => self.unstage_all_files(),
         }
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        match &self.mode {
+            Some(RestoreMode::UnstageAll) => Some(git_command_line(&["restore", "--staged", "."])),
+            _ => Some(git_command_line(&["restore", "."])),
+        }
+    }
+
+    fn undo_scope(&self) -> Option<UndoScope> {
+        Some(UndoScope::WorkingTree)
+    }
 }
 
 impl<'a> GitRestore<'a> {
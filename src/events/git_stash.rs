@@ -1,4 +1,5 @@
-use super::AtomicEvent;
+use super::{AtomicEvent, git_command_line};
+use crate::oplog::UndoScope;
 use crate::{bgit_error::BGitError, config::global::BGitGlobalConfig, rules::Rule};
 use git2::{Repository, StashApplyOptions};
 use std::path::Path;
@@ -6,6 +7,7 @@ use std::path::Path;
 #[derive(Debug, Clone)]
 pub(crate) enum StashOperation {
     Pop,
+    Drop,
 }
 
 pub(crate) struct GitStash<'a> {
@@ -26,6 +28,16 @@ impl<'a> GitStash<'a> {
             _global_config,
         }
     }
+
+    pub fn drop_stash(_global_config: &'a BGitGlobalConfig, index: Option<usize>) -> Self {
+        GitStash {
+            name: "git_stash".to_owned(),
+            pre_check_rules: vec![],
+            operation: Some(StashOperation::Drop),
+            stash_index: index,
+            _global_config,
+        }
+    }
 }
 
 impl<'a> AtomicEvent<'a> for GitStash<'a> {
@@ -49,6 +61,7 @@ impl<'a> AtomicEvent<'a> for GitStash<'a> {
     fn get_action_description(&self) -> &str {
         match &self.operation {
             Some(StashOperation::Pop) => "Pop stash and apply changes",
+            Some(StashOperation::Drop) => "Drop stash without applying changes",
             None => "No stash operation defined",
         }
     }
@@ -67,9 +80,25 @@ impl<'a> AtomicEvent<'a> for GitStash<'a> {
 
         match &self.operation {
             Some(StashOperation::Pop) => self.pop_stash_impl(&mut repo),
+            Some(StashOperation::Drop) => self.drop_stash_impl(&mut repo),
             None => Err(self.to_bgit_error("No stash operation defined")),
         }
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        let stash = format!("stash@{{{}}}", self.stash_index.unwrap_or(0));
+        match &self.operation {
+            Some(StashOperation::Drop) => Some(git_command_line(&["stash", "drop", &stash])),
+            _ => Some(git_command_line(&["stash", "pop", &stash])),
+        }
+    }
+
+    fn undo_scope(&self) -> Option<UndoScope> {
+        match &self.operation {
+            Some(StashOperation::Pop) => Some(UndoScope::WorkingTree),
+            _ => None,
+        }
+    }
 }
 
 impl<'a> GitStash<'a> {
@@ -89,6 +118,18 @@ impl<'a> GitStash<'a> {
         Ok(true)
     }
 
+    fn drop_stash_impl(&self, repo: &mut Repository) -> Result<bool, Box<BGitError>> {
+        let index = self.stash_index.unwrap_or(0);
+
+        self.check_stash_exists(repo, index)?;
+
+        repo.stash_drop(index).map_err(|e| {
+            self.to_bgit_error(&format!("Failed to drop stash at index {index}: {e}"))
+        })?;
+
+        Ok(true)
+    }
+
     fn check_stash_exists(
         &self,
         repo: &mut Repository,
@@ -114,3 +155,45 @@ impl<'a> GitStash<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::commit_all;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_drop_stash_discards_only_the_chosen_stash() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = Repository::init(temp_dir.path()).unwrap();
+        std::fs::write(temp_dir.path().join("a.txt"), "a").unwrap();
+        commit_all(&repo, "base");
+        let signature = Signature::now("bgit", "bgit@example.com").unwrap();
+        for message in ["older", "newer"] {
+            std::fs::write(temp_dir.path().join("a.txt"), message).unwrap();
+            repo.stash_save(&signature, message, None).unwrap();
+        }
+
+        let global_config = BGitGlobalConfig::default();
+        let drop = GitStash::drop_stash(&global_config, Some(1));
+        assert!(drop.drop_stash_impl(&mut repo).unwrap());
+
+        let mut messages = vec![];
+        repo.stash_foreach(|_, message, _| {
+            messages.push(message.to_string());
+            true
+        })
+        .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].ends_with("newer"));
+        // Dropping leaves the working tree alone
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("a.txt")).unwrap(),
+            "a"
+        );
+
+        // The remaining stash moved to index 0, so index 1 is gone
+        assert!(drop.drop_stash_impl(&mut repo).is_err());
+    }
+}
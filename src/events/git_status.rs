@@ -63,6 +63,10 @@ impl<'a> AtomicEvent<'a> for GitStatus<'a> {
             }
         }
     }
+
+    fn dry_run_command(&self) -> Option<String> {
+        None
+    }
 }
 
 impl<'a> GitStatus<'a> {
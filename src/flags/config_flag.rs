@@ -11,6 +11,24 @@ pub mod workflows {
             pub const OVERRIDE_CHECK_FOR_AUTHORS: &str = "overrideCheckForAuthors";
         }
     }
+
+    pub mod check {
+        pub mod check_loose_objects {
+            pub const MAX_LOOSE_OBJECTS: &str = "maxLooseObjects";
+        }
+
+        pub mod check_stale_branches {
+            pub const STALE_AFTER_DAYS: &str = "staleAfterDays";
+        }
+
+        pub mod check_stashes {
+            pub const STALE_AFTER_DAYS: &str = "staleAfterDays";
+        }
+
+        pub mod check_large_blobs {
+            pub const MAX_BLOB_SIZE_BYTES: &str = "maxBlobSizeBytes";
+        }
+    }
 }
 
 #[cfg(test)]
@@ -24,5 +42,9 @@ mod tests {
             workflows::default::is_sole_contributor::OVERRIDE_CHECK_FOR_AUTHORS,
             "overrideCheckForAuthors"
         );
+        assert_eq!(
+            workflows::check::check_stale_branches::STALE_AFTER_DAYS,
+            "staleAfterDays"
+        );
     }
 }
@@ -6,6 +6,7 @@ use std::process::{Command, Stdio};
 use super::error::create_hook_error;
 use super::process::handle_process_output;
 use crate::bgit_error::BGitError;
+use crate::trace;
 use log::debug;
 
 pub fn execute_hook_util(event_hook_path: &Path, event_name: &str) -> Result<bool, Box<BGitError>> {
@@ -91,6 +92,8 @@ pub fn execute_hook_util(event_hook_path: &Path, event_name: &str) -> Result<boo
         .wait()
         .map_err(|e| create_hook_error("Failed to wait on child", &e.to_string(), event_name))?;
 
+    trace::hook_ran(event_hook_path, status.code());
+
     if status.success() {
         Ok(true)
     } else {
@@ -58,6 +58,7 @@ use std::process::{Command, Stdio};
 use super::error::create_hook_error;
 use super::process::handle_process_output;
 use crate::bgit_error::BGitError;
+use crate::trace;
 
 fn create_command_for_hook(hook_path: &Path) -> Option<Command> {
     let extension = hook_path.extension()?.to_str()?;
@@ -168,6 +169,8 @@ pub fn execute_hook_util(event_hook_path: &Path, event_name: &str) -> Result<boo
         )
     })?;
 
+    trace::hook_ran(event_hook_path, status.code());
+
     if status.success() {
         Ok(true)
     } else {
@@ -1,13 +1,20 @@
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
 use crate::cmd::check::check;
 use crate::cmd::default::default_cmd_workflow;
 use crate::cmd::init::init;
 use crate::cmd::log::log;
+use crate::cmd::resume::resume;
+use crate::cmd::run::run;
+use crate::cmd::undo::undo;
+use crate::cmd::workflow::workflow;
 use crate::cmd::{Cli, Commands};
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::BGitConfig;
+use crate::prompt::{Answers, ScriptedPrompter};
 
 mod auth;
 mod bgit_error;
+mod checkpoint;
 mod cmd;
 mod config;
 mod constants;
@@ -15,8 +22,14 @@ mod events;
 mod flags;
 mod hook_executor;
 mod llm_tools;
+mod oplog;
+mod output;
+mod prompt;
+mod rollback;
 mod rules;
+mod signing;
 mod step;
+mod trace;
 mod util;
 mod workflow_queue;
 mod workflows;
@@ -36,21 +49,61 @@ fn main() {
         .format_timestamp_secs()
         .init();
 
-        let bgit_config = BGitConfig::load().unwrap_or_else(|err| {
-            err.print_error();
-            std::process::exit(1);
-        });
-
-        let global_config = BGitGlobalConfig::load_global().unwrap_or_else(|err| {
-            err.print_error();
-            std::process::exit(1);
-        });
-
-        match cli_instance.command {
-            Some(Commands::Log) => log(&bgit_config, &global_config),
-            Some(Commands::Init) => init(&bgit_config, &global_config),
-            Some(Commands::Check) => check(&bgit_config, &global_config),
-            None => default_cmd_workflow(&bgit_config, &global_config),
+        output::set_format(cli_instance.output);
+        let command = cli_instance.command_name();
+        let result = run_command(cli_instance);
+        std::process::exit(output::report(command, &result));
+    }
+}
+
+fn run_command(cli_instance: Cli) -> Result<(), Box<BGitError>> {
+    if let Some(answers_path) = &cli_instance.answers {
+        let answers = Answers::from_file(answers_path)?;
+        prompt::set_prompter(Box::new(ScriptedPrompter::new(answers)));
+    } else if cli_instance.yes {
+        prompt::set_prompter(Box::new(ScriptedPrompter::defaults_only()));
+    }
+
+    if cli_instance.dry_run {
+        if let Some(Commands::Init) = cli_instance.command {
+            return Err(to_usage_error(
+                "--dry-run only applies to workflows, `bgit init` has nothing to simulate",
+            ));
         }
+        events::set_dry_run(true);
+    }
+    if cli_instance.resume && cli_instance.command.is_some() {
+        return Err(to_usage_error(
+            "--resume continues the last failed workflow and takes no subcommand",
+        ));
     }
+    trace::set_print_trace(cli_instance.trace);
+
+    let bgit_config = BGitConfig::load()?;
+    let global_config = BGitGlobalConfig::load_global()?;
+
+    if cli_instance.resume {
+        return resume(&bgit_config, &global_config);
+    }
+
+    match cli_instance.command {
+        Some(Commands::Log(args)) => log(&bgit_config, &global_config, &args),
+        Some(Commands::Init) => init(&bgit_config, &global_config),
+        Some(Commands::Check) => check(&bgit_config, &global_config),
+        Some(Commands::Run(args)) => run(&bgit_config, &global_config, &args),
+        Some(Commands::Workflow(args)) => workflow(&bgit_config, &args),
+        Some(Commands::Undo(args)) => undo(&args),
+        None => default_cmd_workflow(&bgit_config, &global_config),
+    }
+}
+
+fn to_usage_error(message: &str) -> Box<BGitError> {
+    Box::new(BGitError::new(
+        "Invalid arguments",
+        message,
+        BGitErrorWorkflowType::Config,
+        NO_STEP,
+        NO_EVENT,
+        NO_RULE,
+    ))
 }
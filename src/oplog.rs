@@ -0,0 +1,461 @@
+use git2::{
+    BranchType, ErrorCode, IndexAddOption, Oid, Repository, Signature, build::CheckoutBuilder,
+};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::util::bgit_state_dir;
+
+const OPLOG_FILE: &str = "oplog";
+
+/// Operations kept in `.git/bgit/oplog`, oldest are dropped first
+const MAX_OPLOG_ENTRIES: usize = 100;
+
+/// What an undoable event changes, and so what has to be snapshotted around it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum UndoScope {
+    /// HEAD, branches and the index (add, commit)
+    Index,
+    /// The working tree as well (restore, stash pop, pull, moving changes to a branch)
+    WorkingTree,
+}
+
+/// State of the repository an operation can be walked back to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Snapshot {
+    /// Branch HEAD points at, `None` when detached
+    branch: Option<String>,
+    /// Commit HEAD resolves to, `None` on an unborn branch
+    head: Option<String>,
+    /// Tree of the index, `None` when it has conflicts
+    index: Option<String>,
+    /// Tree of the working tree, untracked files included; only for `UndoScope::WorkingTree`
+    worktree: Option<String>,
+    /// Newest stash, if any
+    stash: Option<String>,
+}
+
+/// One entry of `.git/bgit/oplog`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Operation {
+    pub event: String,
+    /// The git command equivalent to what the event did
+    pub command: Option<String>,
+    /// Unix timestamp (seconds) of the operation
+    pub at: u64,
+    scope: UndoScope,
+    before: Snapshot,
+    after: Snapshot,
+    /// Remote-tracking branch tips right after the operation, so commits pushed since can
+    /// be told apart from those that were on the remote already
+    remote_tips: Vec<String>,
+}
+
+impl Snapshot {
+    pub fn take(scope: UndoScope) -> Option<Self> {
+        let repo = Repository::discover(Path::new(".")).ok()?;
+        Self::of(&repo, scope)
+            .inspect_err(|e| warn!("Failed to snapshot the repository for undo: {e}"))
+            .ok()
+    }
+
+    fn of(repo: &Repository, scope: UndoScope) -> Result<Self, git2::Error> {
+        let (branch, head) = match repo.head() {
+            Ok(head) => (
+                head.is_branch()
+                    .then(|| head.shorthand().map(str::to_string))
+                    .flatten(),
+                head.peel_to_commit().ok().map(|c| c.id().to_string()),
+            ),
+            Err(e) if e.code() == ErrorCode::UnbornBranch => {
+                let target = repo.find_reference("HEAD")?;
+                let branch = target
+                    .symbolic_target()
+                    .and_then(|name| name.strip_prefix("refs/heads/"))
+                    .map(str::to_string);
+                (branch, None)
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Never written back, staging the working tree here only serves to hash it
+        let mut index = repo.index()?;
+        let index_tree = index.write_tree().ok().map(|tree| tree.to_string());
+        let worktree = match scope {
+            UndoScope::Index => None,
+            UndoScope::WorkingTree => {
+                index.add_all(["."], IndexAddOption::DEFAULT, None)?;
+                index.update_all(["."], None)?;
+                let worktree = index.write_tree()?.to_string();
+                index.read(true)?;
+                Some(worktree)
+            }
+        };
+
+        let stash = repo
+            .find_reference("refs/stash")
+            .ok()
+            .and_then(|stash| stash.target())
+            .map(|oid| oid.to_string());
+
+        Ok(Snapshot {
+            branch,
+            head,
+            index: index_tree,
+            worktree,
+            stash,
+        })
+    }
+
+    /// Same HEAD, index and (when snapshotted) working tree
+    fn matches(&self, other: &Snapshot) -> bool {
+        self.branch == other.branch
+            && self.head == other.head
+            && self.index == other.index
+            && self.worktree == other.worktree
+    }
+}
+
+fn oid(value: &str) -> Result<Oid, String> {
+    Oid::from_str(value).map_err(|e| format!("invalid object id '{value}': {e}"))
+}
+
+fn remote_tips(repo: &Repository) -> Vec<Oid> {
+    let Ok(branches) = repo.branches(Some(BranchType::Remote)) else {
+        return vec![];
+    };
+    let mut tips: Vec<Oid> = branches
+        .flatten()
+        .filter_map(|(branch, _)| branch.get().target())
+        .collect();
+    tips.sort();
+    tips.dedup();
+    tips
+}
+
+fn reaches(repo: &Repository, tips: &[Oid], commit: Oid) -> bool {
+    tips.iter()
+        .any(|&tip| tip == commit || repo.graph_descendant_of(tip, commit).unwrap_or(false))
+}
+
+impl Operation {
+    pub fn describe(&self) -> &str {
+        self.command.as_deref().unwrap_or(&self.event)
+    }
+
+    /// Walk the repository back to its state before this operation. Refuses when the
+    /// repository changed since, or when that would drop commits pushed since.
+    pub fn undo(&self, repo: &mut Repository) -> Result<(), String> {
+        let current = Snapshot::of(repo, self.scope).map_err(|e| e.to_string())?;
+        if !current.matches(&self.after) {
+            return Err(
+                "the repository changed since, undoing it would lose those changes".to_string(),
+            );
+        }
+        self.ensure_no_pushed_commits_lost(repo)?;
+
+        let before = &self.before;
+        if let Some(worktree) = &before.worktree {
+            let tree = repo
+                .find_object(oid(worktree)?, None)
+                .map_err(|e| e.to_string())?;
+            repo.checkout_tree(&tree, Some(CheckoutBuilder::new().force()))
+                .map_err(|e| e.to_string())?;
+        }
+
+        match (&before.branch, &before.head) {
+            (Some(branch), Some(head)) => {
+                repo.reference(
+                    &format!("refs/heads/{branch}"),
+                    oid(head)?,
+                    true,
+                    "bgit undo",
+                )
+                .map_err(|e| e.to_string())?;
+                repo.set_head(&format!("refs/heads/{branch}"))
+                    .map_err(|e| e.to_string())?;
+            }
+            // Back to an unborn branch, before its first commit
+            (Some(branch), None) => {
+                if let Ok(mut reference) = repo.find_reference(&format!("refs/heads/{branch}")) {
+                    reference.delete().map_err(|e| e.to_string())?;
+                }
+                repo.set_head(&format!("refs/heads/{branch}"))
+                    .map_err(|e| e.to_string())?;
+            }
+            (None, Some(head)) => repo
+                .set_head_detached(oid(head)?)
+                .map_err(|e| e.to_string())?,
+            (None, None) => {}
+        }
+
+        // A branch the operation switched to and created, e.g. by moving changes
+        if let Some(created) = &self.after.branch
+            && before.branch.as_ref() != Some(created)
+            && let Ok(mut branch) = repo.find_branch(created, BranchType::Local)
+            && branch.get().target().map(|t| t.to_string()) == before.head
+        {
+            branch.delete().map_err(|e| e.to_string())?;
+        }
+
+        if let Some(index_tree) = &before.index {
+            let tree = repo
+                .find_tree(oid(index_tree)?)
+                .map_err(|e| e.to_string())?;
+            let mut index = repo.index().map_err(|e| e.to_string())?;
+            index.read_tree(&tree).map_err(|e| e.to_string())?;
+            index.write().map_err(|e| e.to_string())?;
+        }
+
+        if before.stash != self.after.stash
+            && let Some(stash) = &before.stash
+        {
+            self.restore_stash(repo, oid(stash)?)?;
+        }
+        Ok(())
+    }
+
+    fn ensure_no_pushed_commits_lost(&self, repo: &Repository) -> Result<(), String> {
+        let Some(after_head) = &self.after.head else {
+            return Ok(());
+        };
+        if self.before.head.as_ref() == Some(after_head) {
+            return Ok(());
+        }
+
+        let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+        revwalk.push(oid(after_head)?).map_err(|e| e.to_string())?;
+        if let Some(before_head) = &self.before.head {
+            revwalk.hide(oid(before_head)?).map_err(|e| e.to_string())?;
+        }
+
+        let known_tips = self
+            .remote_tips
+            .iter()
+            .map(|tip| oid(tip))
+            .collect::<Result<Vec<_>, _>>()?;
+        let current_tips = remote_tips(repo);
+        for commit in revwalk {
+            let commit = commit.map_err(|e| e.to_string())?;
+            if reaches(repo, &current_tips, commit) && !reaches(repo, &known_tips, commit) {
+                return Err(format!(
+                    "commit {} was pushed since, undoing would drop it from the branch",
+                    &commit.to_string()[..7]
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Put back a stash the operation popped
+    fn restore_stash(&self, repo: &mut Repository, stash: Oid) -> Result<(), String> {
+        let mut already_listed = false;
+        repo.stash_foreach(|_, _, oid| {
+            already_listed |= *oid == stash;
+            !already_listed
+        })
+        .map_err(|e| e.to_string())?;
+        if already_listed {
+            return Ok(());
+        }
+
+        let message = repo
+            .find_commit(stash)
+            .ok()
+            .and_then(|commit| commit.message().map(str::to_string))
+            .unwrap_or_else(|| "bgit undo".to_string());
+        repo.reference("refs/stash", stash, true, &message)
+            .map_err(|e| e.to_string())?;
+
+        // `git stash list` reads the reflog, which is not written for a new refs/stash
+        let mut reflog = repo.reflog("refs/stash").map_err(|e| e.to_string())?;
+        if reflog.get(0).map(|entry| entry.id_new()) != Some(stash) {
+            let signature = repo
+                .signature()
+                .or_else(|_| Signature::now("bgit", "bgit@localhost"))
+                .map_err(|e| e.to_string())?;
+            reflog
+                .append(stash, &signature, Some(&message))
+                .map_err(|e| e.to_string())?;
+            reflog.write().map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+fn oplog_path() -> Option<PathBuf> {
+    bgit_state_dir().map(|dir| dir.join(OPLOG_FILE))
+}
+
+/// Log `event` as undoable if it changed anything since `before` was taken
+pub(crate) fn append(event: &str, command: Option<String>, scope: UndoScope, before: Snapshot) {
+    let Ok(repo) = Repository::discover(Path::new(".")) else {
+        return;
+    };
+    let after = match Snapshot::of(&repo, scope) {
+        Ok(after) => after,
+        Err(e) => {
+            warn!("Failed to snapshot the repository after {event}: {e}");
+            return;
+        }
+    };
+    if after.matches(&before) && after.stash == before.stash {
+        debug!("{event} changed nothing, not logged for undo");
+        return;
+    }
+
+    let operation = Operation {
+        event: event.to_string(),
+        command,
+        at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        scope,
+        before,
+        after,
+        remote_tips: remote_tips(&repo).iter().map(Oid::to_string).collect(),
+    };
+    let Some(path) = oplog_path() else {
+        return;
+    };
+    if let Err(e) = write_entry(&path, &operation) {
+        warn!("Failed to append to {}: {e}", path.display());
+    }
+}
+
+fn write_entry(path: &Path, operation: &Operation) -> Result<(), String> {
+    let line = serde_json::to_string(operation).map_err(|e| e.to_string())?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    writeln!(file, "{line}").map_err(|e| e.to_string())?;
+
+    let operations = load_from(path)?;
+    if operations.len() > MAX_OPLOG_ENTRIES {
+        save_to(path, &operations[operations.len() - MAX_OPLOG_ENTRIES..])?;
+    }
+    Ok(())
+}
+
+fn load_from(path: &Path) -> Result<Vec<Operation>, String> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|e| e.to_string()))
+        .collect()
+}
+
+fn save_to(path: &Path, operations: &[Operation]) -> Result<(), String> {
+    let mut content = String::new();
+    for operation in operations {
+        content.push_str(&serde_json::to_string(operation).map_err(|e| e.to_string())?);
+        content.push('\n');
+    }
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// Logged operations of this repository, oldest first
+pub(crate) fn load() -> Result<Vec<Operation>, String> {
+    oplog_path().map_or(Ok(vec![]), |path| load_from(&path))
+}
+
+/// Replace the log, e.g. with the undone operations removed
+pub(crate) fn save(operations: &[Operation]) -> Result<(), String> {
+    match oplog_path() {
+        Some(path) => save_to(&path, operations),
+        None => Err("not inside a git repository".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::commit_all;
+    use tempfile::TempDir;
+
+    fn operation(repo: &Repository, scope: UndoScope, before: Snapshot) -> Operation {
+        Operation {
+            event: "test".to_string(),
+            command: None,
+            at: 0,
+            scope,
+            before,
+            after: Snapshot::of(repo, scope).unwrap(),
+            remote_tips: vec![],
+        }
+    }
+
+    #[test]
+    fn test_undoes_commit_and_restore() {
+        let dir = TempDir::new().unwrap();
+        let mut repo = Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        let initial = commit_all(&repo, "initial");
+
+        fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        let before = Snapshot::of(&repo, UndoScope::Index).unwrap();
+        commit_all(&repo, "second");
+        let commit = operation(&repo, UndoScope::Index, before);
+
+        commit.undo(&mut repo).unwrap();
+        assert_eq!(repo.head().unwrap().target(), Some(initial));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "two\n"
+        );
+        assert!(
+            commit.undo(&mut repo).is_err(),
+            "an undone operation no longer matches the repository"
+        );
+
+        // Discarding the change can be undone as well, as the working tree was snapshotted
+        let before = Snapshot::of(&repo, UndoScope::WorkingTree).unwrap();
+        repo.checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+        let restore = operation(&repo, UndoScope::WorkingTree, before);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "one\n"
+        );
+
+        restore.undo(&mut repo).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "two\n"
+        );
+    }
+
+    #[test]
+    fn test_refuses_to_drop_commits_pushed_since() {
+        let dir = TempDir::new().unwrap();
+        let mut repo = Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        commit_all(&repo, "initial");
+
+        fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        let before = Snapshot::of(&repo, UndoScope::Index).unwrap();
+        let pushed = commit_all(&repo, "second");
+        let commit = operation(&repo, UndoScope::Index, before);
+
+        repo.reference("refs/remotes/origin/main", pushed, true, "push")
+            .unwrap();
+        let error = commit.undo(&mut repo).unwrap_err();
+        assert!(error.contains("was pushed since"), "{error}");
+        assert_eq!(repo.head().unwrap().target(), Some(pushed));
+    }
+}
@@ -0,0 +1,80 @@
+use clap::ValueEnum;
+use log::warn;
+use serde_json::json;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+use crate::bgit_error::BGitError;
+use crate::trace;
+
+/// How `bgit` reports the outcome of a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Human-readable progress and colored errors
+    #[default]
+    Text,
+    /// A single JSON document on stdout, progress moves to stderr
+    Json,
+}
+
+/// Set under `--output json`: where the JSON document goes. Everything else bgit prints
+/// to stdout is sent to stderr instead, so stdout stays parseable.
+static JSON_OUT: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+pub(crate) fn set_format(format: OutputFormat) {
+    if format == OutputFormat::Json
+        && let Ok(mut out) = JSON_OUT.lock()
+    {
+        *out = Some(redirect_stdout_to_stderr());
+    }
+}
+
+#[cfg(unix)]
+fn redirect_stdout_to_stderr() -> Box<dyn Write + Send> {
+    use std::fs::File;
+    use std::os::fd::{AsRawFd, FromRawFd};
+
+    // SAFETY: `dup` hands back a new descriptor this process owns, which the returned
+    // `File` takes over; `dup2` only repoints descriptor 1, nothing holds it as a `File`.
+    unsafe {
+        let saved = libc::dup(io::stdout().as_raw_fd());
+        if saved < 0 || libc::dup2(io::stderr().as_raw_fd(), io::stdout().as_raw_fd()) < 0 {
+            warn!("Failed to redirect stdout, progress output will mix with the JSON result");
+            return Box::new(io::stdout());
+        }
+        Box::new(File::from_raw_fd(saved))
+    }
+}
+
+#[cfg(not(unix))]
+fn redirect_stdout_to_stderr() -> Box<dyn Write + Send> {
+    warn!("--output json cannot separate progress from the result on this platform");
+    Box::new(io::stdout())
+}
+
+/// Report how `command` ended, as JSON or as a colored error, and return the exit code
+pub(crate) fn report(command: &str, result: &Result<(), Box<BGitError>>) -> i32 {
+    let exit_code = match result {
+        Ok(()) => 0,
+        Err(error) => error.exit_code(),
+    };
+
+    let Some(mut out) = JSON_OUT.lock().ok().and_then(|mut out| out.take()) else {
+        if let Err(error) = result {
+            error.print_error();
+        }
+        return exit_code;
+    };
+
+    let document = json!({
+        "command": command,
+        "status": if result.is_ok() { "ok" } else { "error" },
+        "exit_code": exit_code,
+        "error": result.as_ref().err(),
+        "workflow": trace::take_last(),
+    });
+    if let Err(e) = writeln!(out, "{document:#}").and_then(|_| out.flush()) {
+        warn!("Failed to write the JSON result: {e}");
+    }
+    exit_code
+}
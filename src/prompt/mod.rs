@@ -0,0 +1,163 @@
+mod scripted;
+mod terminal;
+
+pub(crate) use scripted::{Answers, ScriptedPrompter};
+pub(crate) use terminal::TerminalPrompter;
+
+use std::cell::RefCell;
+use std::fmt;
+
+thread_local! {
+    /// Backend answering every question bgit asks; the terminal unless replaced
+    static PROMPTER: RefCell<Box<dyn Prompter>> = RefCell::new(Box::new(TerminalPrompter));
+}
+
+/// Where answers to bgit's questions come from.
+///
+/// `key` identifies the question independently of its wording: the asking step's name,
+/// `<step>_<question>` for follow-ups within a step, or `<section>.<key>` for questions
+/// asked outside workflow steps (e.g. `auth.https_token`).
+pub(crate) trait Prompter {
+    fn select(
+        &mut self,
+        key: &str,
+        prompt: &str,
+        items: &[String],
+        default: usize,
+    ) -> Result<usize, PromptError>;
+
+    fn multi_select(
+        &mut self,
+        key: &str,
+        prompt: &str,
+        items: &[String],
+        defaults: &[bool],
+    ) -> Result<Vec<usize>, PromptError>;
+
+    fn confirm(&mut self, key: &str, prompt: &str, default: bool) -> Result<bool, PromptError>;
+
+    fn input(
+        &mut self,
+        key: &str,
+        prompt: &str,
+        default: Option<&str>,
+    ) -> Result<String, PromptError>;
+
+    /// Secret input, asked twice when `confirmation` holds the repeat and mismatch prompts
+    fn password(
+        &mut self,
+        key: &str,
+        prompt: &str,
+        allow_empty: bool,
+        confirmation: Option<(&str, &str)>,
+    ) -> Result<String, PromptError>;
+
+    /// Whether a person is answering, so callers can avoid tools that read the terminal
+    fn is_interactive(&self) -> bool;
+}
+
+/// Replace the backend for the rest of the run (on this thread)
+pub(crate) fn set_prompter(prompter: Box<dyn Prompter>) {
+    PROMPTER.with(|slot| *slot.borrow_mut() = prompter);
+}
+
+fn with_prompter<R>(f: impl FnOnce(&mut dyn Prompter) -> R) -> R {
+    PROMPTER.with(|slot| f(slot.borrow_mut().as_mut()))
+}
+
+#[derive(Debug)]
+pub(crate) struct PromptError(String);
+
+impl fmt::Display for PromptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for PromptError {}
+
+impl From<dialoguer::Error> for PromptError {
+    fn from(error: dialoguer::Error) -> Self {
+        PromptError(error.to_string())
+    }
+}
+
+fn labels<T: fmt::Display>(items: &[T]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+pub(crate) fn is_interactive() -> bool {
+    with_prompter(|p| p.is_interactive())
+}
+
+pub(crate) fn select<T: fmt::Display>(
+    key: &str,
+    prompt: &str,
+    items: &[T],
+    default: usize,
+) -> Result<usize, PromptError> {
+    let items = labels(items);
+    with_prompter(|p| p.select(key, prompt, &items, default))
+}
+
+pub(crate) fn multi_select<T: fmt::Display>(
+    key: &str,
+    prompt: &str,
+    items: &[T],
+    defaults: &[bool],
+) -> Result<Vec<usize>, PromptError> {
+    let items = labels(items);
+    with_prompter(|p| p.multi_select(key, prompt, &items, defaults))
+}
+
+pub(crate) fn confirm(key: &str, prompt: &str, default: bool) -> Result<bool, PromptError> {
+    with_prompter(|p| p.confirm(key, prompt, default))
+}
+
+pub(crate) fn input(key: &str, prompt: &str, default: Option<&str>) -> Result<String, PromptError> {
+    with_prompter(|p| p.input(key, prompt, default))
+}
+
+pub(crate) fn password(key: &str, prompt: &str, allow_empty: bool) -> Result<String, PromptError> {
+    with_prompter(|p| p.password(key, prompt, allow_empty, None))
+}
+
+/// Like [`password`], asking twice interactively when `confirmation` is given
+pub(crate) fn new_password(
+    key: &str,
+    prompt: &str,
+    allow_empty: bool,
+    confirmation: Option<(&str, &str)>,
+) -> Result<String, PromptError> {
+    with_prompter(|p| p.password(key, prompt, allow_empty, confirmation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::global::BGitGlobalConfig;
+    use crate::step::{ActionStep, PromptStep, Step, Task};
+    use crate::workflows::default::action::ta08_is_pulled_pushed::IsPushedPulled;
+    use crate::workflows::default::prompt::pa08_ask_commit::AskCommit;
+    use crate::workflows::default::prompt::pa11_ask_ai_commit_msg::AskAICommitMessage;
+
+    #[test]
+    fn test_prompt_steps_ask_the_installed_prompter() {
+        let global_config = BGitGlobalConfig::default();
+
+        set_prompter(Box::new(ScriptedPrompter::defaults_only()));
+        let next = AskCommit::new()
+            .execute(None, None, &global_config)
+            .unwrap();
+        assert!(next == Step::Task(Task::PromptStepTask(Box::new(AskAICommitMessage::new()))));
+
+        set_prompter(Box::new(
+            ScriptedPrompter::defaults_only().with_answer("ask_commit", "No"),
+        ));
+        let next = AskCommit::new()
+            .execute(None, None, &global_config)
+            .unwrap();
+        assert!(next == Step::Task(Task::ActionStepTask(Box::new(IsPushedPulled::new()))));
+        assert!(!is_interactive());
+    }
+}
@@ -0,0 +1,347 @@
+use log::debug;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::{PromptError, Prompter};
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
+
+/// Answers for a non-interactive run, keyed by question key
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Answers {
+    values: HashMap<String, Value>,
+}
+
+impl Answers {
+    /// Load an answers file, JSON when the extension says so and TOML otherwise
+    pub fn from_file(path: &Path) -> Result<Self, Box<BGitError>> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            to_answers_error(&format!(
+                "Failed to read answers file {}: {e}",
+                path.display()
+            ))
+        })?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        Self::parse(&content, is_json).map_err(|e| {
+            to_answers_error(&format!(
+                "Failed to parse answers file {}: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    pub fn parse(content: &str, is_json: bool) -> Result<Self, String> {
+        let root: Value = if is_json {
+            serde_json::from_str(content).map_err(|e| e.to_string())?
+        } else {
+            let table: toml::Table = toml::from_str(content).map_err(|e| e.to_string())?;
+            serde_json::to_value(table).map_err(|e| e.to_string())?
+        };
+        let Value::Object(_) = root else {
+            return Err("expected a table of answers keyed by step name".to_string());
+        };
+
+        let mut values = HashMap::new();
+        flatten("", root, &mut values);
+        Ok(Answers { values })
+    }
+
+    fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+}
+
+/// Nested tables become dotted keys so `[auth] https_token = ".."` answers `auth.https_token`
+fn flatten(prefix: &str, value: Value, out: &mut HashMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&key, value, out);
+            }
+        }
+        value => {
+            out.insert(prefix.to_string(), value);
+        }
+    }
+}
+
+/// Answers every question from [`Answers`], falling back to the question's default and
+/// failing when there is none. Never touches the terminal.
+#[derive(Debug, Default)]
+pub(crate) struct ScriptedPrompter {
+    answers: Answers,
+}
+
+impl ScriptedPrompter {
+    pub fn new(answers: Answers) -> Self {
+        ScriptedPrompter { answers }
+    }
+
+    /// Prompter that takes every default (`--yes`)
+    pub fn defaults_only() -> Self {
+        ScriptedPrompter::default()
+    }
+
+    #[cfg(test)]
+    pub fn with_answer(mut self, key: &str, answer: impl Into<Value>) -> Self {
+        self.answers.values.insert(key.to_string(), answer.into());
+        self
+    }
+
+    fn answer(&self, key: &str) -> Option<Value> {
+        self.answers.get(key).cloned()
+    }
+}
+
+impl Prompter for ScriptedPrompter {
+    fn select(
+        &mut self,
+        key: &str,
+        prompt: &str,
+        items: &[String],
+        default: usize,
+    ) -> Result<usize, PromptError> {
+        let answer = self.answer(key);
+        let index = match &answer {
+            Some(value) => match_item(value, items).ok_or_else(|| {
+                invalid_answer(key, value, &format!("one of {}", item_labels(items)))
+            })?,
+            None => default,
+        };
+        echo(prompt, &items[index], answer.is_none());
+        Ok(index)
+    }
+
+    fn multi_select(
+        &mut self,
+        key: &str,
+        prompt: &str,
+        items: &[String],
+        defaults: &[bool],
+    ) -> Result<Vec<usize>, PromptError> {
+        let answer = self.answer(key);
+        let selections: Vec<usize> = match &answer {
+            Some(Value::Array(values)) => values
+                .iter()
+                .map(|value| {
+                    match_item(value, items).ok_or_else(|| {
+                        invalid_answer(key, value, &format!("items of {}", item_labels(items)))
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+            Some(Value::String(s)) if s.eq_ignore_ascii_case("all") => (0..items.len()).collect(),
+            Some(value) => vec![match_item(value, items).ok_or_else(|| {
+                invalid_answer(key, value, &format!("a list of {}", item_labels(items)))
+            })?],
+            None => (0..items.len())
+                .filter(|&i| defaults.get(i).copied().unwrap_or(false))
+                .collect(),
+        };
+        let chosen: Vec<&str> = selections.iter().map(|&i| items[i].as_str()).collect();
+        echo(prompt, &chosen.join(", "), answer.is_none());
+        Ok(selections)
+    }
+
+    fn confirm(&mut self, key: &str, prompt: &str, default: bool) -> Result<bool, PromptError> {
+        let answer = self.answer(key);
+        let confirmed = match &answer {
+            Some(Value::Bool(b)) => *b,
+            Some(value @ Value::String(s)) => match s.trim().to_lowercase().as_str() {
+                "y" | "yes" | "true" => true,
+                "n" | "no" | "false" => false,
+                _ => return Err(invalid_answer(key, value, "yes or no")),
+            },
+            Some(value) => return Err(invalid_answer(key, value, "yes or no")),
+            None => default,
+        };
+        echo(
+            prompt,
+            if confirmed { "yes" } else { "no" },
+            answer.is_none(),
+        );
+        Ok(confirmed)
+    }
+
+    fn input(
+        &mut self,
+        key: &str,
+        prompt: &str,
+        default: Option<&str>,
+    ) -> Result<String, PromptError> {
+        let answer = self.answer(key);
+        let text = match &answer {
+            Some(Value::String(s)) => s.clone(),
+            Some(value @ (Value::Number(_) | Value::Bool(_))) => value.to_string(),
+            Some(value) => return Err(invalid_answer(key, value, "a string")),
+            None => default
+                .map(str::to_string)
+                .ok_or_else(|| missing_answer(key, prompt))?,
+        };
+        echo(prompt, &text, answer.is_none());
+        Ok(text)
+    }
+
+    /// Scripted secrets are never echoed and there is no default to fall back to
+    fn password(
+        &mut self,
+        key: &str,
+        prompt: &str,
+        _allow_empty: bool,
+        _confirmation: Option<(&str, &str)>,
+    ) -> Result<String, PromptError> {
+        match self.answer(key) {
+            Some(Value::String(s)) => {
+                debug!("Using scripted answer for '{key}'");
+                Ok(s)
+            }
+            Some(value) => Err(invalid_answer(key, &value, "a string")),
+            None => Err(missing_answer(key, prompt)),
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+fn missing_answer(key: &str, prompt: &str) -> PromptError {
+    PromptError(format!(
+        "No answer for '{key}' (\"{prompt}\") and it has no default; add `{key}` to the answers file"
+    ))
+}
+
+fn invalid_answer(key: &str, answer: &Value, expected: &str) -> PromptError {
+    PromptError(format!(
+        "Invalid answer {answer} for '{key}', expected {expected}"
+    ))
+}
+
+fn echo(prompt: &str, answer: &str, from_default: bool) {
+    let source = if from_default { " (default)" } else { "" };
+    println!("{prompt} › {answer}{source}");
+}
+
+/// Find the item an answer refers to: its index, its label, or the label's first word
+/// (so a file path answers `src/main.rs (modified)`, and `true`/`false` answer Yes/No)
+fn match_item(answer: &Value, items: &[String]) -> Option<usize> {
+    let wanted = match answer {
+        Value::Number(n) => return n.as_u64().map(|i| i as usize).filter(|&i| i < items.len()),
+        Value::Bool(true) => "yes".to_string(),
+        Value::Bool(false) => "no".to_string(),
+        Value::String(s) => s.trim().to_lowercase(),
+        _ => return None,
+    };
+    items.iter().position(|item| {
+        let label = item.to_lowercase();
+        label == wanted
+            || label
+                .strip_prefix(&wanted)
+                .is_some_and(|rest| rest.starts_with([' ', ',']))
+    })
+}
+
+fn item_labels(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|item| format!("\"{item}\""))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn to_answers_error(message: &str) -> Box<BGitError> {
+    Box::new(BGitError::new(
+        "Answers Error",
+        message,
+        BGitErrorWorkflowType::Config,
+        NO_STEP,
+        NO_EVENT,
+        NO_RULE,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_flattens_toml_and_json() {
+        let toml = Answers::parse(
+            "ask_commit = \"Yes\"\nask_if_same_feat = 1\n[auth]\nhttps_token = \"t0k\"\n",
+            false,
+        )
+        .unwrap();
+        assert_eq!(toml.get("ask_commit"), Some(&Value::from("Yes")));
+        assert_eq!(toml.get("ask_if_same_feat"), Some(&Value::from(1)));
+        assert_eq!(toml.get("auth.https_token"), Some(&Value::from("t0k")));
+
+        let json = Answers::parse(r#"{"ask_to_restore": ["Unstage all files"]}"#, true).unwrap();
+        assert!(json.get("ask_to_restore").unwrap().is_array());
+
+        assert!(Answers::parse("[1, 2]", true).is_err());
+    }
+
+    #[test]
+    fn test_match_item_by_index_label_and_bool() {
+        let yes_no = items(&["Yes", "No"]);
+        assert_eq!(match_item(&Value::from(1), &yes_no), Some(1));
+        assert_eq!(match_item(&Value::from(2), &yes_no), None);
+        assert_eq!(match_item(&Value::from("no"), &yes_no), Some(1));
+        assert_eq!(match_item(&Value::from(true), &yes_no), Some(0));
+
+        let files = items(&["src/main.rs (modified)", "src/main.rs.bak (new)"]);
+        assert_eq!(match_item(&Value::from("src/main.rs"), &files), Some(0));
+        let ai = items(&[
+            "Yes, generate AI commit message",
+            "No, I'll write it myself",
+        ]);
+        assert_eq!(match_item(&Value::from(false), &ai), Some(1));
+    }
+
+    #[test]
+    fn test_falls_back_to_defaults_and_fails_without_one() {
+        let mut prompter = ScriptedPrompter::defaults_only().with_answer("ask_branch_name", "fix");
+
+        assert_eq!(
+            prompter
+                .input("ask_branch_name", "Enter branch name", None)
+                .unwrap(),
+            "fix"
+        );
+        assert_eq!(
+            prompter
+                .select(
+                    "ask_if_same_feat",
+                    "Same feature?",
+                    &items(&["Yes", "No"]),
+                    1
+                )
+                .unwrap(),
+            1
+        );
+        let error = prompter
+            .input(
+                "ask_human_commit_message",
+                "Enter your commit message",
+                None,
+            )
+            .unwrap_err();
+        assert!(error.to_string().contains("ask_human_commit_message"));
+        assert!(
+            prompter
+                .password("auth.https_token", "Token", false, None)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use dialoguer::{Confirm, Input, MultiSelect, Password, Select, theme::ColorfulTheme};
+
+use super::{PromptError, Prompter};
+
+/// Asks the person at the terminal through dialoguer
+pub(crate) struct TerminalPrompter;
+
+impl Prompter for TerminalPrompter {
+    fn select(
+        &mut self,
+        _key: &str,
+        prompt: &str,
+        items: &[String],
+        default: usize,
+    ) -> Result<usize, PromptError> {
+        Ok(Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(items)
+            .default(default)
+            .interact()?)
+    }
+
+    fn multi_select(
+        &mut self,
+        _key: &str,
+        prompt: &str,
+        items: &[String],
+        defaults: &[bool],
+    ) -> Result<Vec<usize>, PromptError> {
+        Ok(MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .items(items)
+            .defaults(defaults)
+            .interact()?)
+    }
+
+    fn confirm(&mut self, _key: &str, prompt: &str, default: bool) -> Result<bool, PromptError> {
+        Ok(Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .default(default)
+            .interact()?)
+    }
+
+    fn input(
+        &mut self,
+        _key: &str,
+        prompt: &str,
+        default: Option<&str>,
+    ) -> Result<String, PromptError> {
+        let theme = ColorfulTheme::default();
+        let mut input = Input::<String>::with_theme(&theme).with_prompt(prompt);
+        if let Some(default) = default {
+            input = input.default(default.to_string());
+        }
+        Ok(input.interact_text()?)
+    }
+
+    fn password(
+        &mut self,
+        _key: &str,
+        prompt: &str,
+        allow_empty: bool,
+        confirmation: Option<(&str, &str)>,
+    ) -> Result<String, PromptError> {
+        let theme = ColorfulTheme::default();
+        let mut password = Password::with_theme(&theme)
+            .with_prompt(prompt)
+            .allow_empty_password(allow_empty);
+        if let Some((repeat, mismatch)) = confirmation {
+            password = password.with_confirmation(repeat, mismatch);
+        }
+        Ok(password.interact()?)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,262 @@
+use git2::{
+    BranchType, Oid, Repository, ResetType, Signature, StashApplyOptions, StashFlags,
+    StatusOptions, build::CheckoutBuilder,
+};
+use log::debug;
+use std::fmt;
+use std::sync::Mutex;
+
+/// Changes the running workflow made, oldest first, each with how to undo it. `None`
+/// outside a workflow, so events run on their own (e.g. by `bgit log`) are not journaled.
+static JOURNAL: Mutex<Option<Vec<Compensation>>> = Mutex::new(None);
+
+/// How to undo one change an event made
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Compensation {
+    /// Put the index back to the tree it had before `git_add` staged files
+    RestoreIndex { tree: Oid },
+    /// Move the branch back to `parent`, keeping the changes of `commit` staged
+    UndoCommit { commit: Oid, parent: Oid },
+    /// Re-apply a stash bgit pushed and never popped
+    PopStash { stash: Oid },
+    /// Carry the working tree back to `branch`, deleting `created` when nothing was
+    /// committed on it
+    SwitchBack { branch: String, created: String },
+}
+
+impl fmt::Display for Compensation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compensation::RestoreIndex { .. } => write!(f, "unstage the files this run staged"),
+            Compensation::UndoCommit { commit, .. } => write!(
+                f,
+                "undo commit {}, keeping its changes staged",
+                &commit.to_string()[..7]
+            ),
+            Compensation::PopStash { .. } => write!(f, "pop the stash bgit created"),
+            Compensation::SwitchBack { branch, created } => write!(
+                f,
+                "switch back to '{branch}' with your changes and delete '{created}'"
+            ),
+        }
+    }
+}
+
+impl Compensation {
+    pub fn apply(&self, repo: &mut Repository) -> Result<(), String> {
+        match self {
+            Compensation::RestoreIndex { tree } => {
+                let tree = repo.find_tree(*tree).map_err(|e| e.to_string())?;
+                let mut index = repo.index().map_err(|e| e.to_string())?;
+                index.read_tree(&tree).map_err(|e| e.to_string())?;
+                index.write().map_err(|e| e.to_string())
+            }
+            Compensation::UndoCommit { commit, parent } => {
+                let head = repo
+                    .head()
+                    .and_then(|head| head.peel_to_commit())
+                    .map_err(|e| e.to_string())?;
+                if head.id() != *commit {
+                    return Err(format!(
+                        "HEAD moved past commit {commit} since, leaving it in place"
+                    ));
+                }
+                if is_pushed(repo, *commit) {
+                    return Err(format!("commit {commit} was already pushed"));
+                }
+                let parent = repo.find_object(*parent, None).map_err(|e| e.to_string())?;
+                repo.reset(&parent, ResetType::Soft, None)
+                    .map_err(|e| e.to_string())
+            }
+            Compensation::PopStash { stash } => {
+                let position = find_stash(repo, *stash)
+                    .ok_or_else(|| format!("stash {stash} no longer exists"))?;
+                pop_stash(repo, position)
+            }
+            Compensation::SwitchBack { branch, created } => {
+                let stashed = if has_tracked_changes(repo)? {
+                    let signature = repo.signature().or_else(|_| {
+                        Signature::now("bgit", "bgit@localhost").map_err(|e| e.to_string())
+                    })?;
+                    repo.stash_save(
+                        &signature,
+                        "bgit rollback: switching back",
+                        Some(StashFlags::DEFAULT),
+                    )
+                    .map_err(|e| e.to_string())?;
+                    true
+                } else {
+                    false
+                };
+
+                repo.set_head(&format!("refs/heads/{branch}"))
+                    .map_err(|e| e.to_string())?;
+                repo.checkout_head(Some(CheckoutBuilder::default().force()))
+                    .map_err(|e| e.to_string())?;
+                if stashed {
+                    pop_stash(repo, 0)?;
+                }
+
+                let original = repo
+                    .find_branch(branch, BranchType::Local)
+                    .ok()
+                    .and_then(|b| b.get().target());
+                if let Ok(mut new_branch) = repo.find_branch(created, BranchType::Local) {
+                    if new_branch.get().target() == original {
+                        new_branch.delete().map_err(|e| e.to_string())?;
+                    } else {
+                        debug!("Keeping branch '{created}', it has commits of its own");
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn pop_stash(repo: &mut Repository, position: usize) -> Result<(), String> {
+    let mut apply_options = StashApplyOptions::default();
+    apply_options.reinstantiate_index();
+    repo.stash_pop(position, Some(&mut apply_options))
+        .map_err(|e| e.to_string())
+}
+
+fn find_stash(repo: &mut Repository, stash: Oid) -> Option<usize> {
+    let mut position = None;
+    let _ = repo.stash_foreach(|index, _, oid| {
+        if *oid == stash {
+            position = Some(index);
+        }
+        position.is_none()
+    });
+    position
+}
+
+fn has_tracked_changes(repo: &Repository) -> Result<bool, String> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    repo.statuses(Some(&mut options))
+        .map(|statuses| !statuses.is_empty())
+        .map_err(|e| e.to_string())
+}
+
+/// Whether a remote-tracking branch already contains `commit`
+fn is_pushed(repo: &Repository, commit: Oid) -> bool {
+    let Ok(branches) = repo.branches(Some(BranchType::Remote)) else {
+        return false;
+    };
+    branches
+        .flatten()
+        .filter_map(|(branch, _)| branch.get().target())
+        .any(|tip| tip == commit || repo.graph_descendant_of(tip, commit).unwrap_or(false))
+}
+
+/// Start journaling the changes of a new workflow run
+pub(crate) fn begin() {
+    if let Ok(mut journal) = JOURNAL.lock() {
+        *journal = Some(vec![]);
+    }
+}
+
+/// Stop journaling, returning what the run changed, oldest first
+pub(crate) fn finish() -> Vec<Compensation> {
+    JOURNAL
+        .lock()
+        .ok()
+        .and_then(|mut journal| journal.take())
+        .unwrap_or_default()
+}
+
+pub(crate) fn record(compensation: Compensation) {
+    if let Ok(mut journal) = JOURNAL.lock()
+        && let Some(entries) = journal.as_mut()
+    {
+        debug!("Journaled: {compensation}");
+        entries.push(compensation);
+    }
+}
+
+/// Drop an entry whose change the event undid itself (e.g. a stash it popped again)
+pub(crate) fn forget(compensation: &Compensation) {
+    if let Ok(mut journal) = JOURNAL.lock()
+        && let Some(entries) = journal.as_mut()
+    {
+        entries.retain(|entry| entry != compensation);
+    }
+}
+
+/// Undo `changes` newest first, stopping at the first one that cannot be undone.
+/// Returns how many were undone, with the error that stopped it, if any.
+pub(crate) fn roll_back(
+    repo: &mut Repository,
+    changes: &[Compensation],
+) -> (usize, Option<String>) {
+    for (undone, change) in changes.iter().rev().enumerate() {
+        if let Err(e) = change.apply(repo) {
+            return (undone, Some(format!("Could not {change}: {e}")));
+        }
+        debug!("Rolled back: {change}");
+    }
+    (changes.len(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::commit_all;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rolls_back_add_and_commit() {
+        let dir = TempDir::new().unwrap();
+        let mut repo = Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join("README.md"), "# hello\n").unwrap();
+        let initial = commit_all(&repo, "initial");
+
+        let tree_before = repo.index().unwrap().write_tree().unwrap();
+        fs::write(dir.path().join("lib.rs"), "fn main() {}\n").unwrap();
+        let commit = commit_all(&repo, "add lib");
+
+        let changes = vec![
+            Compensation::RestoreIndex { tree: tree_before },
+            Compensation::UndoCommit {
+                commit,
+                parent: initial,
+            },
+        ];
+        assert_eq!(roll_back(&mut repo, &changes), (2, None));
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.id(), initial);
+        assert!(
+            repo.index()
+                .unwrap()
+                .get_path(Path::new("lib.rs"), 0)
+                .is_none()
+        );
+        assert!(dir.path().join("lib.rs").exists());
+    }
+
+    #[test]
+    fn test_keeps_commit_once_head_moved() {
+        let dir = TempDir::new().unwrap();
+        let mut repo = Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join("a.txt"), "a\n").unwrap();
+        let initial = commit_all(&repo, "initial");
+        fs::write(dir.path().join("b.txt"), "b\n").unwrap();
+        let commit = commit_all(&repo, "add b");
+        fs::write(dir.path().join("c.txt"), "c\n").unwrap();
+        let later = commit_all(&repo, "add c");
+
+        let changes = vec![Compensation::UndoCommit {
+            commit,
+            parent: initial,
+        }];
+        let (undone, error) = roll_back(&mut repo, &changes);
+        assert_eq!(undone, 0);
+        assert!(error.unwrap().contains("HEAD moved"));
+        assert_eq!(repo.head().unwrap().target(), Some(later));
+    }
+}
@@ -3,7 +3,21 @@ use serde::{Deserialize, Serialize};
 use crate::{
     bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_STEP},
     config::local::WorkflowRules,
+    trace::{self, RuleOutcome},
 };
+use a01_git_install::IsGitInstalledLocally;
+use a02_git_name_email_setup::GitNameEmailSetup;
+use a09_commit_gpg_sign::CommitGpgSign;
+use a10_git_default_config::GitDefaultConfig;
+use a11_git_remote_http_ssh::GitRemoteHttpSsh;
+use a12_no_secrets_staged::NoSecretsStaged;
+use a12b_no_secret_files_staged::NoSecretFilesStaged;
+use a13_git_lfs::GitLfs;
+use a14_big_repo_size::IsRepoSizeTooBig;
+use a15_file_not_gitignored::FileNotGitignored;
+use a16_no_large_file::NoLargeFile;
+use a17_conventional_commit_message::ConventionalCommitMessage;
+use a18_remote_exists::RemoteExists;
 
 pub(crate) mod a01_git_install;
 pub(crate) mod a02_git_name_email_setup;
@@ -13,13 +27,14 @@ mod a05_github_credentials_http;
 mod a06_gitlab_credentials_http;
 mod a07_github_credentials_ssh;
 mod a08_gitlab_credentials_ssh;
-mod a09_commit_gpg_sign;
-mod a11_git_remote_http_ssh;
+pub(crate) mod a09_commit_gpg_sign;
+pub(crate) mod a10_git_default_config;
+pub(crate) mod a11_git_remote_http_ssh;
 pub(crate) mod a12_no_secrets_staged;
 pub(crate) mod a12b_no_secret_files_staged;
-mod a13_git_lfs;
+pub(crate) mod a13_git_lfs;
 pub(crate) mod a14_big_repo_size;
-mod a15_file_not_gitignored;
+pub(crate) mod a15_file_not_gitignored;
 pub(crate) mod a16_no_large_file;
 pub(crate) mod a17_conventional_commit_message;
 pub(crate) mod a18_remote_exists;
@@ -41,6 +56,28 @@ pub(crate) enum RuleOutput {
     Success,
 }
 
+/// Instantiate every implemented rule, resolving levels from `workflow_rule_config`.
+/// Register new rules here as well, `bgit init` scaffolds the project config from this list.
+pub(crate) fn all_rules(
+    workflow_rule_config: Option<&WorkflowRules>,
+) -> Vec<Box<dyn Rule + Send + Sync>> {
+    vec![
+        Box::new(IsGitInstalledLocally::new(workflow_rule_config)),
+        Box::new(GitNameEmailSetup::new(workflow_rule_config)),
+        Box::new(GitDefaultConfig::new(workflow_rule_config)),
+        Box::new(NoSecretsStaged::new(workflow_rule_config)),
+        Box::new(NoSecretFilesStaged::new(workflow_rule_config)),
+        Box::new(FileNotGitignored::new(workflow_rule_config)),
+        Box::new(IsRepoSizeTooBig::new(workflow_rule_config)),
+        Box::new(NoLargeFile::new(workflow_rule_config)),
+        Box::new(GitLfs::new(workflow_rule_config)),
+        Box::new(ConventionalCommitMessage::new(workflow_rule_config)),
+        Box::new(CommitGpgSign::new(workflow_rule_config)),
+        Box::new(RemoteExists::new(workflow_rule_config)),
+        Box::new(GitRemoteHttpSsh::new(workflow_rule_config)),
+    ]
+}
+
 /// Sample struct for Rule
 /// pub(crate) struct SampleRule {
 ///     name: String,
@@ -86,21 +123,28 @@ pub(crate) trait Rule {
 
     fn execute(&self) -> Result<bool, Box<BGitError>> {
         if self.get_level() == RuleLevel::Skip {
+            self.record(RuleOutcome::Skipped, "");
             return Ok(true);
         }
         let check_report = self.check()?;
         match check_report {
-            RuleOutput::Success => Ok(true),
+            RuleOutput::Success => {
+                self.record(RuleOutcome::Passed, "");
+                Ok(true)
+            }
             RuleOutput::Exception(exception) => {
                 let fix_report = self.try_fix()?;
                 if self.get_level() == RuleLevel::Warning {
                     // No need to verify as it's a warning level!
+                    self.record(RuleOutcome::Warned, &exception);
                     Ok(true)
                 } else if fix_report {
                     let verify_report = self.verify()?;
                     if verify_report {
+                        self.record(RuleOutcome::Fixed, &exception);
                         Ok(true)
                     } else {
+                        self.record(RuleOutcome::Failed, &exception);
                         Err(Box::new(BGitError::new(
                             "Failed to verify the rule",
                             &exception,
@@ -111,6 +155,7 @@ pub(crate) trait Rule {
                         )))
                     }
                 } else {
+                    self.record(RuleOutcome::Failed, &exception);
                     Err(Box::new(BGitError::new(
                         "Failed to fix the rule",
                         &exception,
@@ -124,6 +169,11 @@ pub(crate) trait Rule {
         }
     }
 
+    /// Add this rule's outcome to the workflow trace
+    fn record(&self, outcome: RuleOutcome, message: &str) {
+        trace::rule_evaluated(self.get_name(), self.get_level(), outcome, message);
+    }
+
     fn verify(&self) -> Result<bool, Box<BGitError>> {
         match self.check()? {
             RuleOutput::Success => Ok(true),
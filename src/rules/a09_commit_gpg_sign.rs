@@ -1 +1,136 @@
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_STEP};
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::WorkflowRules;
+use crate::events::AtomicEvent;
+use crate::events::git_config::{ConfigOperation, ConfigScope, GitConfig};
+use crate::prompt;
+use crate::rules::{Rule, RuleLevel, RuleOutput};
+use crate::signing::{self, CommitSigner, SigningFormat};
+use git2::{Config, Repository};
 
+pub(crate) struct CommitGpgSign {
+    name: String,
+    description: String,
+    level: RuleLevel,
+}
+
+impl Rule for CommitGpgSign {
+    fn new(workflow_rule_config: Option<&WorkflowRules>) -> Self {
+        // Opt-in: most contributors have no signing key, so teams that require signed
+        // commits raise this to Error in .bgit/config.toml
+        let default_rule_level = RuleLevel::Skip;
+        let name = "CommitGpgSign";
+        let rule_level = workflow_rule_config
+            .and_then(|config| config.get_rule_level(name))
+            .cloned()
+            .unwrap_or(default_rule_level);
+
+        Self {
+            name: name.to_string(),
+            description: "Ensure commits are signed with a GPG or SSH key".to_string(),
+            level: rule_level,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_description(&self) -> &str {
+        &self.description
+    }
+
+    fn get_level(&self) -> RuleLevel {
+        self.level.clone()
+    }
+
+    fn check(&self) -> Result<RuleOutput, Box<BGitError>> {
+        // The repository's config includes the global one, and may override it
+        let config = match Repository::discover(".")
+            .and_then(|repo| repo.config())
+            .or_else(|_| Config::open_default())
+        {
+            Ok(config) => config,
+            Err(e) => {
+                return Ok(RuleOutput::Exception(format!(
+                    "Failed to open Git config: {e}"
+                )));
+            }
+        };
+
+        match CommitSigner::for_commits(&config) {
+            Ok(None) => Ok(RuleOutput::Exception(
+                "commit.gpgSign is not enabled, commits will not be signed".to_string(),
+            )),
+            Ok(Some(signer)) => match signer.check_ready() {
+                Ok(()) => Ok(RuleOutput::Success),
+                Err(e) => Ok(RuleOutput::Exception(e)),
+            },
+            Err(e) => Ok(RuleOutput::Exception(e)),
+        }
+    }
+
+    fn try_fix(&self) -> Result<bool, Box<BGitError>> {
+        let keys = signing::available_keys();
+        if keys.is_empty() {
+            println!("No SSH or GPG key found to sign commits with. Create one, then run:");
+            println!("  git config --global gpg.format ssh");
+            println!("  git config --global user.signingKey ~/.ssh/id_ed25519.pub");
+            println!("  git config --global commit.gpgSign true");
+            return Ok(false);
+        }
+
+        let mut choices: Vec<String> = keys
+            .iter()
+            .map(|(format, key)| match format {
+                SigningFormat::Ssh => format!("SSH key {key}"),
+                SigningFormat::OpenPgp => format!("GPG key {key}"),
+            })
+            .collect();
+        choices.push("Don't sign commits".to_string());
+        let selected = prompt::select(
+            "commit_gpg_sign.key",
+            "Commits must be signed. Which key should sign them?",
+            &choices,
+            0,
+        )
+        .map_err(|e| self.to_fix_error(&format!("Failed to get user input: {e}")))?;
+        let Some((format, key)) = keys.get(selected) else {
+            return Ok(false);
+        };
+
+        let format_name = match format {
+            SigningFormat::Ssh => "ssh",
+            SigningFormat::OpenPgp => "openpgp",
+        };
+        let global_config = BGitGlobalConfig::default();
+        for (name, value) in [
+            ("gpg.format", format_name),
+            ("user.signingKey", key.as_str()),
+            ("commit.gpgSign", "true"),
+        ] {
+            GitConfig::new(&global_config)
+                .with_operation(ConfigOperation::Set)
+                .with_scope(ConfigScope::Global)
+                .with_key(name.to_string())
+                .with_value(value.to_string())
+                .execute()?;
+        }
+
+        println!("Commits will now be signed with {}", choices[selected]);
+        Ok(true)
+    }
+}
+
+impl CommitGpgSign {
+    fn to_fix_error(&self, message: &str) -> Box<BGitError> {
+        Box::new(BGitError::new(
+            "CommitGpgSign",
+            message,
+            BGitErrorWorkflowType::Rules,
+            NO_STEP,
+            NO_EVENT,
+            self.get_name(),
+        ))
+    }
+}
@@ -0,0 +1,280 @@
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_STEP};
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::WorkflowRules;
+use crate::events::AtomicEvent;
+use crate::events::git_config::{ConfigOperation, ConfigScope, GitConfig};
+use crate::prompt;
+use crate::rules::{Rule, RuleLevel, RuleOutput};
+use git2::{Config, Repository};
+
+/// Settings every contributor should have, before `[rules.<workflow>.params.GitDefaultConfig]`
+fn recommended_settings() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("init.defaultBranch", "main"),
+        ("pull.rebase", "false"),
+        (
+            "core.autocrlf",
+            if cfg!(windows) { "true" } else { "input" },
+        ),
+        ("fetch.prune", "true"),
+        ("push.autoSetupRemote", "true"),
+    ]
+}
+
+pub(crate) struct GitDefaultConfig {
+    name: String,
+    description: String,
+    level: RuleLevel,
+    /// Config key and expected value, in the order they are reported
+    baseline: Vec<(String, String)>,
+}
+
+impl Rule for GitDefaultConfig {
+    fn new(workflow_rule_config: Option<&WorkflowRules>) -> Self {
+        let default_rule_level = RuleLevel::Warning;
+        let name = "GitDefaultConfig";
+        let rule_level = workflow_rule_config
+            .and_then(|config| config.get_rule_level(name))
+            .cloned()
+            .unwrap_or(default_rule_level);
+
+        let mut baseline: Vec<(String, String)> = recommended_settings()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        if let Some(params) = workflow_rule_config.and_then(|config| config.get_rule_params(name)) {
+            let mut overrides: Vec<_> = params.params.iter().collect();
+            overrides.sort_by(|a, b| a.0.cmp(b.0));
+            for (key, value) in overrides {
+                let value = match value {
+                    serde_json::Value::String(value) => value.clone(),
+                    other => other.to_string(),
+                };
+                // Section and variable names are case-insensitive in git
+                let existing = baseline
+                    .iter()
+                    .position(|(known, _)| known.eq_ignore_ascii_case(key));
+                match (existing, value.is_empty()) {
+                    (Some(position), true) => {
+                        baseline.remove(position);
+                    }
+                    (Some(position), false) => baseline[position].1 = value,
+                    (None, false) => baseline.push((key.clone(), value)),
+                    (None, true) => {}
+                }
+            }
+        }
+
+        Self {
+            name: name.to_string(),
+            description: "Ensure recommended Git settings like init.defaultBranch are configured"
+                .to_string(),
+            level: rule_level,
+            baseline,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_description(&self) -> &str {
+        &self.description
+    }
+
+    fn get_level(&self) -> RuleLevel {
+        self.level.clone()
+    }
+
+    fn check(&self) -> Result<RuleOutput, Box<BGitError>> {
+        let config = match Self::open_config() {
+            Ok(config) => config,
+            Err(e) => {
+                return Ok(RuleOutput::Exception(format!(
+                    "Failed to open Git config: {e}"
+                )));
+            }
+        };
+
+        let mismatches = self.mismatches(&config);
+        if mismatches.is_empty() {
+            return Ok(RuleOutput::Success);
+        }
+        let report: Vec<String> = mismatches
+            .iter()
+            .map(|(key, expected, actual)| match actual {
+                Some(actual) => format!("{key} is '{actual}' (expected '{expected}')"),
+                None => format!("{key} is not set (expected '{expected}')"),
+            })
+            .collect();
+        Ok(RuleOutput::Exception(format!(
+            "Git settings differ from the recommended ones: {}",
+            report.join(", ")
+        )))
+    }
+
+    fn try_fix(&self) -> Result<bool, Box<BGitError>> {
+        let config = Self::open_config()
+            .map_err(|e| self.to_fix_error(&format!("Failed to open Git config: {e}")))?;
+        let mismatches = self.mismatches(&config);
+        if mismatches.is_empty() {
+            return Ok(true);
+        }
+
+        println!("These Git settings differ from the recommended ones:");
+        for (key, expected, actual) in &mismatches {
+            println!(
+                "  {key} = {expected} (currently {})",
+                actual.as_deref().unwrap_or("unset")
+            );
+        }
+        let choices = [
+            "Apply them globally, for all repositories",
+            "Apply them to this repository only",
+            "Leave them as they are",
+        ];
+        let selected = prompt::select(
+            "git_default_config.scope",
+            "Apply the recommended settings?",
+            &choices,
+            0,
+        )
+        .map_err(|e| self.to_fix_error(&format!("Failed to get user input: {e}")))?;
+        let scope = match selected {
+            0 => ConfigScope::Global,
+            1 if Repository::discover(".").is_ok() => ConfigScope::Local,
+            1 => {
+                println!("Not inside a Git repository, nothing was changed.");
+                return Ok(false);
+            }
+            _ => return Ok(false),
+        };
+
+        let global_config = BGitGlobalConfig::default();
+        for (key, expected, _) in mismatches {
+            GitConfig::new(&global_config)
+                .with_operation(ConfigOperation::Set)
+                .with_scope(scope.clone())
+                .with_key(key)
+                .with_value(expected)
+                .execute()?;
+        }
+        Ok(true)
+    }
+}
+
+impl GitDefaultConfig {
+    /// The config git itself would read here: the repository's, falling back to the
+    /// global one outside a repository
+    fn open_config() -> Result<Config, git2::Error> {
+        Repository::discover(".")
+            .and_then(|repo| repo.config())
+            .or_else(|_| Config::open_default())
+    }
+
+    /// Baseline entries `config` does not match, as (key, expected, actual)
+    fn mismatches(&self, config: &Config) -> Vec<(String, String, Option<String>)> {
+        self.baseline
+            .iter()
+            .filter_map(|(key, expected)| {
+                let actual = config.get_string(key).ok();
+                let matches = actual
+                    .as_deref()
+                    .is_some_and(|actual| normalize(actual) == normalize(expected));
+                (!matches).then(|| (key.clone(), expected.clone(), actual))
+            })
+            .collect()
+    }
+
+    fn to_fix_error(&self, message: &str) -> Box<BGitError> {
+        Box::new(BGitError::new(
+            "GitDefaultConfig",
+            message,
+            BGitErrorWorkflowType::Rules,
+            NO_STEP,
+            NO_EVENT,
+            self.get_name(),
+        ))
+    }
+}
+
+/// Fold the boolean spellings git accepts (`yes`, `on`, `1`, ...) together, so they
+/// compare equal
+fn normalize(value: &str) -> String {
+    let value = value.trim();
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => "true".to_string(),
+        "false" | "no" | "off" | "0" => "false".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_baseline_overrides_from_params() {
+        let rules: WorkflowRules = toml::from_str(
+            r#"
+GitDefaultConfig = "Error"
+[params.GitDefaultConfig]
+"init.defaultBranch" = "trunk"
+"pull.rebase" = true
+"fetch.prune" = ""
+"rerere.enabled" = true
+"#,
+        )
+        .unwrap();
+        let rule = GitDefaultConfig::new(Some(&rules));
+
+        assert_eq!(rule.get_level(), RuleLevel::Error);
+        let baseline: Vec<(&str, &str)> = rule
+            .baseline
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        assert!(baseline.contains(&("init.defaultBranch", "trunk")));
+        assert!(baseline.contains(&("pull.rebase", "true")));
+        assert!(baseline.contains(&("rerere.enabled", "true")));
+        assert!(!baseline.iter().any(|(key, _)| *key == "fetch.prune"));
+    }
+
+    #[test]
+    fn test_reports_only_mismatched_settings() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo
+            .config()
+            .unwrap()
+            .open_level(git2::ConfigLevel::Local)
+            .unwrap();
+        config.set_str("init.defaultBranch", "main").unwrap();
+        config.set_str("pull.rebase", "no").unwrap();
+        config.set_str("fetch.prune", "false").unwrap();
+
+        let rule = GitDefaultConfig {
+            baseline: vec![
+                ("init.defaultBranch".to_string(), "main".to_string()),
+                ("pull.rebase".to_string(), "false".to_string()),
+                ("fetch.prune".to_string(), "true".to_string()),
+                ("bgit.unsetForTest".to_string(), "true".to_string()),
+            ],
+            ..GitDefaultConfig::new(None)
+        };
+        let mismatches = rule.mismatches(&config);
+
+        assert_eq!(
+            mismatches,
+            vec![
+                (
+                    "fetch.prune".to_string(),
+                    "true".to_string(),
+                    Some("false".to_string())
+                ),
+                ("bgit.unsetForTest".to_string(), "true".to_string(), None),
+            ]
+        );
+    }
+}
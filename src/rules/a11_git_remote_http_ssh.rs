@@ -1 +1,353 @@
+use crate::auth::auth_utils::transform_url_for_preference;
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_STEP};
+use crate::config::global::{GlobalAuth, PreferredAuth};
+use crate::config::local::WorkflowRules;
+use crate::prompt;
+use crate::rules::{Rule, RuleLevel, RuleOutput};
+use git2::Repository;
+use log::warn;
+use std::fmt;
 
+/// Parameter of `[rules.<workflow>.params.GitRemoteHttpSsh]` holding "ssh" or "https"
+const REQUIRE_PARAM: &str = "require";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Transport {
+    Https,
+    Ssh,
+    Http,
+    Git,
+    /// Local paths and `file://` URLs, which have no scheme policy
+    Local,
+}
+
+impl Transport {
+    fn of(url: &str) -> Self {
+        let url = url.trim();
+        if url.starts_with("https://") {
+            Transport::Https
+        } else if url.starts_with("http://") {
+            Transport::Http
+        } else if url.starts_with("git://") {
+            Transport::Git
+        } else if ["ssh://", "git+ssh://", "ssh+git://"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme))
+            || Self::is_scp_like(url)
+        {
+            Transport::Ssh
+        } else {
+            Transport::Local
+        }
+    }
+
+    /// `user@host:path`; a single letter before the colon is a Windows drive instead
+    fn is_scp_like(url: &str) -> bool {
+        !url.contains("://")
+            && url
+                .split_once(':')
+                .is_some_and(|(host, _)| host.len() > 1 && !host.contains(['/', '\\']))
+    }
+}
+
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transport::Https => write!(f, "HTTPS"),
+            Transport::Ssh => write!(f, "SSH"),
+            Transport::Http => write!(f, "http://"),
+            Transport::Git => write!(f, "git://"),
+            Transport::Local => write!(f, "a local path"),
+        }
+    }
+}
+
+pub(crate) struct GitRemoteHttpSsh {
+    name: String,
+    description: String,
+    level: RuleLevel,
+    required_remote: String,
+    /// Transport the repository insists on, from the `require` parameter
+    required: Option<Transport>,
+    auth: GlobalAuth,
+}
+
+impl Rule for GitRemoteHttpSsh {
+    fn new(workflow_rule_config: Option<&WorkflowRules>) -> Self {
+        let default_rule_level = RuleLevel::Warning;
+        let name = "GitRemoteHttpSsh";
+        let rule_level = workflow_rule_config
+            .and_then(|config| config.get_rule_level(name))
+            .cloned()
+            .unwrap_or(default_rule_level);
+
+        let params = workflow_rule_config.and_then(|config| config.get_rule_params(name));
+        let required = match params.and_then(|params| params.params.get(REQUIRE_PARAM)) {
+            None => None,
+            Some(value) => match serde_json::from_value(value.clone()) {
+                Ok(PreferredAuth::Ssh) => Some(Transport::Ssh),
+                Ok(PreferredAuth::Https) => Some(Transport::Https),
+                Ok(PreferredAuth::RepositoryURLBased) => None,
+                Err(_) => {
+                    warn!(
+                        "Ignoring {name} parameter {REQUIRE_PARAM} = {value}, use \"ssh\" or \"https\""
+                    );
+                    None
+                }
+            },
+        };
+
+        Self {
+            name: name.to_string(),
+            description: "Ensure the remote uses a secure scheme matching the preferred auth"
+                .to_string(),
+            level: rule_level,
+            required_remote: "origin".to_string(),
+            required,
+            auth: GlobalAuth::default(),
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_description(&self) -> &str {
+        &self.description
+    }
+
+    fn get_level(&self) -> RuleLevel {
+        self.level.clone()
+    }
+
+    fn check(&self) -> Result<RuleOutput, Box<BGitError>> {
+        let problems: Vec<String> = self
+            .remote_urls()?
+            .iter()
+            .filter_map(|(kind, url)| {
+                self.violation(url)
+                    .map(|problem| format!("{kind} {url} {problem}"))
+            })
+            .collect();
+
+        if problems.is_empty() {
+            Ok(RuleOutput::Success)
+        } else {
+            Ok(RuleOutput::Exception(format!(
+                "Remote '{}': {}",
+                self.required_remote,
+                problems.join("; ")
+            )))
+        }
+    }
+
+    fn try_fix(&self) -> Result<bool, Box<BGitError>> {
+        let repo = Repository::discover(".")
+            .map_err(|e| self.to_rule_error(&format!("Failed to discover repository: {e}")))?;
+        let mut fixed_all = true;
+
+        for (kind, url) in self.remote_urls()? {
+            if self.violation(&url).is_none() {
+                continue;
+            }
+            let Some(new_url) = self.fixed_url(&url) else {
+                println!(
+                    "Cannot tell which URL {kind} {url} should use for remote '{}'. Set it with:",
+                    self.required_remote
+                );
+                println!("  git remote set-url origin <new-url>");
+                fixed_all = false;
+                continue;
+            };
+
+            let confirmed = prompt::confirm(
+                "git_remote_http_ssh.rewrite",
+                &format!(
+                    "Change the {kind} of remote '{}' to {new_url}?",
+                    self.required_remote
+                ),
+                true,
+            )
+            .map_err(|e| self.to_rule_error(&format!("Failed to get user input: {e}")))?;
+            if !confirmed {
+                fixed_all = false;
+                continue;
+            }
+
+            let updated = if kind == "push URL" {
+                repo.remote_set_pushurl(&self.required_remote, Some(&new_url))
+            } else {
+                repo.remote_set_url(&self.required_remote, &new_url)
+            };
+            updated.map_err(|e| self.to_rule_error(&format!("Failed to update remote: {e}")))?;
+            println!("Remote '{}' {kind} is now {new_url}", self.required_remote);
+        }
+
+        Ok(fixed_all)
+    }
+}
+
+impl GitRemoteHttpSsh {
+    /// Judge remotes against the preferred auth of the global config
+    pub fn with_auth(mut self, auth: &GlobalAuth) -> Self {
+        self.auth = auth.clone();
+        self
+    }
+
+    /// The remote's fetch URL and push URL (when it has its own), labelled for messages
+    fn remote_urls(&self) -> Result<Vec<(&'static str, String)>, Box<BGitError>> {
+        let repo = Repository::discover(".")
+            .map_err(|e| self.to_rule_error(&format!("Failed to discover repository: {e}")))?;
+        // A missing remote is RemoteExists' business
+        let Ok(remote) = repo.find_remote(&self.required_remote) else {
+            return Ok(vec![]);
+        };
+        let mut urls = vec![];
+        if let Some(url) = remote.url() {
+            urls.push(("URL", url.to_string()));
+        }
+        if let Some(url) = remote.pushurl() {
+            urls.push(("push URL", url.to_string()));
+        }
+        Ok(urls)
+    }
+
+    /// What is wrong with `url`, if anything
+    fn violation(&self, url: &str) -> Option<String> {
+        let transport = Transport::of(url);
+        match (transport, self.required) {
+            (Transport::Local, _) => None,
+            (Transport::Http, _) => Some("is unencrypted, use HTTPS or SSH".to_string()),
+            (Transport::Git, _) => Some("is unauthenticated, use HTTPS or SSH".to_string()),
+            (_, Some(required)) if required != transport => Some(format!(
+                "uses {transport} but this repository requires {required}"
+            )),
+            (_, Some(_)) => None,
+            (_, None) => transform_url_for_preference(url, &self.auth)
+                .map(|_| format!("uses {transport}, not your preferred auth")),
+        }
+    }
+
+    /// `url` moved to a secure scheme and the required or preferred transport, `None`
+    /// when the host is unknown and there is no safe rewrite
+    fn fixed_url(&self, url: &str) -> Option<String> {
+        let secure = match Transport::of(url) {
+            Transport::Http => url.replacen("http://", "https://", 1),
+            Transport::Git => url.replacen("git://", "https://", 1),
+            _ => url.to_string(),
+        };
+
+        let mut policy = self.auth.clone();
+        if let Some(required) = self.required {
+            // The repository's requirement wins over per-host preferences
+            policy.preferred = match required {
+                Transport::Ssh => PreferredAuth::Ssh,
+                _ => PreferredAuth::Https,
+            };
+            for host in &mut policy.hosts {
+                host.preferred = None;
+            }
+        }
+        let target = transform_url_for_preference(&secure, &policy).unwrap_or(secure);
+        (target != url && self.violation(&target).is_none()).then_some(target)
+    }
+
+    fn to_rule_error(&self, message: &str) -> Box<BGitError> {
+        Box::new(BGitError::new(
+            "GitRemoteHttpSsh",
+            message,
+            BGitErrorWorkflowType::Rules,
+            NO_STEP,
+            NO_EVENT,
+            self.get_name(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(require: Option<&str>, preferred: PreferredAuth) -> GitRemoteHttpSsh {
+        let rules: WorkflowRules = match require {
+            Some(require) => toml::from_str(&format!(
+                "[params.GitRemoteHttpSsh]\nrequire = \"{require}\"\n"
+            ))
+            .unwrap(),
+            None => WorkflowRules::default(),
+        };
+        GitRemoteHttpSsh::new(Some(&rules)).with_auth(&GlobalAuth {
+            preferred,
+            ..GlobalAuth::default()
+        })
+    }
+
+    #[test]
+    fn test_classifies_remote_urls() {
+        assert_eq!(
+            Transport::of("https://github.com/o/r.git"),
+            Transport::Https
+        );
+        assert_eq!(Transport::of("http://example.com/r.git"), Transport::Http);
+        assert_eq!(Transport::of("git://example.com/r.git"), Transport::Git);
+        assert_eq!(Transport::of("git@github.com:o/r.git"), Transport::Ssh);
+        assert_eq!(Transport::of("ssh://git@host:2222/o/r"), Transport::Ssh);
+        assert_eq!(Transport::of("/srv/git/r.git"), Transport::Local);
+        assert_eq!(Transport::of("C:\\repos\\r.git"), Transport::Local);
+        assert_eq!(Transport::of("file:///srv/git/r.git"), Transport::Local);
+    }
+
+    #[test]
+    fn test_flags_insecure_and_contradicting_remotes() {
+        let url_based = rule(None, PreferredAuth::RepositoryURLBased);
+        assert!(url_based.violation("git@github.com:o/r.git").is_none());
+        assert!(url_based.violation("https://github.com/o/r.git").is_none());
+        assert!(url_based.violation("http://github.com/o/r.git").is_some());
+        assert!(url_based.violation("git://example.com/r.git").is_some());
+
+        let prefers_ssh = rule(None, PreferredAuth::Ssh);
+        assert!(
+            prefers_ssh
+                .violation("https://github.com/o/r.git")
+                .is_some()
+        );
+        // Unknown hosts cannot be rewritten, so the preference does not apply to them
+        assert!(
+            prefers_ssh
+                .violation("https://example.com/o/r.git")
+                .is_none()
+        );
+
+        let https_only = rule(Some("https"), PreferredAuth::Ssh);
+        assert!(https_only.violation("git@example.com:o/r.git").is_some());
+        assert!(
+            https_only
+                .violation("https://example.com/o/r.git")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_rewrites_to_required_or_preferred_scheme() {
+        let prefers_ssh = rule(None, PreferredAuth::Ssh);
+        assert_eq!(
+            prefers_ssh
+                .fixed_url("http://github.com/o/r.git")
+                .as_deref(),
+            Some("git@github.com:o/r.git")
+        );
+
+        let url_based = rule(None, PreferredAuth::RepositoryURLBased);
+        assert_eq!(
+            url_based.fixed_url("git://example.com/r.git").as_deref(),
+            Some("https://example.com/r.git")
+        );
+
+        let ssh_only = rule(Some("ssh"), PreferredAuth::Https);
+        assert_eq!(
+            ssh_only.fixed_url("https://gitlab.com/g/r.git").as_deref(),
+            Some("git@gitlab.com:g/r.git")
+        );
+        assert_eq!(ssh_only.fixed_url("https://example.com/g/r.git"), None);
+    }
+}
@@ -1 +1,362 @@
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_STEP};
+use crate::config::local::WorkflowRules;
+use crate::prompt;
+use crate::rules::{Rule, RuleLevel, RuleOutput};
+use git2::{AttrCheckFlags, Delta, Repository};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
+/// Git LFS never writes pointers larger than this
+const MAX_POINTER_SIZE: usize = 1024;
+
+/// A staged file that should go through Git LFS but will not arrive intact
+#[derive(Debug, PartialEq)]
+enum LfsProblem {
+    /// Staged with its full content, as the LFS clean filter did not run
+    RawBlob(String),
+    /// Staged as a pointer to an object missing from the local LFS store
+    MissingObject { path: String, oid: String },
+}
+
+pub(crate) struct GitLfs {
+    name: String,
+    description: String,
+    level: RuleLevel,
+}
+
+impl Rule for GitLfs {
+    fn new(workflow_rule_config: Option<&WorkflowRules>) -> Self {
+        let default_rule_level = RuleLevel::Warning;
+        let name = "GitLfs";
+        let rule_level = workflow_rule_config
+            .and_then(|config| config.get_rule_level(name))
+            .cloned()
+            .unwrap_or(default_rule_level);
+
+        Self {
+            name: name.to_string(),
+            description: "Ensure Git LFS is set up and LFS files are staged as pointers"
+                .to_string(),
+            level: rule_level,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_description(&self) -> &str {
+        &self.description
+    }
+
+    fn get_level(&self) -> RuleLevel {
+        self.level.clone()
+    }
+
+    fn check(&self) -> Result<RuleOutput, Box<BGitError>> {
+        let repo = match Repository::discover(".") {
+            Ok(repo) => repo,
+            Err(e) => {
+                return Ok(RuleOutput::Exception(format!(
+                    "Failed to open repository: {e}"
+                )));
+            }
+        };
+
+        let problems = match staged_lfs_problems(&repo) {
+            Ok(problems) => problems,
+            Err(e) => {
+                return Ok(RuleOutput::Exception(format!(
+                    "Failed to inspect staged files: {e}"
+                )));
+            }
+        };
+        if (!problems.is_empty() || uses_lfs(&repo))
+            && let Some(setup) = lfs_setup_problem(&repo)
+        {
+            return Ok(RuleOutput::Exception(setup));
+        }
+        if problems.is_empty() {
+            return Ok(RuleOutput::Success);
+        }
+
+        let report: Vec<String> = problems
+            .iter()
+            .map(|problem| match problem {
+                LfsProblem::RawBlob(path) => format!("{path} is staged without LFS"),
+                LfsProblem::MissingObject { path, .. } => {
+                    format!("{path} points to an LFS object missing locally")
+                }
+            })
+            .collect();
+        Ok(RuleOutput::Exception(format!(
+            "LFS files would be committed incorrectly: {}",
+            report.join(", ")
+        )))
+    }
+
+    fn try_fix(&self) -> Result<bool, Box<BGitError>> {
+        let repo = Repository::discover(".")
+            .map_err(|e| self.to_rule_error(&format!("Failed to open repository: {e}")))?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| self.to_rule_error("Repository has no working directory"))?
+            .to_path_buf();
+
+        if which::which("git-lfs").is_err() {
+            println!("Git LFS is not installed. Install it from https://git-lfs.com, e.g.:");
+            println!("  brew install git-lfs        # macOS");
+            println!("  sudo apt-get install git-lfs  # Debian/Ubuntu");
+            println!("  winget install GitHub.GitLFS  # Windows");
+            println!("then run `git lfs install` and stage the files again.");
+            return Ok(false);
+        }
+        if lfs_setup_problem(&repo).is_some() {
+            let install = prompt::confirm(
+                "git_lfs.install",
+                "Git LFS filters are not set up. Run `git lfs install`?",
+                true,
+            )
+            .map_err(|e| self.to_rule_error(&format!("Failed to get user input: {e}")))?;
+            if !install || !run_git(&workdir, &["lfs", "install"]) {
+                return Ok(false);
+            }
+        }
+
+        let problems = staged_lfs_problems(&repo)
+            .map_err(|e| self.to_rule_error(&format!("Failed to inspect staged files: {e}")))?;
+        let mut fixed_all = true;
+        let mut restage = vec![];
+        for problem in problems {
+            match problem {
+                LfsProblem::RawBlob(path) => restage.push(path),
+                LfsProblem::MissingObject { path, oid } => {
+                    // Cleaning the real content again recreates the object; a pointer in
+                    // the working tree means it has to come from the remote
+                    let on_disk = fs::read(workdir.join(&path)).unwrap_or_default();
+                    if pointer_oid(&on_disk).is_none() {
+                        restage.push(path);
+                    } else {
+                        println!("{path}: LFS object {oid} is missing, run `git lfs pull`");
+                        fixed_all = false;
+                    }
+                }
+            }
+        }
+        if restage.is_empty() {
+            return Ok(fixed_all);
+        }
+
+        let confirmed = prompt::confirm(
+            "git_lfs.restage",
+            &format!("Stage {} again through Git LFS?", restage.join(", ")),
+            true,
+        )
+        .map_err(|e| self.to_rule_error(&format!("Failed to get user input: {e}")))?;
+        if !confirmed {
+            return Ok(false);
+        }
+        let mut args = vec!["add", "--renormalize", "--"];
+        args.extend(restage.iter().map(String::as_str));
+        Ok(run_git(&workdir, &args) && fixed_all)
+    }
+}
+
+impl GitLfs {
+    fn to_rule_error(&self, message: &str) -> Box<BGitError> {
+        Box::new(BGitError::new(
+            "GitLfs",
+            message,
+            BGitErrorWorkflowType::Rules,
+            NO_STEP,
+            NO_EVENT,
+            self.get_name(),
+        ))
+    }
+}
+
+fn run_git(workdir: &Path, args: &[&str]) -> bool {
+    match Command::new("git").args(args).current_dir(workdir).output() {
+        Ok(output) if output.status.success() => true,
+        Ok(output) => {
+            eprintln!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            false
+        }
+        Err(e) => {
+            eprintln!("Failed to run git {}: {e}", args.join(" "));
+            false
+        }
+    }
+}
+
+/// Whether the top-level `.gitattributes` routes any files through LFS
+fn uses_lfs(repo: &Repository) -> bool {
+    repo.workdir()
+        .and_then(|workdir| fs::read_to_string(workdir.join(".gitattributes")).ok())
+        .is_some_and(|attributes| attributes.contains("filter=lfs"))
+}
+
+/// Why LFS files cannot be cleaned or smudged here, if they cannot
+fn lfs_setup_problem(repo: &Repository) -> Option<String> {
+    if which::which("git-lfs").is_err() {
+        return Some("Files are tracked with Git LFS, but git-lfs is not installed".to_string());
+    }
+    let config = repo.config().ok()?;
+    let configured = ["filter.lfs.clean", "filter.lfs.smudge"]
+        .iter()
+        .all(|key| config.get_string(key).is_ok_and(|value| !value.is_empty()));
+    (!configured).then(|| "Git LFS filters are not configured, run `git lfs install`".to_string())
+}
+
+/// Staged additions and changes to files `.gitattributes` assigns to LFS that are
+/// not proper pointers, or whose objects are not in the local LFS store
+fn staged_lfs_problems(repo: &Repository) -> Result<Vec<LfsProblem>, git2::Error> {
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let diff = repo.diff_tree_to_index(head_tree.as_ref(), None, None)?;
+    let storage = lfs_storage_dir(repo);
+
+    let mut problems = vec![];
+    for delta in diff.deltas() {
+        if !matches!(delta.status(), Delta::Added | Delta::Modified) {
+            continue;
+        }
+        let Some(path) = delta.new_file().path() else {
+            continue;
+        };
+        let filter = repo.get_attr(path, "filter", AttrCheckFlags::INDEX_THEN_FILE)?;
+        if filter != Some("lfs") {
+            continue;
+        }
+
+        let blob = repo.find_blob(delta.new_file().id())?;
+        let path = path.to_string_lossy().into_owned();
+        match pointer_oid(blob.content()) {
+            None => problems.push(LfsProblem::RawBlob(path)),
+            Some(oid) if !lfs_object_path(&storage, &oid).exists() => {
+                problems.push(LfsProblem::MissingObject { path, oid })
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(problems)
+}
+
+/// Where Git LFS keeps objects: `lfs.storage`, relative to the git dir, or `.git/lfs`
+fn lfs_storage_dir(repo: &Repository) -> PathBuf {
+    let configured = repo
+        .config()
+        .ok()
+        .and_then(|config| config.get_path("lfs.storage").ok());
+    match configured {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => repo.path().join(path),
+        None => repo.path().join("lfs"),
+    }
+}
+
+fn lfs_object_path(storage: &Path, oid: &str) -> PathBuf {
+    storage
+        .join("objects")
+        .join(&oid[..2])
+        .join(&oid[2..4])
+        .join(oid)
+}
+
+/// The sha256 object id a Git LFS pointer refers to, `None` when `content` is not one
+fn pointer_oid(content: &[u8]) -> Option<String> {
+    if content.len() > MAX_POINTER_SIZE {
+        return None;
+    }
+    let text = std::str::from_utf8(content).ok()?;
+    let mut lines = text.lines();
+    if !lines
+        .next()?
+        .starts_with("version https://git-lfs.github.com/spec/")
+    {
+        return None;
+    }
+
+    let mut oid = None;
+    let mut has_size = false;
+    for line in lines {
+        if let Some(hash) = line.strip_prefix("oid sha256:") {
+            oid = (hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+                .then(|| hash.to_ascii_lowercase());
+        } else if let Some(size) = line.strip_prefix("size ") {
+            has_size = size.parse::<u64>().is_ok();
+        }
+    }
+    oid.filter(|_| has_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn pointer(oid: &str) -> String {
+        format!("version https://git-lfs.github.com/spec/v1\noid sha256:{oid}\nsize 12345\n")
+    }
+
+    #[test]
+    fn test_parses_lfs_pointers() {
+        let oid = "4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393";
+        assert_eq!(pointer_oid(pointer(oid).as_bytes()), Some(oid.to_string()));
+        assert_eq!(pointer_oid(b"\x89PNG\r\n\x1a\n binary"), None);
+        assert_eq!(
+            pointer_oid(b"version https://git-lfs.github.com/spec/v1\noid sha256:abc\nsize 1\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_reports_raw_blobs_and_missing_objects() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let present = "a".repeat(64);
+        let missing = "b".repeat(64);
+        fs::write(
+            dir.path().join(".gitattributes"),
+            "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("raw.bin"), [0u8, 159, 146, 150]).unwrap();
+        fs::write(dir.path().join("present.bin"), pointer(&present)).unwrap();
+        fs::write(dir.path().join("missing.bin"), pointer(&missing)).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not in LFS\n").unwrap();
+        let object = lfs_object_path(&repo.path().join("lfs"), &present);
+        fs::create_dir_all(object.parent().unwrap()).unwrap();
+        fs::write(object, "content").unwrap();
+
+        let mut index = repo.index().unwrap();
+        for path in [
+            ".gitattributes",
+            "raw.bin",
+            "present.bin",
+            "missing.bin",
+            "notes.txt",
+        ] {
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+
+        let mut problems = staged_lfs_problems(&repo).unwrap();
+        problems.sort_by_key(|problem| format!("{problem:?}"));
+        assert_eq!(
+            problems,
+            vec![
+                LfsProblem::MissingObject {
+                    path: "missing.bin".to_string(),
+                    oid: missing,
+                },
+                LfsProblem::RawBlob("raw.bin".to_string()),
+            ]
+        );
+        assert!(uses_lfs(&repo));
+    }
+}
@@ -108,7 +108,7 @@ impl Rule for IsRepoSizeTooBig {
 }
 
 impl IsRepoSizeTooBig {
-    fn calculate_repo_size(&self, repo: &Repository) -> Result<u64, String> {
+    pub(crate) fn calculate_repo_size(&self, repo: &Repository) -> Result<u64, String> {
         let mut total_size = 0u64;
 
         // Get the index to access tracked files
@@ -193,6 +193,10 @@ impl IsRepoSizeTooBig {
         Ok(cleanup_performed)
     }
 
+    pub(crate) fn get_max_size_mb(&self) -> u64 {
+        self.max_size_mb
+    }
+
     /// Method to set custom size limit
     #[allow(dead_code)]
     pub fn with_max_size_mb(mut self, max_size_mb: u64) -> Self {
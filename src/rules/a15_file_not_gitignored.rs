@@ -1 +1,441 @@
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_STEP};
+use crate::config::local::WorkflowRules;
+use crate::prompt;
+use crate::rules::{Rule, RuleLevel, RuleOutput};
+use git2::{Repository, Status, StatusOptions};
+use std::fs;
+use std::path::Path;
 
+/// A `.gitignore` section for one kind of project, or for files every project ignores
+struct IgnoreTemplate {
+    name: &'static str,
+    /// Files at the repository root marking a project of this kind; empty when the
+    /// template always applies
+    markers: &'static [&'static str],
+    patterns: &'static [&'static str],
+}
+
+const IGNORE_TEMPLATES: &[IgnoreTemplate] = &[
+    IgnoreTemplate {
+        name: "Rust",
+        markers: &["Cargo.toml"],
+        patterns: &["target/", "**/*.rs.bk"],
+    },
+    IgnoreTemplate {
+        name: "Node",
+        markers: &["package.json"],
+        patterns: &[
+            "node_modules/",
+            "dist/",
+            ".next/",
+            "npm-debug.log*",
+            "yarn-error.log*",
+        ],
+    },
+    IgnoreTemplate {
+        name: "Python",
+        markers: &["pyproject.toml", "setup.py", "requirements.txt"],
+        patterns: &[
+            "__pycache__/",
+            "*.py[cod]",
+            ".venv/",
+            "*.egg-info/",
+            ".pytest_cache/",
+            "dist/",
+            "build/",
+        ],
+    },
+    IgnoreTemplate {
+        name: "Editors",
+        markers: &[],
+        patterns: &[".idea/", "*.swp", "*.swo", "*~"],
+    },
+    IgnoreTemplate {
+        name: "OS files",
+        markers: &[],
+        patterns: &[".DS_Store", "Thumbs.db", "desktop.ini"],
+    },
+];
+
+/// A new file that should not be added
+#[derive(Debug, PartialEq)]
+struct Finding {
+    path: String,
+    /// Template whose patterns match the file, `None` when `.gitignore` already
+    /// ignores it and it was staged regardless
+    template: Option<&'static str>,
+    staged: bool,
+}
+
+pub(crate) struct FileNotGitignored {
+    name: String,
+    description: String,
+    level: RuleLevel,
+    /// Files about to be added, `None` when every change is
+    paths: Option<Vec<String>>,
+}
+
+impl Rule for FileNotGitignored {
+    fn new(workflow_rule_config: Option<&WorkflowRules>) -> Self {
+        let default_rule_level = RuleLevel::Warning;
+        let name = "FileNotGitignored";
+        let rule_level = workflow_rule_config
+            .and_then(|config| config.get_rule_level(name))
+            .cloned()
+            .unwrap_or(default_rule_level);
+
+        Self {
+            name: name.to_string(),
+            description: "Keep build outputs, editor and OS files out of the index".to_string(),
+            level: rule_level,
+            paths: None,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_description(&self) -> &str {
+        &self.description
+    }
+
+    fn get_level(&self) -> RuleLevel {
+        self.level.clone()
+    }
+
+    fn check(&self) -> Result<RuleOutput, Box<BGitError>> {
+        let repo = match Repository::discover(".") {
+            Ok(repo) => repo,
+            Err(e) => {
+                return Ok(RuleOutput::Exception(format!(
+                    "Failed to open repository: {e}"
+                )));
+            }
+        };
+
+        let findings = match find_unignored_files(&repo, self.paths.as_deref()) {
+            Ok(findings) => findings,
+            Err(e) => {
+                return Ok(RuleOutput::Exception(format!(
+                    "Failed to get repository status: {e}"
+                )));
+            }
+        };
+        if findings.is_empty() {
+            return Ok(RuleOutput::Success);
+        }
+
+        let report: Vec<String> = findings
+            .iter()
+            .map(|finding| match finding.template {
+                Some(template) => format!("{} ({template})", finding.path),
+                None => format!("{} (ignored by .gitignore)", finding.path),
+            })
+            .collect();
+        Ok(RuleOutput::Exception(format!(
+            "Files that belong in .gitignore are about to be added: {}",
+            report.join(", ")
+        )))
+    }
+
+    fn try_fix(&self) -> Result<bool, Box<BGitError>> {
+        let repo = Repository::discover(".")
+            .map_err(|e| self.to_rule_error(&format!("Failed to open repository: {e}")))?;
+        let findings = find_unignored_files(&repo, self.paths.as_deref())
+            .map_err(|e| self.to_rule_error(&format!("Failed to get repository status: {e}")))?;
+        if findings.is_empty() {
+            return Ok(true);
+        }
+
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| self.to_rule_error("Repository has no working directory"))?;
+        let gitignore_path = workdir.join(".gitignore");
+        let existing = fs::read_to_string(&gitignore_path).unwrap_or_default();
+        let missing = missing_patterns(&existing, &findings);
+
+        println!("These files are usually kept out of Git:");
+        for finding in &findings {
+            println!("  {}", finding.path);
+        }
+        if !missing.is_empty() {
+            println!("Suggested .gitignore additions:");
+            for (name, patterns) in &missing {
+                println!("# {name}\n{}", patterns.join("\n"));
+            }
+        }
+        let confirmed = prompt::confirm(
+            "file_not_gitignored.fix",
+            "Update .gitignore and unstage these files?",
+            true,
+        )
+        .map_err(|e| self.to_rule_error(&format!("Failed to get user input: {e}")))?;
+        if !confirmed {
+            return Ok(false);
+        }
+
+        if !missing.is_empty() {
+            fs::write(&gitignore_path, add_patterns(&existing, &missing)).map_err(|e| {
+                self.to_rule_error(&format!(
+                    "Failed to write {}: {e}",
+                    gitignore_path.display()
+                ))
+            })?;
+            println!("Updated {}", gitignore_path.display());
+        }
+
+        let staged: Vec<&str> = findings
+            .iter()
+            .filter(|finding| finding.staged)
+            .map(|finding| finding.path.as_str())
+            .collect();
+        if !staged.is_empty() {
+            // Same as `git reset -- <paths>`: new files leave the index, nothing else moves
+            let head = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+            repo.reset_default(head.as_ref().map(|commit| commit.as_object()), &staged)
+                .map_err(|e| self.to_rule_error(&format!("Failed to unstage files: {e}")))?;
+            println!("Unstaged {}", staged.join(", "));
+        }
+        Ok(true)
+    }
+}
+
+impl FileNotGitignored {
+    /// Judge only `paths`, the files selected for adding
+    pub fn with_paths(mut self, paths: Vec<String>) -> Self {
+        self.paths = Some(paths);
+        self
+    }
+
+    fn to_rule_error(&self, message: &str) -> Box<BGitError> {
+        Box::new(BGitError::new(
+            "FileNotGitignored",
+            message,
+            BGitErrorWorkflowType::Rules,
+            NO_STEP,
+            NO_EVENT,
+            self.get_name(),
+        ))
+    }
+}
+
+/// Templates for the project kinds whose marker files are at the repository root,
+/// plus the ones every project gets
+fn applicable_templates(workdir: &Path) -> Vec<&'static IgnoreTemplate> {
+    IGNORE_TEMPLATES
+        .iter()
+        .filter(|template| {
+            template.markers.is_empty()
+                || template
+                    .markers
+                    .iter()
+                    .any(|marker| workdir.join(marker).exists())
+        })
+        .collect()
+}
+
+/// New files, staged or about to be added, that `.gitignore` already ignores or that
+/// match a template for this project. With `paths`, only those files are judged.
+fn find_unignored_files(
+    repo: &Repository,
+    paths: Option<&[String]>,
+) -> Result<Vec<Finding>, git2::Error> {
+    let Some(workdir) = repo.workdir() else {
+        return Ok(vec![]);
+    };
+    let templates = applicable_templates(workdir);
+
+    let mut status_options = StatusOptions::new();
+    status_options.include_untracked(true);
+    status_options.include_ignored(false);
+    // Selected paths are single files, while a whole untracked directory is one entry
+    status_options.recurse_untracked_dirs(paths.is_some());
+    let statuses = repo.statuses(Some(&mut status_options))?;
+    let candidates: Vec<(String, bool)> = statuses
+        .iter()
+        .filter(|entry| {
+            entry
+                .status()
+                .intersects(Status::INDEX_NEW | Status::WT_NEW)
+        })
+        .filter(|entry| {
+            paths.is_none_or(|paths| {
+                entry
+                    .path()
+                    .is_some_and(|path| paths.iter().any(|selected| selected == path))
+            })
+        })
+        .filter_map(|entry| {
+            let staged = entry.status().contains(Status::INDEX_NEW);
+            entry.path().map(|path| (path.to_string(), staged))
+        })
+        .collect();
+
+    let mut findings = vec![];
+    let mut unmatched = vec![];
+    for (path, staged) in candidates {
+        if staged && repo.is_path_ignored(Path::new(&path))? {
+            findings.push(Finding {
+                path,
+                template: None,
+                staged,
+            });
+        } else {
+            unmatched.push((path, staged));
+        }
+    }
+
+    // Match with git's own ignore rules, added in memory one template at a time
+    for template in templates {
+        repo.add_ignore_rule(&template.patterns.join("\n"))?;
+        let mut remaining = vec![];
+        for (path, staged) in unmatched {
+            if repo.is_path_ignored(Path::new(&path))? {
+                findings.push(Finding {
+                    path,
+                    template: Some(template.name),
+                    staged,
+                });
+            } else {
+                remaining.push((path, staged));
+            }
+        }
+        unmatched = remaining;
+        repo.clear_ignore_rules()?;
+    }
+    Ok(findings)
+}
+
+/// Patterns of the templates behind `findings` that `existing` does not list yet, by
+/// template name
+fn missing_patterns(
+    existing: &str,
+    findings: &[Finding],
+) -> Vec<(&'static str, Vec<&'static str>)> {
+    let present: Vec<&str> = existing.lines().map(str::trim).collect();
+    IGNORE_TEMPLATES
+        .iter()
+        .filter(|template| {
+            findings
+                .iter()
+                .any(|finding| finding.template == Some(template.name))
+        })
+        .map(|template| {
+            let patterns: Vec<&str> = template
+                .patterns
+                .iter()
+                .copied()
+                .filter(|pattern| !present.contains(pattern))
+                .collect();
+            (template.name, patterns)
+        })
+        .filter(|(_, patterns)| !patterns.is_empty())
+        .collect()
+}
+
+/// `existing` with `missing` added, each under its `# <template>` section. A section that
+/// is not there yet is appended.
+fn add_patterns(existing: &str, missing: &[(&str, Vec<&str>)]) -> String {
+    let mut lines: Vec<String> = existing.lines().map(str::to_string).collect();
+    for (name, patterns) in missing {
+        let header = format!("# {name}");
+        let patterns = patterns.iter().map(|pattern| pattern.to_string());
+        match lines.iter().position(|line| line.trim() == header) {
+            Some(start) => {
+                // A section runs until the next blank line or comment
+                let end = lines[start + 1..]
+                    .iter()
+                    .position(|line| line.trim().is_empty() || line.trim().starts_with('#'))
+                    .map_or(lines.len(), |offset| start + 1 + offset);
+                lines.splice(end..end, patterns);
+            }
+            None => {
+                if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push(header);
+                lines.extend(patterns);
+            }
+        }
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_flags_build_outputs_for_detected_languages() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+        fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        fs::write(dir.path().join("target/debug/app"), "binary").unwrap();
+        fs::create_dir_all(dir.path().join("src/__pycache__")).unwrap();
+        fs::write(dir.path().join("src/__pycache__/mod.pyc"), "bytecode").unwrap();
+        fs::write(dir.path().join(".DS_Store"), "junk").unwrap();
+        fs::write(dir.path().join(".gitignore"), "*.log\n").unwrap();
+        fs::write(dir.path().join("debug.log"), "log").unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("debug.log")).unwrap();
+        index.write().unwrap();
+
+        let mut findings = find_unignored_files(&repo, None).unwrap();
+        findings.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(
+            findings,
+            vec![
+                Finding {
+                    path: ".DS_Store".to_string(),
+                    template: Some("OS files"),
+                    staged: false,
+                },
+                Finding {
+                    path: "debug.log".to_string(),
+                    template: None,
+                    staged: true,
+                },
+                Finding {
+                    path: "target/".to_string(),
+                    template: Some("Rust"),
+                    staged: false,
+                },
+            ]
+        );
+
+        // Python was not detected, so its section is not suggested
+        let existing = "# Rust\ntarget/\n\n# Logs\n*.log\n";
+        let missing = missing_patterns(existing, &findings);
+        assert_eq!(
+            missing,
+            vec![
+                ("Rust", vec!["**/*.rs.bk"]),
+                ("OS files", vec![".DS_Store", "Thumbs.db", "desktop.ini"]),
+            ]
+        );
+        // The existing Rust section is extended rather than repeated
+        assert_eq!(
+            add_patterns(existing, &missing),
+            "# Rust\ntarget/\n**/*.rs.bk\n\n# Logs\n*.log\n\n# OS files\n.DS_Store\nThumbs.db\ndesktop.ini\n"
+        );
+        assert_eq!(add_patterns("", &missing[..1]), "# Rust\n**/*.rs.bk\n");
+
+        // Only the files selected for adding are judged
+        let selected = ["target/debug/app".to_string(), "Cargo.toml".to_string()];
+        let findings = find_unignored_files(&repo, Some(&selected)).unwrap();
+        assert_eq!(
+            findings,
+            vec![Finding {
+                path: "target/debug/app".to_string(),
+                template: Some("Rust"),
+                staged: false,
+            }]
+        );
+    }
+}
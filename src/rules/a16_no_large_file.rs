@@ -230,6 +230,11 @@ impl Rule for NoLargeFile {
             match self.add_lfs_tracking(&extensions.into_iter().collect::<Vec<_>>()) {
                 Ok(_) => {
                     println!("\nAutomatically added LFS tracking to .gitattributes");
+                    if which::which("git-lfs").is_err() {
+                        println!(
+                            "Git LFS is not installed yet, install it before committing these files"
+                        );
+                    }
                     Ok(true)
                 }
                 Err(e) => {
@@ -1,8 +1,7 @@
 use crate::bgit_error::BGitError;
 use crate::config::local::WorkflowRules;
+use crate::prompt;
 use crate::rules::{Rule, RuleLevel, RuleOutput};
-use dialoguer::Input;
-use dialoguer::theme::ColorfulTheme;
 use git2::Repository;
 use std::process::Command;
 
@@ -59,22 +58,24 @@ impl Rule for RemoteExists {
 You can paste the SSH URL below (HTTPS also works, but SSH is preferred)."#
         );
 
-        let repo_url: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!(
+        let repo_url = prompt::input(
+            "remote_exists.url",
+            &format!(
                 "Enter the repository URL for remote '{}'",
                 self.required_remote
+            ),
+            None,
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "RemoteExists",
+                &format!("Failed to get user input: {e}"),
+                crate::bgit_error::BGitErrorWorkflowType::Rules,
+                "try_fix",
+                "remote_check",
+                "RemoteExists",
             ))
-            .interact_text()
-            .map_err(|e| {
-                Box::new(BGitError::new(
-                    "RemoteExists",
-                    &format!("Failed to get user input: {e}"),
-                    crate::bgit_error::BGitErrorWorkflowType::Rules,
-                    "try_fix",
-                    "remote_check",
-                    "RemoteExists",
-                ))
-            })?;
+        })?;
 
         if repo_url.trim().is_empty() {
             println!("No URL provided. Remote not added.");
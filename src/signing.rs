@@ -0,0 +1,350 @@
+use git2::Config;
+use log::debug;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How commits are signed, from `gpg.format`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SigningFormat {
+    OpenPgp,
+    Ssh,
+}
+
+/// Signs commits the way git does for `commit.gpgSign`, by shelling out to gpg or
+/// `ssh-keygen -Y sign`
+#[derive(Debug, Clone)]
+pub(crate) struct CommitSigner {
+    pub format: SigningFormat,
+    /// `user.signingKey`: a gpg key id, or an SSH key file / literal public key
+    pub key: Option<String>,
+    pub program: String,
+}
+
+impl CommitSigner {
+    /// The signer for new commits, `None` when `commit.gpgSign` is off
+    pub fn for_commits(config: &Config) -> Result<Option<Self>, String> {
+        if !config.get_bool("commit.gpgSign").unwrap_or(false) {
+            return Ok(None);
+        }
+        Self::from_config(config).map(Some)
+    }
+
+    /// The signer `gpg.format`, `user.signingKey` and the program overrides describe,
+    /// whether or not signing is enabled
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let format = match config.get_string("gpg.format").ok().as_deref() {
+            None | Some("openpgp") => SigningFormat::OpenPgp,
+            Some("ssh") => SigningFormat::Ssh,
+            Some(other) => {
+                return Err(format!(
+                    "gpg.format '{other}' is not supported, use 'openpgp' or 'ssh'"
+                ));
+            }
+        };
+        let key = config
+            .get_string("user.signingKey")
+            .ok()
+            .filter(|key| !key.trim().is_empty());
+        if format == SigningFormat::Ssh && key.is_none() {
+            return Err("gpg.format is 'ssh' but user.signingKey is not set".to_string());
+        }
+        let program = match format {
+            SigningFormat::OpenPgp => config
+                .get_string("gpg.openpgp.program")
+                .or_else(|_| config.get_string("gpg.program"))
+                .unwrap_or_else(|_| "gpg".to_string()),
+            SigningFormat::Ssh => config
+                .get_string("gpg.ssh.program")
+                .unwrap_or_else(|_| "ssh-keygen".to_string()),
+        };
+        Ok(Self {
+            format,
+            key,
+            program,
+        })
+    }
+
+    /// Whether the signing program and key can be used, without signing anything
+    pub fn check_ready(&self) -> Result<(), String> {
+        which::which(&self.program)
+            .map_err(|_| format!("Signing program '{}' was not found", self.program))?;
+        match self.format {
+            SigningFormat::Ssh => match self.ssh_key_file() {
+                Some(path) if !path.exists() => {
+                    Err(format!("SSH signing key {} does not exist", path.display()))
+                }
+                _ => Ok(()),
+            },
+            SigningFormat::OpenPgp => {
+                let Some(key) = &self.key else {
+                    return Ok(());
+                };
+                let listed = Command::new(&self.program)
+                    .args(["--batch", "--list-secret-keys", key])
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .map(|status| status.success())
+                    .unwrap_or(false);
+                if listed {
+                    Ok(())
+                } else {
+                    Err(format!("No secret gpg key found for '{key}'"))
+                }
+            }
+        }
+    }
+
+    /// Armored detached signature of `payload`. `committer` ("Name <email>") picks the
+    /// gpg key when `user.signingKey` is unset, as git does.
+    pub fn sign(&self, payload: &str, committer: &str) -> Result<String, String> {
+        let signature = match self.format {
+            SigningFormat::OpenPgp => self.sign_openpgp(payload, committer)?,
+            SigningFormat::Ssh => self.sign_ssh(payload)?,
+        };
+        if signature.trim().is_empty() {
+            return Err(format!("'{}' produced an empty signature", self.program));
+        }
+        Ok(signature)
+    }
+
+    fn sign_openpgp(&self, payload: &str, committer: &str) -> Result<String, String> {
+        let key = self.key.as_deref().unwrap_or(committer);
+        debug!("Signing with {} for key {key}", self.program);
+        let mut child = Command::new(&self.program)
+            .args(["--status-fd=2", "-bsau", key])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run '{}': {e}", self.program))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(payload.as_bytes())
+                .map_err(|e| format!("Failed to write to '{}': {e}", self.program))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Failed to run '{}': {e}", self.program))?;
+        let status = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() || !status.contains("SIG_CREATED") {
+            return Err(format!("gpg failed to sign the commit: {}", status.trim()));
+        }
+        String::from_utf8(output.stdout).map_err(|e| format!("Invalid gpg signature: {e}"))
+    }
+
+    fn sign_ssh(&self, payload: &str) -> Result<String, String> {
+        let scratch = ScratchFiles::new();
+        let payload_file = scratch.path("payload");
+        fs::write(&payload_file, payload)
+            .map_err(|e| format!("Failed to write {}: {e}", payload_file.display()))?;
+
+        let mut command = Command::new(&self.program);
+        command.args(["-Y", "sign", "-n", "git", "-f"]);
+        match self.ssh_key_file() {
+            Some(path) => {
+                command.arg(path);
+            }
+            None => {
+                // A literal public key: ssh-agent holds the private half
+                let key_file = scratch.path("key.pub");
+                fs::write(&key_file, self.literal_ssh_key().unwrap_or_default())
+                    .map_err(|e| format!("Failed to write {}: {e}", key_file.display()))?;
+                command.arg(key_file).arg("-U");
+            }
+        }
+        debug!("Signing with {} -Y sign", self.program);
+        let output = command
+            .arg(&payload_file)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to run '{}': {e}", self.program))?;
+        if !output.status.success() {
+            return Err(format!(
+                "ssh-keygen failed to sign the commit: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        let signature_file = scratch.path("payload.sig");
+        fs::read_to_string(&signature_file)
+            .map_err(|e| format!("Failed to read {}: {e}", signature_file.display()))
+    }
+
+    /// `user.signingKey` given inline (`key::ssh-ed25519 AAAA...` or `ssh-ed25519 AAAA...`)
+    fn literal_ssh_key(&self) -> Option<&str> {
+        let key = self.key.as_deref()?.trim();
+        key.strip_prefix("key::")
+            .or_else(|| key.starts_with("ssh-").then_some(key))
+    }
+
+    /// `user.signingKey` as a key file, with `~` expanded
+    fn ssh_key_file(&self) -> Option<PathBuf> {
+        if self.literal_ssh_key().is_some() {
+            return None;
+        }
+        let key = self.key.as_deref()?.trim();
+        match key.strip_prefix("~/") {
+            Some(rest) => home::home_dir().map(|home| home.join(rest)),
+            None => Some(PathBuf::from(key)),
+        }
+    }
+}
+
+/// A private temp directory for one signing call, removed when dropped
+struct ScratchFiles(PathBuf);
+
+impl ScratchFiles {
+    fn new() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let dir = std::env::temp_dir().join(format!("bgit-sign-{}-{nanos}", std::process::id()));
+        if let Err(e) = fs::create_dir_all(&dir) {
+            debug!("Failed to create {}: {e}", dir.display());
+        }
+        Self(dir)
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for ScratchFiles {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// SSH public keys under `~/.ssh` and gpg secret key fingerprints, as
+/// (`gpg.format`, `user.signingKey`) pairs that could sign commits
+pub(crate) fn available_keys() -> Vec<(SigningFormat, String)> {
+    let mut keys = vec![];
+    if let Some(ssh_dir) = home::home_dir().map(|home| home.join(".ssh"))
+        && let Ok(entries) = fs::read_dir(&ssh_dir)
+    {
+        let mut public_keys: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "pub"))
+            .collect();
+        public_keys.sort();
+        keys.extend(
+            public_keys
+                .iter()
+                .map(|path| (SigningFormat::Ssh, path.display().to_string())),
+        );
+    }
+    if let Ok(output) = Command::new("gpg")
+        .args(["--batch", "--list-secret-keys", "--with-colons"])
+        .stderr(Stdio::null())
+        .output()
+    {
+        keys.extend(
+            gpg_fingerprints(&String::from_utf8_lossy(&output.stdout))
+                .into_iter()
+                .map(|fingerprint| (SigningFormat::OpenPgp, fingerprint)),
+        );
+    }
+    keys
+}
+
+/// Primary key fingerprints from `gpg --list-secret-keys --with-colons`
+fn gpg_fingerprints(listing: &str) -> Vec<String> {
+    let mut fingerprints = vec![];
+    let mut in_primary = false;
+    for line in listing.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        match fields.first() {
+            Some(&"sec") => in_primary = true,
+            Some(&"fpr") if in_primary => {
+                if let Some(fingerprint) = fields.get(9).filter(|f| !f.is_empty()) {
+                    fingerprints.push(fingerprint.to_string());
+                }
+                in_primary = false;
+            }
+            Some(&"ssb") => in_primary = false,
+            _ => {}
+        }
+    }
+    fingerprints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Repository, Signature};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parses_gpg_fingerprints() {
+        let listing = "sec:u:255:22:AAAA:1700000000:::u:::scESC:::+:::ed25519:::0:\n\
+fpr:::::::::0123456789ABCDEF0123456789ABCDEF01234567:\n\
+uid:u::::1700000000::HASH::Dev <dev@example.com>::::::::::0:\n\
+ssb:u:255:18:BBBB:1700000000::::::e:::+:::cv25519::\n\
+fpr:::::::::FEDCBA9876543210FEDCBA9876543210FEDCBA98:\n";
+        assert_eq!(
+            gpg_fingerprints(listing),
+            vec!["0123456789ABCDEF0123456789ABCDEF01234567".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_ssh_signed_commit_verifies() {
+        if which::which("ssh-keygen").is_err() {
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        let key = dir.path().join("id_ed25519");
+        let generated = Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-C", "bgit-test", "-f"])
+            .arg(&key)
+            .status()
+            .unwrap();
+        assert!(generated.success());
+
+        let repo = Repository::init(dir.path().join("repo")).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_bool("commit.gpgSign", true).unwrap();
+        config.set_str("gpg.format", "ssh").unwrap();
+        config
+            .set_str("user.signingKey", &key.display().to_string())
+            .unwrap();
+        let signer = CommitSigner::for_commits(&repo.config().unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(signer.format, SigningFormat::Ssh);
+        signer.check_ready().unwrap();
+
+        let signature = Signature::now("bgit", "bgit@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let buffer = repo
+            .commit_create_buffer(&signature, &signature, "initial", &tree, &[])
+            .unwrap();
+        let payload = buffer.as_str().unwrap();
+        let armored = signer.sign(payload, "bgit <bgit@example.com>").unwrap();
+        assert!(armored.starts_with("-----BEGIN SSH SIGNATURE-----"));
+        let commit = repo.commit_signed(payload, &armored, None).unwrap();
+
+        // What git would verify: the signature stored on the commit over its payload
+        let (stored, signed_data) = repo.extract_signature(&commit, None).unwrap();
+        let signature_file = dir.path().join("commit.sig");
+        fs::write(&signature_file, &*stored).unwrap();
+        let mut check = Command::new("ssh-keygen")
+            .args(["-Y", "check-novalidate", "-n", "git", "-s"])
+            .arg(&signature_file)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        check.stdin.take().unwrap().write_all(&signed_data).unwrap();
+        assert!(check.wait().unwrap().success());
+    }
+}
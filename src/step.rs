@@ -1,3 +1,5 @@
+use serde_json::Value;
+
 use crate::{
     bgit_error::BGitError,
     config::{
@@ -6,6 +8,9 @@ use crate::{
     },
 };
 
+/// Name standing for [`Step::Stop`] wherever steps are referred to by name
+pub(crate) const STOP: &str = "stop";
+
 #[derive(PartialEq)]
 pub(crate) enum Step {
     Start(Task),
@@ -18,6 +23,36 @@ pub(crate) enum Task {
     PromptStepTask(Box<dyn PromptStep>),
 }
 
+impl Task {
+    pub(crate) fn get_name(&self) -> &str {
+        match self {
+            Task::ActionStepTask(step) => step.get_name(),
+            Task::PromptStepTask(step) => step.get_name(),
+        }
+    }
+
+    pub(crate) fn successors(&self) -> &'static [&'static str] {
+        match self {
+            Task::ActionStepTask(step) => step.successors(),
+            Task::PromptStepTask(step) => step.successors(),
+        }
+    }
+
+    pub(crate) fn checkpoint_context(&self) -> Option<Value> {
+        match self {
+            Task::ActionStepTask(step) => step.checkpoint_context(),
+            Task::PromptStepTask(step) => step.checkpoint_context(),
+        }
+    }
+
+    pub(crate) fn restore_context(&mut self, context: Value) -> Result<(), serde_json::Error> {
+        match self {
+            Task::ActionStepTask(step) => step.restore_context(context),
+            Task::PromptStepTask(step) => step.restore_context(context),
+        }
+    }
+}
+
 impl std::cmp::PartialEq for Task {
     fn eq(&self, other: &Task) -> bool {
         // TODO: Improve the core matching logic for Task
@@ -35,6 +70,21 @@ pub(crate) trait ActionStep {
     where
         Self: Sized;
     fn get_name(&self) -> &str;
+    /// Names of every step `execute` may continue with, [`STOP`] included when it can end
+    /// the workflow. Drives `bgit workflow graph`, so keep it in sync with `execute`.
+    fn successors(&self) -> &'static [&'static str];
+
+    /// State this step was handed by its predecessor, saved in a checkpoint when the
+    /// workflow fails here so `bgit --resume` can rebuild the step. Never include secrets.
+    fn checkpoint_context(&self) -> Option<Value> {
+        None
+    }
+
+    /// Take back the state [`Self::checkpoint_context`] saved
+    fn restore_context(&mut self, _context: Value) -> Result<(), serde_json::Error> {
+        Ok(())
+    }
+
     fn execute(
         &self,
         step_config_flags: Option<&StepFlags>,
@@ -48,6 +98,21 @@ pub(crate) trait PromptStep {
     where
         Self: Sized;
     fn get_name(&self) -> &str;
+    /// Names of every step `execute` may continue with, [`STOP`] included when it can end
+    /// the workflow. Drives `bgit workflow graph`, so keep it in sync with `execute`.
+    fn successors(&self) -> &'static [&'static str];
+
+    /// State this step was handed by its predecessor, saved in a checkpoint when the
+    /// workflow fails here so `bgit --resume` can rebuild the step. Never include secrets.
+    fn checkpoint_context(&self) -> Option<Value> {
+        None
+    }
+
+    /// Take back the state [`Self::checkpoint_context`] saved
+    fn restore_context(&mut self, _context: Value) -> Result<(), serde_json::Error> {
+        Ok(())
+    }
+
     fn execute(
         &self,
         step_config_flags: Option<&StepFlags>,
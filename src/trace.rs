@@ -0,0 +1,371 @@
+use log::{debug, warn};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::bgit_error::BGitError;
+use crate::rules::RuleLevel;
+use crate::util::{bgit_state_dir, civil_from_days};
+
+/// Trace of the workflow currently running, if any. Events and rules run outside a
+/// workflow (e.g. by `bgit log`) are not recorded.
+static CURRENT_TRACE: Mutex<Option<WorkflowTrace>> = Mutex::new(None);
+
+/// Trace of the last finished workflow, for the `--output json` result
+static LAST_TRACE: Mutex<Option<serde_json::Value>> = Mutex::new(None);
+
+/// Set by `--trace`: print finished traces to stdout instead of saving them
+static PRINT_TRACE: AtomicBool = AtomicBool::new(false);
+
+/// Traces kept under `.git/bgit/traces`, oldest are removed first
+const MAX_SAVED_TRACES: usize = 20;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct WorkflowTrace {
+    workflow: String,
+    /// Unix timestamp (seconds) the workflow started at
+    started_at: u64,
+    #[serde(skip)]
+    started_at_ms: u64,
+    dry_run: bool,
+    duration_ms: u128,
+    completed: bool,
+    error: Option<serde_json::Value>,
+    steps: Vec<StepTrace>,
+    #[serde(skip)]
+    started: Option<Instant>,
+}
+
+#[derive(Debug, Serialize)]
+struct StepTrace {
+    name: String,
+    kind: StepKind,
+    /// Step the workflow continued with (`stop` when it ended), `None` if this step failed
+    next: Option<String>,
+    duration_ms: u128,
+    error: Option<serde_json::Value>,
+    events: Vec<EventTrace>,
+    #[serde(skip)]
+    started: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum StepKind {
+    Action,
+    Prompt,
+}
+
+#[derive(Debug, Serialize)]
+struct EventTrace {
+    name: String,
+    /// Command printed instead of running the event under `--dry-run`
+    simulated: Option<String>,
+    result: Option<bool>,
+    duration_ms: u128,
+    error: Option<serde_json::Value>,
+    rules: Vec<RuleTrace>,
+    hooks: Vec<HookTrace>,
+    #[serde(skip)]
+    started: Option<Instant>,
+}
+
+#[derive(Debug, Serialize)]
+struct RuleTrace {
+    name: String,
+    level: RuleLevel,
+    outcome: RuleOutcome,
+    message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RuleOutcome {
+    Skipped,
+    Passed,
+    /// Broken, then fixed and verified
+    Fixed,
+    /// Broken at warning level, a fix was attempted and the event went ahead regardless
+    Warned,
+    Failed,
+    /// Broken during a dry run, where fixes are never attempted
+    NeedsFix,
+}
+
+#[derive(Debug, Serialize)]
+struct HookTrace {
+    path: PathBuf,
+    /// `None` when the hook was killed by a signal
+    exit_code: Option<i32>,
+}
+
+fn with_trace(f: impl FnOnce(&mut WorkflowTrace)) {
+    if let Ok(mut slot) = CURRENT_TRACE.lock()
+        && let Some(trace) = slot.as_mut()
+    {
+        f(trace);
+    }
+}
+
+fn with_current_event(f: impl FnOnce(&mut EventTrace)) {
+    with_trace(|trace| {
+        if let Some(event) = trace
+            .steps
+            .last_mut()
+            .and_then(|step| step.events.last_mut())
+            .filter(|event| event.started.is_some())
+        {
+            f(event);
+        }
+    });
+}
+
+fn elapsed_ms(started: &mut Option<Instant>) -> u128 {
+    started.take().map_or(0, |s| s.elapsed().as_millis())
+}
+
+fn error_value(error: &BGitError) -> Option<serde_json::Value> {
+    serde_json::to_value(error).ok()
+}
+
+pub(crate) fn set_print_trace(enabled: bool) {
+    PRINT_TRACE.store(enabled, Ordering::Relaxed);
+}
+
+/// Begin recording `workflow`, replacing any trace left unfinished
+pub(crate) fn start(workflow: &str, dry_run: bool) {
+    let started_at_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    if let Ok(mut slot) = CURRENT_TRACE.lock() {
+        *slot = Some(WorkflowTrace {
+            workflow: workflow.to_string(),
+            started_at: started_at_ms / 1000,
+            started_at_ms,
+            dry_run,
+            duration_ms: 0,
+            completed: false,
+            error: None,
+            steps: vec![],
+            started: Some(Instant::now()),
+        });
+    }
+}
+
+pub(crate) fn step_started(name: &str, kind: StepKind) {
+    with_trace(|trace| {
+        trace.steps.push(StepTrace {
+            name: name.to_string(),
+            kind,
+            next: None,
+            duration_ms: 0,
+            error: None,
+            events: vec![],
+            started: Some(Instant::now()),
+        })
+    });
+}
+
+/// Close the current step with the name of the step it chose, or the error it failed with
+pub(crate) fn step_finished(outcome: Result<&str, &BGitError>) {
+    with_trace(|trace| {
+        if let Some(step) = trace.steps.last_mut() {
+            step.duration_ms = elapsed_ms(&mut step.started);
+            match outcome {
+                Ok(next) => step.next = Some(next.to_string()),
+                Err(error) => step.error = error_value(error),
+            }
+        }
+    });
+}
+
+pub(crate) fn event_started(name: &str, simulated: Option<&str>) {
+    with_trace(|trace| {
+        if let Some(step) = trace.steps.last_mut() {
+            step.events.push(EventTrace {
+                name: name.to_string(),
+                simulated: simulated.map(str::to_string),
+                result: None,
+                duration_ms: 0,
+                error: None,
+                rules: vec![],
+                hooks: vec![],
+                started: Some(Instant::now()),
+            });
+        }
+    });
+}
+
+pub(crate) fn event_finished(result: &Result<bool, Box<BGitError>>) {
+    with_current_event(|event| {
+        event.duration_ms = elapsed_ms(&mut event.started);
+        match result {
+            Ok(status) => event.result = Some(*status),
+            Err(error) => event.error = error_value(error),
+        }
+    });
+}
+
+pub(crate) fn rule_evaluated(name: &str, level: RuleLevel, outcome: RuleOutcome, message: &str) {
+    with_current_event(|event| {
+        event.rules.push(RuleTrace {
+            name: name.to_string(),
+            level,
+            outcome,
+            message: (!message.is_empty()).then(|| message.to_string()),
+        })
+    });
+}
+
+pub(crate) fn hook_ran(path: &Path, exit_code: Option<i32>) {
+    with_current_event(|event| {
+        event.hooks.push(HookTrace {
+            path: path.to_path_buf(),
+            exit_code,
+        })
+    });
+}
+
+/// Stop recording and save the trace under `.git/bgit/traces`, or print it with `--trace`.
+/// Dry runs change nothing on disk, so their traces are never saved.
+pub(crate) fn finish(result: &Result<bool, Box<BGitError>>) {
+    let Some(mut trace) = CURRENT_TRACE.lock().ok().and_then(|mut slot| slot.take()) else {
+        return;
+    };
+    trace.duration_ms = elapsed_ms(&mut trace.started);
+    match result {
+        Ok(_) => trace.completed = true,
+        Err(error) => trace.error = error_value(error),
+    }
+
+    let json = match serde_json::to_value(&trace)
+        .and_then(|value| serde_json::to_string_pretty(&value).map(|json| (value, json)))
+    {
+        Ok((value, json)) => {
+            if let Ok(mut last) = LAST_TRACE.lock() {
+                *last = Some(value);
+            }
+            json
+        }
+        Err(e) => {
+            warn!("Failed to serialize workflow trace: {e}");
+            return;
+        }
+    };
+
+    if PRINT_TRACE.load(Ordering::Relaxed) {
+        println!("{json}");
+        return;
+    }
+
+    if trace.dry_run {
+        debug!("Dry run, workflow trace not saved");
+        return;
+    }
+
+    // The workflow may have cloned or initialized a repository, so look it up only now
+    let Some(traces_dir) = bgit_state_dir().map(|dir| dir.join("traces")) else {
+        debug!("Not inside a git repository, workflow trace not saved");
+        return;
+    };
+    // The pid keeps runs started in the same millisecond apart
+    let path = traces_dir.join(format!(
+        "{}-{}.json",
+        trace_file_stem(trace.started_at_ms),
+        std::process::id()
+    ));
+    match fs::create_dir_all(&traces_dir).and_then(|_| fs::write(&path, json)) {
+        Ok(()) => {
+            debug!("Workflow trace saved to {}", path.display());
+            prune_traces(&traces_dir);
+        }
+        Err(e) => warn!("Failed to save workflow trace to {}: {e}", path.display()),
+    }
+}
+
+/// The trace of the workflow that finished last, if any ran
+pub(crate) fn take_last() -> Option<serde_json::Value> {
+    LAST_TRACE.lock().ok().and_then(|mut last| last.take())
+}
+
+/// `YYYYMMDDTHHMMSS.mmmZ`, so file names sort chronologically
+fn trace_file_stem(epoch_ms: u64) -> String {
+    let epoch = (epoch_ms / 1000) as i64;
+    let (year, month, day) = civil_from_days(epoch.div_euclid(86400));
+    let seconds_of_day = epoch.rem_euclid(86400);
+    format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}.{:03}Z",
+        seconds_of_day / 3600,
+        (seconds_of_day % 3600) / 60,
+        seconds_of_day % 60,
+        epoch_ms % 1000
+    )
+}
+
+fn prune_traces(traces_dir: &Path) {
+    let Ok(entries) = fs::read_dir(traces_dir) else {
+        return;
+    };
+    let mut traces: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    traces.sort();
+    let excess = traces.len().saturating_sub(MAX_SAVED_TRACES);
+    for old in &traces[..excess] {
+        if let Err(e) = fs::remove_file(old) {
+            debug!("Failed to remove old trace {}: {e}", old.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bgit_error::{BGitErrorWorkflowType, NO_RULE, NO_STEP};
+
+    #[test]
+    fn test_records_steps_events_rules_and_hooks() {
+        start("default", false);
+        step_started("ask_add_mode", StepKind::Prompt);
+        event_started("git_add", None);
+        rule_evaluated("NoSecretsStaged", RuleLevel::Error, RuleOutcome::Passed, "");
+        hook_ran(Path::new(".bgit/hooks/pre_git_add"), Some(0));
+        event_finished(&Ok(true));
+        step_finished(Ok("has_uncommitted"));
+        step_started("has_uncommitted", StepKind::Action);
+        let error = Box::new(BGitError::new(
+            "BGitError",
+            "boom",
+            BGitErrorWorkflowType::ActionStep,
+            NO_STEP,
+            "git_status",
+            NO_RULE,
+        ));
+        step_finished(Err(&error));
+
+        let trace = CURRENT_TRACE.lock().unwrap().take().unwrap();
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(json["workflow"], "default");
+        assert_eq!(json["steps"][0]["kind"], "prompt");
+        assert_eq!(json["steps"][0]["next"], "has_uncommitted");
+        let event = &json["steps"][0]["events"][0];
+        assert_eq!(event["result"], true);
+        assert_eq!(event["rules"][0]["outcome"], "passed");
+        assert_eq!(event["rules"][0]["level"], "Error");
+        assert_eq!(event["hooks"][0]["exit_code"], 0);
+        assert_eq!(json["steps"][1]["next"], serde_json::Value::Null);
+        assert_eq!(json["steps"][1]["error"]["event_name"], "git_status");
+    }
+
+    #[test]
+    fn test_trace_file_stem_sorts_by_time() {
+        assert_eq!(trace_file_stem(0), "19700101T000000.000Z");
+        assert_eq!(trace_file_stem(1_700_000_000_042), "20231114T221320.042Z");
+    }
+}
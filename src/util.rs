@@ -1,4 +1,8 @@
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(test)]
+pub(crate) mod test_support;
 
 /// # Extension Precedence (for Windows)
 /// The module checks for hook files in the following order:
@@ -36,3 +40,62 @@ pub(crate) fn find_hook_with_extension(hook_path: &Path) -> Option<PathBuf> {
 
     None
 }
+
+/// bgit's own state inside the current repository's git dir (`.git/bgit`), which is
+/// never committed nor shared between worktrees
+pub(crate) fn bgit_state_dir() -> Option<PathBuf> {
+    let repo = git2::Repository::discover(Path::new(".")).ok()?;
+    Some(repo.path().join("bgit"))
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian calendar date
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Calendar date (year, month, day) for a count of days since 1970-01-01
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = if days >= 0 { days } else { days - 146096 } / 146097;
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Format a unix timestamp as `YYYY-MM-DD HH:MM +ZZZZ` in the given timezone offset
+pub(crate) fn format_epoch_with_offset(epoch: i64, offset_minutes: i32) -> String {
+    let local = epoch + offset_minutes as i64 * 60;
+    let (year, month, day) = civil_from_days(local.div_euclid(86400));
+    let seconds_of_day = local.rem_euclid(86400);
+    let sign = if offset_minutes < 0 { '-' } else { '+' };
+    let offset = offset_minutes.abs();
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} {sign}{:02}{:02}",
+        seconds_of_day / 3600,
+        (seconds_of_day % 3600) / 60,
+        offset / 60,
+        offset % 60
+    )
+}
+
+/// Whole days elapsed since a unix timestamp, clamped at zero for future timestamps
+pub(crate) fn age_in_days(epoch: i64) -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(epoch);
+    (now - epoch).max(0) as u64 / 86400
+}
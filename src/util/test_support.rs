@@ -0,0 +1,38 @@
+use git2::{Commit, IndexAddOption, Oid, Repository, Signature, Time};
+
+/// Stage everything in the working tree and commit it on top of HEAD
+pub(crate) fn commit_all(repo: &Repository, message: &str) -> Oid {
+    let now = Signature::now("bgit", "bgit@example.com").unwrap().when();
+    commit_all_at(repo, message, now.seconds())
+}
+
+/// Same as [`commit_all`], dated `epoch` seconds
+pub(crate) fn commit_all_at(repo: &Repository, message: &str, epoch: i64) -> Oid {
+    let mut index = repo.index().unwrap();
+    index.add_all(["."], IndexAddOption::DEFAULT, None).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = Signature::new("bgit", "bgit@example.com", &Time::new(epoch, 0)).unwrap();
+    let parents: Vec<Commit> = repo
+        .head()
+        .ok()
+        .and_then(|head| head.peel_to_commit().ok())
+        .into_iter()
+        .collect();
+    let parent_refs: Vec<&Commit> = parents.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parent_refs,
+    )
+    .unwrap()
+}
+
+/// Unix timestamp `days` days before now
+pub(crate) fn days_ago(days: u64) -> i64 {
+    let now = Signature::now("bgit", "bgit@example.com").unwrap().when();
+    now.seconds() - days as i64 * 86400
+}
@@ -1,8 +1,14 @@
 use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
+use crate::checkpoint::Checkpoint;
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{WorkflowRules, WorkflowSteps};
+use crate::events::is_dry_run;
+use crate::prompt;
+use crate::rollback;
 use crate::step::Task::{ActionStepTask, PromptStepTask};
-use crate::step::{Step, Task};
+use crate::step::{STOP, Step, Task};
+use crate::trace::{self, StepKind};
+use crate::workflows::custom::WorkflowGraph;
 use colored::Colorize;
 use git2::{Config, Repository};
 use indicatif::{HumanDuration, ProgressBar, ProgressStyle};
@@ -12,13 +18,20 @@ use std::time::Instant;
 
 const HATCHING_CHICK_EMOJI: &str = "🐣";
 
+/// Suffix marking steps of a `--dry-run`, whose events are only simulated
+fn dry_run_marker() -> &'static str {
+    if is_dry_run() { " (dry run)" } else { "" }
+}
+
 pub(crate) struct WorkflowQueue {
+    name: String,
     init_step: Step,
     pb: ProgressBar,
+    graph: Option<WorkflowGraph>,
 }
 
 impl WorkflowQueue {
-    pub(crate) fn new(init_step: Step) -> Self {
+    pub(crate) fn new(name: &str, init_step: Step) -> Self {
         // Initialize spinner for progress indication
         let pb = ProgressBar::new_spinner();
         pb.enable_steady_tick(Duration::from_millis(200));
@@ -29,7 +42,39 @@ impl WorkflowQueue {
             .unwrap(),
         );
 
-        WorkflowQueue { init_step, pb }
+        WorkflowQueue {
+            name: name.to_string(),
+            init_step,
+            pb,
+            graph: None,
+        }
+    }
+
+    /// Queue for a custom workflow: starts at the graph's start step and lets the graph
+    /// redirect transitions
+    pub(crate) fn from_graph(graph: WorkflowGraph) -> Self {
+        let mut workflow_queue = Self::new(graph.name(), graph.start_step());
+        workflow_queue.graph = Some(graph);
+        workflow_queue
+    }
+
+    /// Start at `task` instead, e.g. the step a failed run stopped at
+    pub(crate) fn resume_at(mut self, task: Task) -> Self {
+        self.init_step = Step::Start(task);
+        self
+    }
+
+    fn route(&self, from: &Task, next_step: Step) -> Step {
+        debug_assert!(
+            from.successors().contains(&Self::step_name(&next_step)),
+            "'{}' continued with '{}', which its `successors` doesn't declare",
+            from.get_name(),
+            Self::step_name(&next_step)
+        );
+        match &self.graph {
+            Some(graph) => graph.route(from.get_name(), next_step),
+            None => next_step,
+        }
     }
 
     fn run_step_and_traverse(
@@ -42,9 +87,10 @@ impl WorkflowQueue {
         match task {
             ActionStepTask(action_step_task) => {
                 eprintln!(
-                    "{} Running Action Step: {}",
+                    "{} Running Action Step: {}{}",
                     HATCHING_CHICK_EMOJI,
-                    action_step_task.get_name().cyan().bold()
+                    action_step_task.get_name().cyan().bold(),
+                    dry_run_marker().yellow()
                 );
                 self.pb.set_message(format!(
                     "Step '{}' in progress...",
@@ -54,11 +100,14 @@ impl WorkflowQueue {
                 let action_step_config_flags = workflow_config_flags
                     .and_then(|flags| flags.get_step_flags(action_step_task.get_name()));
 
+                trace::step_started(action_step_task.get_name(), StepKind::Action);
                 let action_step_result = action_step_task.execute(
                     action_step_config_flags,
                     workflow_rules_config,
                     global_config,
-                )?;
+                );
+                trace::step_finished(Self::step_outcome(&action_step_result));
+                let action_step_result = action_step_result?;
 
                 self.pb.inc(1);
                 self.pb.tick();
@@ -68,9 +117,10 @@ impl WorkflowQueue {
             PromptStepTask(prompt_step_task) => {
                 self.pb.disable_steady_tick();
                 eprintln!(
-                    "{} Running Prompt Step: {}",
+                    "{} Running Prompt Step: {}{}",
                     HATCHING_CHICK_EMOJI,
-                    prompt_step_task.get_name().cyan().bold()
+                    prompt_step_task.get_name().cyan().bold(),
+                    dry_run_marker().yellow()
                 );
 
                 self.pb.set_message(format!(
@@ -82,13 +132,16 @@ impl WorkflowQueue {
                     .and_then(|flags| flags.get_step_flags(prompt_step_task.get_name()));
 
                 // Suspend drawing so dialoguer prompts on stderr are clean
-                let prompt_step_result: Step = self.pb.suspend(|| {
+                trace::step_started(prompt_step_task.get_name(), StepKind::Prompt);
+                let prompt_step_result = self.pb.suspend(|| {
                     prompt_step_task.execute(
                         prompt_step_config_flags,
                         workflow_rules_config,
                         global_config,
                     )
-                })?;
+                });
+                trace::step_finished(Self::step_outcome(&prompt_step_result));
+                let prompt_step_result: Step = prompt_step_result?;
                 self.pb.enable_steady_tick(Duration::from_millis(200));
 
                 self.pb.inc(1);
@@ -99,24 +152,139 @@ impl WorkflowQueue {
         }
     }
 
+    /// After `task` failed: offer to undo what this run changed, or else remember the
+    /// failing step so `bgit --resume` can pick up from there
+    fn recover(&self, task: &Task) {
+        if is_dry_run() {
+            return;
+        }
+        if self.offer_rollback(task) {
+            return;
+        }
+        if let Some(checkpoint) = Checkpoint::save(&self.name, task) {
+            eprintln!(
+                "{}",
+                format!(
+                    "Saved a checkpoint at step '{}', fix the problem and run `bgit --resume` to continue from there",
+                    checkpoint.step
+                )
+                .yellow()
+            );
+        }
+    }
+
+    /// Returns whether the run's changes were rolled back
+    fn offer_rollback(&self, task: &Task) -> bool {
+        let changes = rollback::finish();
+        if changes.is_empty() {
+            return false;
+        }
+
+        eprintln!(
+            "{}",
+            "This run changed the repository before failing:".yellow()
+        );
+        for change in changes.iter().rev() {
+            eprintln!("  - {change}");
+        }
+        let prompt = format!(
+            "Step '{}' failed. Roll back these changes?",
+            task.get_name()
+        );
+        let confirmed = self.pb.suspend(|| {
+            prompt::confirm("workflow.rollback", &prompt, false).unwrap_or_else(|e| {
+                warn!("Not rolling back: {e}");
+                false
+            })
+        });
+        if !confirmed {
+            return false;
+        }
+
+        let mut repo = match Repository::discover(".") {
+            Ok(repo) => repo,
+            Err(e) => {
+                eprintln!("{} {e}", "Rollback failed:".red());
+                return false;
+            }
+        };
+        match rollback::roll_back(&mut repo, &changes) {
+            (undone, None) => {
+                Checkpoint::clear();
+                eprintln!("{}", format!("Rolled back {undone} change(s)").green());
+                true
+            }
+            (undone, Some(e)) => {
+                eprintln!(
+                    "{} {e}. Rolled back {undone} of {} change(s), the rest is left as is.",
+                    "Rollback stopped:".red(),
+                    changes.len()
+                );
+                // Partly rolled back, resuming from the failed step would not be safe
+                Checkpoint::clear();
+                true
+            }
+        }
+    }
+
+    fn step_name(step: &Step) -> &str {
+        match step {
+            Step::Task(task) | Step::Start(task) => task.get_name(),
+            Step::Stop => STOP,
+        }
+    }
+
+    /// Name of the step a finished step chose, for the trace
+    fn step_outcome(result: &Result<Step, Box<BGitError>>) -> Result<&str, &BGitError> {
+        match result {
+            Ok(step) => Ok(Self::step_name(step)),
+            Err(error) => Err(error),
+        }
+    }
+
     pub(crate) fn execute(
         &self,
         workflow_config_flags: Option<&WorkflowSteps>,
         workflow_rules_config: Option<&WorkflowRules>,
         global_config: &BGitGlobalConfig,
+    ) -> Result<bool, Box<BGitError>> {
+        trace::start(&self.name, is_dry_run());
+        rollback::begin();
+        let result = self.traverse(workflow_config_flags, workflow_rules_config, global_config);
+        rollback::finish();
+        trace::finish(&result);
+        result
+    }
+
+    fn traverse(
+        &self,
+        workflow_config_flags: Option<&WorkflowSteps>,
+        workflow_rules_config: Option<&WorkflowRules>,
+        global_config: &BGitGlobalConfig,
     ) -> Result<bool, Box<BGitError>> {
         match &self.init_step {
             Step::Start(task) => {
                 let started = Instant::now();
 
                 Self::warn_unsupported_client_hooks_if_any();
+                if is_dry_run() {
+                    eprintln!(
+                        "{}",
+                        "Dry run: events that would change the repository are only printed, so later steps see it unchanged"
+                            .yellow()
+                    );
+                }
 
-                let mut next_step: Step = self.run_step_and_traverse(
-                    workflow_config_flags,
-                    workflow_rules_config,
-                    global_config,
+                let mut next_step: Step = self.route(
                     task,
-                )?;
+                    self.run_step_and_traverse(
+                        workflow_config_flags,
+                        workflow_rules_config,
+                        global_config,
+                        task,
+                    )
+                    .inspect_err(|_| self.recover(task))?,
+                );
 
                 while next_step != Step::Stop {
                     if let Step::Start(_) = next_step {
@@ -132,12 +300,16 @@ impl WorkflowQueue {
 
                     match next_step {
                         Step::Task(task) => {
-                            next_step = self.run_step_and_traverse(
-                                workflow_config_flags,
-                                workflow_rules_config,
-                                global_config,
+                            next_step = self.route(
                                 &task,
-                            )?;
+                                self.run_step_and_traverse(
+                                    workflow_config_flags,
+                                    workflow_rules_config,
+                                    global_config,
+                                    &task,
+                                )
+                                .inspect_err(|_| self.recover(&task))?,
+                            );
                         }
                         _ => {
                             unreachable!("This code is unreachable")
@@ -148,7 +320,15 @@ impl WorkflowQueue {
                 self.pb.finish_with_message("Workflow complete");
 
                 if next_step == Step::Stop {
-                    println!("Done in {}", HumanDuration(started.elapsed()));
+                    if is_dry_run() {
+                        println!(
+                            "Dry run done in {}, nothing was changed",
+                            HumanDuration(started.elapsed())
+                        );
+                    } else {
+                        Checkpoint::clear();
+                        println!("Done in {}", HumanDuration(started.elapsed()));
+                    }
                     Ok(true)
                 } else {
                     Err(Box::new(BGitError::new(
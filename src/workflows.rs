@@ -1 +1,5 @@
+pub(crate) mod check;
+pub(crate) mod custom;
 pub(crate) mod default;
+pub(crate) mod diagram;
+pub(crate) mod registry;
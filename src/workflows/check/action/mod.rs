@@ -0,0 +1,6 @@
+pub(crate) mod ta01_check_repo_size;
+pub(crate) mod ta02_check_loose_objects;
+pub(crate) mod ta03_check_merged_branches;
+pub(crate) mod ta04_check_stale_branches;
+pub(crate) mod ta05_check_stashes;
+pub(crate) mod ta06_check_large_blobs;
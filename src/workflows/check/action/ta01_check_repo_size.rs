@@ -0,0 +1,112 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::rules::Rule;
+use crate::rules::a14_big_repo_size::IsRepoSizeTooBig;
+use crate::step::PromptStep;
+use crate::step::Task::{ActionStepTask, PromptStepTask};
+use crate::workflows::check::action::ta02_check_loose_objects::CheckLooseObjects;
+use crate::workflows::check::prompt::pa01_ask_repack::AskRepack;
+use crate::{
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
+    step::{ActionStep, Step},
+};
+use colored::Colorize;
+use git2::Repository;
+use indicatif::HumanBytes;
+use std::fs;
+use std::path::Path;
+
+pub(crate) struct CheckRepoSize {
+    name: String,
+}
+
+impl ActionStep for CheckRepoSize {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        CheckRepoSize {
+            name: "check_repo_size".to_owned(),
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_repack", "check_loose_objects"]
+    }
+
+    fn execute(
+        &self,
+        _step_config_flags: Option<&StepFlags>,
+        workflow_rules_config: Option<&WorkflowRules>,
+        _global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        let repo = Repository::discover(Path::new(".")).map_err(|e| {
+            Box::new(BGitError::new(
+                "Not a git repository",
+                &format!("bgit check must be run inside a git repository: {e}"),
+                BGitErrorWorkflowType::ActionStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
+
+        let repo_size_rule = IsRepoSizeTooBig::new(workflow_rules_config);
+        let max_size_bytes = repo_size_rule.get_max_size_mb() * 1024 * 1024;
+
+        let tracked_size = repo_size_rule.calculate_repo_size(&repo).map_err(|e| {
+            Box::new(BGitError::new(
+                "Failed to calculate repository size",
+                &e,
+                BGitErrorWorkflowType::ActionStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
+        let object_db_size = dir_size(&repo.path().join("objects"));
+
+        println!("Tracked files: {}", HumanBytes(tracked_size));
+        println!("Object database: {}", HumanBytes(object_db_size));
+
+        if tracked_size > max_size_bytes {
+            println!(
+                "{}",
+                format!(
+                    "Tracked files exceed the recommended limit of {}. Consider moving large assets to Git LFS.",
+                    HumanBytes(max_size_bytes)
+                )
+                .yellow()
+            );
+        }
+
+        if object_db_size > max_size_bytes {
+            Ok(Step::Task(PromptStepTask(Box::new(
+                AskRepack::new().with_object_db_size(object_db_size),
+            ))))
+        } else {
+            Ok(Step::Task(ActionStepTask(Box::new(
+                CheckLooseObjects::new(),
+            ))))
+        }
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
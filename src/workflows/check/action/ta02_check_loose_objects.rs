@@ -0,0 +1,107 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::constants::DEFAULT_MAX_LOOSE_OBJECTS;
+use crate::events::AtomicEvent;
+use crate::events::git_gc::GitGc;
+use crate::flags::config_flag;
+use crate::step::PromptStep;
+use crate::step::Task::{ActionStepTask, PromptStepTask};
+use crate::workflows::check::action::ta03_check_merged_branches::CheckMergedBranches;
+use crate::workflows::check::prompt::pa02_ask_prune_objects::AskPruneObjects;
+use crate::{
+    bgit_error::BGitError,
+    step::{ActionStep, Step},
+};
+use indicatif::HumanBytes;
+use log::warn;
+
+/// Prune once there are more loose objects than `max_loose_objects`, or any unreachable ones
+fn needs_pruning(loose_count: usize, unreachable_count: usize, max_loose_objects: usize) -> bool {
+    loose_count > max_loose_objects || unreachable_count > 0
+}
+
+pub(crate) struct CheckLooseObjects {
+    name: String,
+}
+
+impl ActionStep for CheckLooseObjects {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        CheckLooseObjects {
+            name: "check_loose_objects".to_owned(),
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_prune_objects", "check_merged_branches"]
+    }
+
+    fn execute(
+        &self,
+        step_config_flags: Option<&StepFlags>,
+        _workflow_rules_config: Option<&WorkflowRules>,
+        global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        let max_loose_objects = step_config_flags
+            .and_then(|flags| {
+                flags.get_flag::<usize>(
+                    config_flag::workflows::check::check_loose_objects::MAX_LOOSE_OBJECTS,
+                )
+            })
+            .unwrap_or(DEFAULT_MAX_LOOSE_OBJECTS);
+
+        let git_gc = GitGc::new(global_config);
+        let loose_stats = git_gc.get_loose_object_stats()?;
+        let unreachable_count = git_gc.get_unreachable_object_count().unwrap_or_else(|e| {
+            warn!("Skipping unreachable object scan: {e:?}");
+            0
+        });
+
+        println!(
+            "Loose objects: {} ({}), unreachable objects: {}",
+            loose_stats.count,
+            HumanBytes(loose_stats.size_bytes),
+            unreachable_count
+        );
+
+        if needs_pruning(loose_stats.count, unreachable_count, max_loose_objects) {
+            Ok(Step::Task(PromptStepTask(Box::new(
+                AskPruneObjects::new().with_counts(loose_stats.count, unreachable_count),
+            ))))
+        } else {
+            Ok(Step::Task(ActionStepTask(Box::new(
+                CheckMergedBranches::new(),
+            ))))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_pruning_past_the_loose_object_threshold() {
+        assert!(!needs_pruning(0, 0, DEFAULT_MAX_LOOSE_OBJECTS));
+        assert!(!needs_pruning(
+            DEFAULT_MAX_LOOSE_OBJECTS,
+            0,
+            DEFAULT_MAX_LOOSE_OBJECTS
+        ));
+        assert!(needs_pruning(
+            DEFAULT_MAX_LOOSE_OBJECTS + 1,
+            0,
+            DEFAULT_MAX_LOOSE_OBJECTS
+        ));
+        // Unreachable objects are worth pruning however few loose objects there are
+        assert!(needs_pruning(0, 1, DEFAULT_MAX_LOOSE_OBJECTS));
+        // A configured threshold replaces the default
+        assert!(needs_pruning(11, 0, 10));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::step::PromptStep;
+use crate::step::Task::{ActionStepTask, PromptStepTask};
+use crate::workflows::check::action::ta04_check_stale_branches::CheckStaleBranches;
+use crate::workflows::check::prompt::pa03_ask_delete_merged_branches::AskDeleteMergedBranches;
+use crate::{
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
+    step::{ActionStep, Step},
+};
+use git2::{BranchType, Repository};
+use std::path::Path;
+
+/// Branches that are never offered for cleanup
+const PROTECTED_BRANCHES: [&str; 3] = ["main", "master", "dev"];
+
+/// A local branch other than the current and protected ones
+pub(crate) struct LocalBranchInfo {
+    pub name: String,
+    /// Commit time of the branch tip as unix timestamp
+    pub tip_time: i64,
+    /// Whether the branch tip is already contained in HEAD
+    pub merged: bool,
+}
+
+pub(crate) fn collect_local_branches(
+    repo: &Repository,
+) -> Result<Vec<LocalBranchInfo>, git2::Error> {
+    let head_oid = match repo.head() {
+        Ok(head) => head.peel_to_commit()?.id(),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+
+    let mut branches = Vec::new();
+    for branch_result in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch_result?;
+        if branch.is_head() {
+            continue;
+        }
+
+        let Some(name) = branch.name()?.map(|n| n.to_string()) else {
+            continue;
+        };
+        if PROTECTED_BRANCHES.contains(&name.as_str()) {
+            continue;
+        }
+
+        let tip = branch.get().peel_to_commit()?;
+        let merged = tip.id() == head_oid || repo.graph_descendant_of(head_oid, tip.id())?;
+
+        branches.push(LocalBranchInfo {
+            name,
+            tip_time: tip.time().seconds(),
+            merged,
+        });
+    }
+
+    Ok(branches)
+}
+
+pub(crate) struct CheckMergedBranches {
+    name: String,
+}
+
+impl ActionStep for CheckMergedBranches {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        CheckMergedBranches {
+            name: "check_merged_branches".to_owned(),
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_delete_merged_branches", "check_stale_branches"]
+    }
+
+    fn execute(
+        &self,
+        _step_config_flags: Option<&StepFlags>,
+        _workflow_rules_config: Option<&WorkflowRules>,
+        _global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        let repo = Repository::discover(Path::new(".")).map_err(|e| self.to_step_error(e))?;
+        let merged_branches: Vec<String> = collect_local_branches(&repo)
+            .map_err(|e| self.to_step_error(e))?
+            .into_iter()
+            .filter(|branch| branch.merged)
+            .map(|branch| branch.name)
+            .collect();
+
+        if merged_branches.is_empty() {
+            println!("No merged branches found.");
+            return Ok(Step::Task(ActionStepTask(Box::new(
+                CheckStaleBranches::new(),
+            ))));
+        }
+
+        println!(
+            "Found {} branch(es) already merged into the current branch: {}",
+            merged_branches.len(),
+            merged_branches.join(", ")
+        );
+        Ok(Step::Task(PromptStepTask(Box::new(
+            AskDeleteMergedBranches::new().with_branches(merged_branches),
+        ))))
+    }
+}
+
+impl CheckMergedBranches {
+    fn to_step_error(&self, e: git2::Error) -> Box<BGitError> {
+        Box::new(BGitError::new(
+            "Failed to inspect branches",
+            &e.to_string(),
+            BGitErrorWorkflowType::ActionStep,
+            &self.name,
+            NO_EVENT,
+            NO_RULE,
+        ))
+    }
+}
@@ -0,0 +1,133 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::constants::DEFAULT_STALE_BRANCH_DAYS;
+use crate::flags::config_flag;
+use crate::step::PromptStep;
+use crate::step::Task::{ActionStepTask, PromptStepTask};
+use crate::util::age_in_days;
+use crate::workflows::check::action::ta03_check_merged_branches::collect_local_branches;
+use crate::workflows::check::action::ta05_check_stashes::CheckStashes;
+use crate::workflows::check::prompt::pa04_ask_delete_stale_branches::AskDeleteStaleBranches;
+use crate::{
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
+    step::{ActionStep, Step},
+};
+use git2::Repository;
+use std::path::Path;
+
+/// Unmerged local branches whose tip is at least `stale_after_days` old, with their age in days
+fn find_stale_branches(
+    repo: &Repository,
+    stale_after_days: u64,
+) -> Result<Vec<(String, u64)>, git2::Error> {
+    Ok(collect_local_branches(repo)?
+        .into_iter()
+        // Merged branches were already offered for cleanup by the previous step
+        .filter(|branch| !branch.merged)
+        .map(|branch| (branch.name, age_in_days(branch.tip_time)))
+        .filter(|(_, age_days)| *age_days >= stale_after_days)
+        .collect())
+}
+
+pub(crate) struct CheckStaleBranches {
+    name: String,
+}
+
+impl ActionStep for CheckStaleBranches {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        CheckStaleBranches {
+            name: "check_stale_branches".to_owned(),
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_delete_stale_branches", "check_stashes"]
+    }
+
+    fn execute(
+        &self,
+        step_config_flags: Option<&StepFlags>,
+        _workflow_rules_config: Option<&WorkflowRules>,
+        _global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        let stale_after_days = step_config_flags
+            .and_then(|flags| {
+                flags.get_flag::<u64>(
+                    config_flag::workflows::check::check_stale_branches::STALE_AFTER_DAYS,
+                )
+            })
+            .unwrap_or(DEFAULT_STALE_BRANCH_DAYS);
+
+        let stale_branches = Repository::discover(Path::new("."))
+            .and_then(|repo| find_stale_branches(&repo, stale_after_days))
+            .map_err(|e| {
+                Box::new(BGitError::new(
+                    "Failed to inspect branches",
+                    &e.to_string(),
+                    BGitErrorWorkflowType::ActionStep,
+                    &self.name,
+                    NO_EVENT,
+                    NO_RULE,
+                ))
+            })?;
+
+        if stale_branches.is_empty() {
+            println!("No stale branches found (threshold: {stale_after_days} days).");
+            return Ok(Step::Task(ActionStepTask(Box::new(CheckStashes::new()))));
+        }
+
+        println!(
+            "Found {} unmerged branch(es) without commits in the last {} days.",
+            stale_branches.len(),
+            stale_after_days
+        );
+        Ok(Step::Task(PromptStepTask(Box::new(
+            AskDeleteStaleBranches::new().with_branches(stale_branches),
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::{commit_all_at, days_ago};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_stale_branches_uses_the_tip_age() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        std::fs::write(temp_dir.path().join("a.txt"), "a").unwrap();
+        let base = commit_all_at(&repo, "base", days_ago(200));
+        let base = repo.find_commit(base).unwrap();
+        let main = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        // Unmerged branches whose tips sit just past and just short of the threshold
+        for (branch, age) in [
+            ("old-feature", DEFAULT_STALE_BRANCH_DAYS + 1),
+            ("recent-feature", DEFAULT_STALE_BRANCH_DAYS - 1),
+        ] {
+            repo.branch(branch, &base, false).unwrap();
+            repo.set_head(&format!("refs/heads/{branch}")).unwrap();
+            std::fs::write(temp_dir.path().join(format!("{branch}.txt")), branch).unwrap();
+            commit_all_at(&repo, branch, days_ago(age));
+            std::fs::remove_file(temp_dir.path().join(format!("{branch}.txt"))).unwrap();
+        }
+        // An old branch already merged into HEAD is left to the merged-branch check
+        repo.branch("merged", &base, false).unwrap();
+        repo.set_head(&format!("refs/heads/{main}")).unwrap();
+
+        let stale = find_stale_branches(&repo, DEFAULT_STALE_BRANCH_DAYS).unwrap();
+        assert_eq!(
+            stale,
+            vec![("old-feature".to_string(), DEFAULT_STALE_BRANCH_DAYS + 1)]
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::constants::DEFAULT_STALE_STASH_DAYS;
+use crate::flags::config_flag;
+use crate::step::PromptStep;
+use crate::step::Task::{ActionStepTask, PromptStepTask};
+use crate::util::age_in_days;
+use crate::workflows::check::action::ta06_check_large_blobs::CheckLargeBlobs;
+use crate::workflows::check::prompt::pa05_ask_drop_stashes::{AskDropStashes, StaleStash};
+use crate::{
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
+    step::{ActionStep, Step},
+};
+use git2::{Oid, Repository};
+use std::path::Path;
+
+/// Stashes at least `stale_after_days` old
+fn find_stale_stashes(
+    repo: &mut Repository,
+    stale_after_days: u64,
+) -> Result<Vec<StaleStash>, git2::Error> {
+    let mut stashes: Vec<(usize, String, Oid)> = Vec::new();
+    repo.stash_foreach(|index, message, oid| {
+        stashes.push((index, message.to_string(), *oid));
+        true
+    })?;
+
+    let mut stale_stashes = Vec::new();
+    for (index, message, oid) in stashes {
+        let age_days = age_in_days(repo.find_commit(oid)?.time().seconds());
+        if age_days >= stale_after_days {
+            stale_stashes.push(StaleStash {
+                index,
+                message,
+                age_days,
+            });
+        }
+    }
+    Ok(stale_stashes)
+}
+
+pub(crate) struct CheckStashes {
+    name: String,
+}
+
+impl ActionStep for CheckStashes {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        CheckStashes {
+            name: "check_stashes".to_owned(),
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_drop_stashes", "check_large_blobs"]
+    }
+
+    fn execute(
+        &self,
+        step_config_flags: Option<&StepFlags>,
+        _workflow_rules_config: Option<&WorkflowRules>,
+        _global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        let stale_after_days = step_config_flags
+            .and_then(|flags| {
+                flags
+                    .get_flag::<u64>(config_flag::workflows::check::check_stashes::STALE_AFTER_DAYS)
+            })
+            .unwrap_or(DEFAULT_STALE_STASH_DAYS);
+
+        let mut repo = Repository::discover(Path::new(".")).map_err(|e| self.to_step_error(e))?;
+
+        let stale_stashes =
+            find_stale_stashes(&mut repo, stale_after_days).map_err(|e| self.to_step_error(e))?;
+
+        if stale_stashes.is_empty() {
+            println!("No forgotten stashes found (threshold: {stale_after_days} days).");
+            return Ok(Step::Task(ActionStepTask(Box::new(CheckLargeBlobs::new()))));
+        }
+
+        println!(
+            "Found {} stash(es) older than {} days.",
+            stale_stashes.len(),
+            stale_after_days
+        );
+        Ok(Step::Task(PromptStepTask(Box::new(
+            AskDropStashes::new().with_stashes(stale_stashes),
+        ))))
+    }
+}
+
+impl CheckStashes {
+    fn to_step_error(&self, e: git2::Error) -> Box<BGitError> {
+        Box::new(BGitError::new(
+            "Failed to inspect stashes",
+            &e.to_string(),
+            BGitErrorWorkflowType::ActionStep,
+            &self.name,
+            NO_EVENT,
+            NO_RULE,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_support::{commit_all, days_ago};
+    use git2::{Signature, Time};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_stale_stashes_uses_the_stash_age() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut repo = Repository::init(temp_dir.path()).unwrap();
+        std::fs::write(temp_dir.path().join("a.txt"), "a").unwrap();
+        commit_all(&repo, "base");
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        // Stashes dated just past and just short of the threshold, newest on top
+        for (message, age) in [
+            ("old work", DEFAULT_STALE_STASH_DAYS + 1),
+            ("recent work", DEFAULT_STALE_STASH_DAYS - 1),
+        ] {
+            std::fs::write(temp_dir.path().join("a.txt"), message).unwrap();
+            let signature =
+                Signature::new("bgit", "bgit@example.com", &Time::new(days_ago(age), 0)).unwrap();
+            repo.stash_save(&signature, message, None).unwrap();
+        }
+
+        let stale = find_stale_stashes(&mut repo, DEFAULT_STALE_STASH_DAYS).unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].index, 1);
+        assert_eq!(stale[0].message, format!("On {branch}: old work"));
+        assert_eq!(stale[0].age_days, DEFAULT_STALE_STASH_DAYS + 1);
+    }
+}
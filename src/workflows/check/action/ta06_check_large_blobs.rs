@@ -0,0 +1,245 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::constants::DEFAULT_MAX_LARGE_FILE_SIZE_IN_BYTES;
+use crate::flags::config_flag;
+use crate::step::PromptStep;
+use crate::step::STOP;
+use crate::step::Task::PromptStepTask;
+use crate::workflows::check::prompt::pa06_ask_purge_large_blobs::AskPurgeLargeBlobs;
+use crate::{
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
+    step::{ActionStep, Step},
+};
+use git2::{ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use indicatif::HumanBytes;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+pub(crate) struct CheckLargeBlobs {
+    name: String,
+}
+
+impl ActionStep for CheckLargeBlobs {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        CheckLargeBlobs {
+            name: "check_large_blobs".to_owned(),
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_purge_large_blobs", STOP]
+    }
+
+    fn execute(
+        &self,
+        step_config_flags: Option<&StepFlags>,
+        _workflow_rules_config: Option<&WorkflowRules>,
+        _global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        let max_blob_size = step_config_flags
+            .and_then(|flags| {
+                flags.get_flag::<u64>(
+                    config_flag::workflows::check::check_large_blobs::MAX_BLOB_SIZE_BYTES,
+                )
+            })
+            .unwrap_or(DEFAULT_MAX_LARGE_FILE_SIZE_IN_BYTES);
+
+        let repo = Repository::discover(Path::new(".")).map_err(|e| self.to_step_error(e))?;
+        let large_blobs =
+            find_large_blobs(&repo, max_blob_size).map_err(|e| self.to_step_error(e))?;
+
+        if large_blobs.is_empty() {
+            println!(
+                "No blobs larger than {} found in history.",
+                HumanBytes(max_blob_size)
+            );
+            return Ok(Step::Stop);
+        }
+
+        println!(
+            "Found {} path(s) in history with blobs larger than {}:",
+            large_blobs.len(),
+            HumanBytes(max_blob_size)
+        );
+        for (path, size) in &large_blobs {
+            println!("  {} ({})", path, HumanBytes(*size));
+        }
+
+        Ok(Step::Task(PromptStepTask(Box::new(
+            AskPurgeLargeBlobs::new().with_blobs(large_blobs),
+        ))))
+    }
+}
+
+impl CheckLargeBlobs {
+    fn to_step_error(&self, e: git2::Error) -> Box<BGitError> {
+        Box::new(BGitError::new(
+            "Failed to scan history for large blobs",
+            &e.to_string(),
+            BGitErrorWorkflowType::ActionStep,
+            &self.name,
+            NO_EVENT,
+            NO_RULE,
+        ))
+    }
+}
+
+/// Walk every commit reachable from any ref and return paths whose blobs exceed `max_size`,
+/// largest first. Each path is reported with the biggest blob it ever pointed to, and a blob
+/// stored at several paths (renamed or copied) is reported under every one of them.
+fn find_large_blobs(repo: &Repository, max_size: u64) -> Result<Vec<(String, u64)>, git2::Error> {
+    let odb = repo.odb()?;
+    let mut revwalk = repo.revwalk()?;
+    // Tags may point at trees or blobs, which a revwalk cannot start from
+    for reference in repo.references()? {
+        if let Ok(commit) = reference?.peel_to_commit() {
+            revwalk.push(commit.id())?;
+        }
+    }
+    if let Ok(head) = repo.head().and_then(|head| head.peel_to_commit()) {
+        revwalk.push(head.id())?;
+    }
+
+    // Keyed by path as well as id, so a moved directory is scanned again under its new name
+    let mut seen_trees: HashSet<(String, Oid)> = HashSet::new();
+    let mut blob_sizes: HashMap<Oid, u64> = HashMap::new();
+    let mut large_paths: HashMap<String, u64> = HashMap::new();
+
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let tree = commit.tree()?;
+        if !seen_trees.insert((String::new(), tree.id())) {
+            continue;
+        }
+
+        tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+            let path = || format!("{}{}", root, entry.name().unwrap_or_default());
+            match entry.kind() {
+                // Identical subtrees at the same path were already scanned
+                Some(ObjectType::Tree) if !seen_trees.insert((path(), entry.id())) => {
+                    return TreeWalkResult::Skip;
+                }
+                Some(ObjectType::Blob) => {
+                    let size = *blob_sizes.entry(entry.id()).or_insert_with(|| {
+                        odb.read_header(entry.id())
+                            .map_or(0, |(size, _)| size as u64)
+                    });
+                    if size > max_size {
+                        let largest = large_paths.entry(path()).or_insert(0);
+                        *largest = (*largest).max(size);
+                    }
+                }
+                _ => {}
+            }
+            TreeWalkResult::Ok
+        })?;
+    }
+
+    let mut large_blobs: Vec<(String, u64)> = large_paths.into_iter().collect();
+    large_blobs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(large_blobs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_large_blobs_reports_removed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let signature = Signature::now("test", "test@example.com").unwrap();
+
+        // Commit a large blob, then delete it again so it only lives in history
+        std::fs::write(temp_dir.path().join("big.bin"), vec![0u8; 4096]).unwrap();
+        std::fs::write(temp_dir.path().join("small.txt"), "hello").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("big.bin")).unwrap();
+        index.add_path(Path::new("small.txt")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let first = repo
+            .commit(Some("HEAD"), &signature, &signature, "add", &tree, &[])
+            .unwrap();
+
+        index.remove_path(Path::new("big.bin")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.find_commit(first).unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "remove",
+            &tree,
+            &[&parent],
+        )
+        .unwrap();
+
+        let large_blobs = find_large_blobs(&repo, 1024).unwrap();
+        assert_eq!(large_blobs, vec![("big.bin".to_string(), 4096)]);
+    }
+
+    #[test]
+    fn test_find_large_blobs_reports_every_path_of_a_moved_blob() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let signature = Signature::now("test", "test@example.com").unwrap();
+        let big = vec![7u8; 4096];
+
+        std::fs::create_dir_all(temp_dir.path().join("assets")).unwrap();
+        std::fs::write(temp_dir.path().join("assets/big.bin"), &big).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("assets/big.bin")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let first = repo
+            .commit(Some("HEAD"), &signature, &signature, "add", &tree, &[])
+            .unwrap();
+
+        // Rename the directory, and copy the blob to a second path as well
+        std::fs::create_dir_all(temp_dir.path().join("static")).unwrap();
+        std::fs::rename(
+            temp_dir.path().join("assets/big.bin"),
+            temp_dir.path().join("static/big.bin"),
+        )
+        .unwrap();
+        std::fs::write(temp_dir.path().join("copy.bin"), &big).unwrap();
+        index.remove_path(Path::new("assets/big.bin")).unwrap();
+        index.add_path(Path::new("static/big.bin")).unwrap();
+        index.add_path(Path::new("copy.bin")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.find_commit(first).unwrap();
+        let second = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "move",
+                &tree,
+                &[&parent],
+            )
+            .unwrap();
+
+        // A tag pointing at a tree must not stop the scan
+        repo.tag_lightweight("tree-tag", tree.as_object(), false)
+            .unwrap();
+        assert_eq!(repo.find_commit(second).unwrap().tree_id(), tree.id());
+
+        let large_blobs = find_large_blobs(&repo, 1024).unwrap();
+        assert_eq!(
+            large_blobs,
+            vec![
+                ("assets/big.bin".to_string(), 4096),
+                ("copy.bin".to_string(), 4096),
+                ("static/big.bin".to_string(), 4096),
+            ]
+        );
+    }
+}
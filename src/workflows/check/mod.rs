@@ -0,0 +1,2 @@
+pub(crate) mod action;
+pub(crate) mod prompt;
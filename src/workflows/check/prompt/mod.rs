@@ -0,0 +1,6 @@
+pub(crate) mod pa01_ask_repack;
+pub(crate) mod pa02_ask_prune_objects;
+pub(crate) mod pa03_ask_delete_merged_branches;
+pub(crate) mod pa04_ask_delete_stale_branches;
+pub(crate) mod pa05_ask_drop_stashes;
+pub(crate) mod pa06_ask_purge_large_blobs;
@@ -0,0 +1,94 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::events::AtomicEvent;
+use crate::events::git_gc::GitGc;
+use crate::prompt;
+use crate::rules::Rule;
+use crate::rules::a01_git_install::IsGitInstalledLocally;
+use crate::step::ActionStep;
+use crate::step::Task::ActionStepTask;
+use crate::workflows::check::action::ta02_check_loose_objects::CheckLooseObjects;
+use crate::{
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
+    step::{PromptStep, Step},
+};
+use indicatif::HumanBytes;
+use serde_json::{Value, json};
+
+pub(crate) struct AskRepack {
+    name: String,
+    object_db_size: u64,
+}
+
+impl AskRepack {
+    pub fn with_object_db_size(mut self, object_db_size: u64) -> Self {
+        self.object_db_size = object_db_size;
+        self
+    }
+}
+
+impl PromptStep for AskRepack {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        AskRepack {
+            name: "ask_repack".to_owned(),
+            object_db_size: 0,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &["check_loose_objects"]
+    }
+
+    fn checkpoint_context(&self) -> Option<Value> {
+        Some(json!({ "object_db_size": self.object_db_size }))
+    }
+
+    fn restore_context(&mut self, mut context: Value) -> Result<(), serde_json::Error> {
+        self.object_db_size = serde_json::from_value(context["object_db_size"].take())?;
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        _step_config_flags: Option<&StepFlags>,
+        workflow_rules_config: Option<&WorkflowRules>,
+        global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        let confirmed = prompt::confirm(
+            &self.name,
+            &format!(
+                "Object database is {}. Run an aggressive repack (git gc --aggressive)? This can take a while",
+                HumanBytes(self.object_db_size)
+            ),
+            false,
+        )
+            .map_err(|e| {
+                Box::new(BGitError::new(
+                    "Input Error",
+                    &e.to_string(),
+                    BGitErrorWorkflowType::PromptStep,
+                    &self.name,
+                    NO_EVENT,
+                    NO_RULE,
+                ))
+            })?;
+
+        if confirmed {
+            let mut git_gc = GitGc::new(global_config).with_aggressive(true);
+            git_gc.add_pre_check_rule(Box::new(IsGitInstalledLocally::new(workflow_rules_config)));
+            git_gc.execute()?;
+            println!("Repository repacked successfully.");
+        }
+
+        Ok(Step::Task(ActionStepTask(Box::new(
+            CheckLooseObjects::new(),
+        ))))
+    }
+}
@@ -0,0 +1,100 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::events::AtomicEvent;
+use crate::events::git_gc::GitGc;
+use crate::prompt;
+use crate::rules::Rule;
+use crate::rules::a01_git_install::IsGitInstalledLocally;
+use crate::step::ActionStep;
+use crate::step::Task::ActionStepTask;
+use crate::workflows::check::action::ta03_check_merged_branches::CheckMergedBranches;
+use crate::{
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
+    step::{PromptStep, Step},
+};
+use serde_json::{Value, json};
+
+pub(crate) struct AskPruneObjects {
+    name: String,
+    loose_count: usize,
+    unreachable_count: usize,
+}
+
+impl AskPruneObjects {
+    pub fn with_counts(mut self, loose_count: usize, unreachable_count: usize) -> Self {
+        self.loose_count = loose_count;
+        self.unreachable_count = unreachable_count;
+        self
+    }
+}
+
+impl PromptStep for AskPruneObjects {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        AskPruneObjects {
+            name: "ask_prune_objects".to_owned(),
+            loose_count: 0,
+            unreachable_count: 0,
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &["check_merged_branches"]
+    }
+
+    fn checkpoint_context(&self) -> Option<Value> {
+        Some(json!({
+            "loose_count": self.loose_count,
+            "unreachable_count": self.unreachable_count,
+        }))
+    }
+
+    fn restore_context(&mut self, mut context: Value) -> Result<(), serde_json::Error> {
+        self.loose_count = serde_json::from_value(context["loose_count"].take())?;
+        self.unreachable_count = serde_json::from_value(context["unreachable_count"].take())?;
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        _step_config_flags: Option<&StepFlags>,
+        workflow_rules_config: Option<&WorkflowRules>,
+        global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        let confirmed = prompt::confirm(
+            &self.name,
+            &format!(
+                "Pack {} loose object(s) and prune {} unreachable object(s) with git gc?",
+                self.loose_count, self.unreachable_count
+            ),
+            true,
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "Input Error",
+                &e.to_string(),
+                BGitErrorWorkflowType::PromptStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
+
+        if confirmed {
+            let mut git_gc = GitGc::new(global_config);
+            git_gc.add_pre_check_rule(Box::new(IsGitInstalledLocally::new(workflow_rules_config)));
+            git_gc.execute()?;
+            println!("Garbage collection completed successfully.");
+        }
+
+        Ok(Step::Task(ActionStepTask(Box::new(
+            CheckMergedBranches::new(),
+        ))))
+    }
+}
@@ -0,0 +1,95 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::events::AtomicEvent;
+use crate::events::git_branch::GitBranch;
+use crate::prompt;
+use crate::step::ActionStep;
+use crate::step::Task::ActionStepTask;
+use crate::workflows::check::action::ta04_check_stale_branches::CheckStaleBranches;
+use crate::{
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
+    step::{PromptStep, Step},
+};
+use serde_json::{Value, json};
+
+pub(crate) struct AskDeleteMergedBranches {
+    name: String,
+    branches: Vec<String>,
+}
+
+impl AskDeleteMergedBranches {
+    pub fn with_branches(mut self, branches: Vec<String>) -> Self {
+        self.branches = branches;
+        self
+    }
+}
+
+impl PromptStep for AskDeleteMergedBranches {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        AskDeleteMergedBranches {
+            name: "ask_delete_merged_branches".to_owned(),
+            branches: vec![],
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &["check_stale_branches"]
+    }
+
+    fn checkpoint_context(&self) -> Option<Value> {
+        Some(json!({ "branches": self.branches }))
+    }
+
+    fn restore_context(&mut self, mut context: Value) -> Result<(), serde_json::Error> {
+        self.branches = serde_json::from_value(context["branches"].take())?;
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        _step_config_flags: Option<&StepFlags>,
+        _workflow_rules_config: Option<&WorkflowRules>,
+        global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        // Merged branches are safe to delete, so preselect all of them
+        let defaults = vec![true; self.branches.len()];
+        let selections = prompt::multi_select(
+            &self.name,
+            "Select merged branches to delete (space to toggle, enter to confirm)",
+            &self.branches,
+            &defaults,
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "Input Error",
+                &e.to_string(),
+                BGitErrorWorkflowType::PromptStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
+
+        let selected: Vec<String> = selections
+            .into_iter()
+            .map(|index| self.branches[index].clone())
+            .collect();
+
+        if !selected.is_empty() {
+            let count = selected.len();
+            GitBranch::delete_branches(global_config, selected).execute()?;
+            println!("Deleted {count} merged branch(es).");
+        }
+
+        Ok(Step::Task(ActionStepTask(Box::new(
+            CheckStaleBranches::new(),
+        ))))
+    }
+}
@@ -0,0 +1,99 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::events::AtomicEvent;
+use crate::events::git_branch::GitBranch;
+use crate::prompt;
+use crate::step::ActionStep;
+use crate::step::Task::ActionStepTask;
+use crate::workflows::check::action::ta05_check_stashes::CheckStashes;
+use crate::{
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
+    step::{PromptStep, Step},
+};
+use serde_json::{Value, json};
+
+pub(crate) struct AskDeleteStaleBranches {
+    name: String,
+    /// Branch name with the age of its last commit in days
+    branches: Vec<(String, u64)>,
+}
+
+impl AskDeleteStaleBranches {
+    pub fn with_branches(mut self, branches: Vec<(String, u64)>) -> Self {
+        self.branches = branches;
+        self
+    }
+}
+
+impl PromptStep for AskDeleteStaleBranches {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        AskDeleteStaleBranches {
+            name: "ask_delete_stale_branches".to_owned(),
+            branches: vec![],
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &["check_stashes"]
+    }
+
+    fn checkpoint_context(&self) -> Option<Value> {
+        Some(json!({ "branches": self.branches }))
+    }
+
+    fn restore_context(&mut self, mut context: Value) -> Result<(), serde_json::Error> {
+        self.branches = serde_json::from_value(context["branches"].take())?;
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        _step_config_flags: Option<&StepFlags>,
+        _workflow_rules_config: Option<&WorkflowRules>,
+        global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        let items: Vec<String> = self
+            .branches
+            .iter()
+            .map(|(name, age_days)| format!("{name} (last commit {age_days} days ago)"))
+            .collect();
+
+        // These branches hold unmerged work, so nothing is selected by default
+        let selections = prompt::multi_select(
+            &self.name,
+            "Select stale branches to delete. Unmerged commits on them will be lost",
+            &items,
+            &[],
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "Input Error",
+                &e.to_string(),
+                BGitErrorWorkflowType::PromptStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
+
+        let selected: Vec<String> = selections
+            .into_iter()
+            .map(|index| self.branches[index].0.clone())
+            .collect();
+
+        if !selected.is_empty() {
+            let count = selected.len();
+            GitBranch::delete_branches(global_config, selected).execute()?;
+            println!("Deleted {count} stale branch(es).");
+        }
+
+        Ok(Step::Task(ActionStepTask(Box::new(CheckStashes::new()))))
+    }
+}
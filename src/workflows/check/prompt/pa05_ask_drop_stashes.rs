@@ -0,0 +1,113 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::events::AtomicEvent;
+use crate::events::git_stash::GitStash;
+use crate::prompt;
+use crate::step::ActionStep;
+use crate::step::Task::ActionStepTask;
+use crate::workflows::check::action::ta06_check_large_blobs::CheckLargeBlobs;
+use crate::{
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
+    step::{PromptStep, Step},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct StaleStash {
+    pub index: usize,
+    pub message: String,
+    pub age_days: u64,
+}
+
+pub(crate) struct AskDropStashes {
+    name: String,
+    stashes: Vec<StaleStash>,
+}
+
+impl AskDropStashes {
+    pub fn with_stashes(mut self, stashes: Vec<StaleStash>) -> Self {
+        self.stashes = stashes;
+        self
+    }
+}
+
+impl PromptStep for AskDropStashes {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        AskDropStashes {
+            name: "ask_drop_stashes".to_owned(),
+            stashes: vec![],
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &["check_large_blobs"]
+    }
+
+    fn checkpoint_context(&self) -> Option<Value> {
+        Some(json!({ "stashes": self.stashes }))
+    }
+
+    fn restore_context(&mut self, mut context: Value) -> Result<(), serde_json::Error> {
+        self.stashes = serde_json::from_value(context["stashes"].take())?;
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        _step_config_flags: Option<&StepFlags>,
+        _workflow_rules_config: Option<&WorkflowRules>,
+        global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        let items: Vec<String> = self
+            .stashes
+            .iter()
+            .map(|stash| {
+                format!(
+                    "stash@{{{}}}: {} ({} days old)",
+                    stash.index, stash.message, stash.age_days
+                )
+            })
+            .collect();
+
+        let selections = prompt::multi_select(
+            &self.name,
+            "Select stashes to drop (space to toggle, enter to confirm)",
+            &items,
+            &[],
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "Input Error",
+                &e.to_string(),
+                BGitErrorWorkflowType::PromptStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
+
+        let mut indices: Vec<usize> = selections
+            .into_iter()
+            .map(|selection| self.stashes[selection].index)
+            .collect();
+        // Drop from the highest index down so the remaining indices stay valid
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+
+        for index in &indices {
+            GitStash::drop_stash(global_config, Some(*index)).execute()?;
+        }
+        if !indices.is_empty() {
+            println!("Dropped {} stash(es).", indices.len());
+        }
+
+        Ok(Step::Task(ActionStepTask(Box::new(CheckLargeBlobs::new()))))
+    }
+}
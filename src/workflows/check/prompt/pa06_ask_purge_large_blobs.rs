@@ -0,0 +1,122 @@
+use crate::config::global::BGitGlobalConfig;
+use crate::config::local::{StepFlags, WorkflowRules};
+use crate::events::AtomicEvent;
+use crate::events::git_filter_repo::GitFilterRepo;
+use crate::prompt::{self, PromptError};
+use crate::rules::Rule;
+use crate::rules::a01_git_install::IsGitInstalledLocally;
+use crate::step::STOP;
+use crate::{
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
+    step::{PromptStep, Step},
+};
+use colored::Colorize;
+use indicatif::HumanBytes;
+use serde_json::{Value, json};
+
+pub(crate) struct AskPurgeLargeBlobs {
+    name: String,
+    /// Path with the size of the largest blob it pointed to
+    blobs: Vec<(String, u64)>,
+}
+
+impl AskPurgeLargeBlobs {
+    pub fn with_blobs(mut self, blobs: Vec<(String, u64)>) -> Self {
+        self.blobs = blobs;
+        self
+    }
+
+    fn to_input_error(&self, e: PromptError) -> Box<BGitError> {
+        Box::new(BGitError::new(
+            "Input Error",
+            &e.to_string(),
+            BGitErrorWorkflowType::PromptStep,
+            &self.name,
+            NO_EVENT,
+            NO_RULE,
+        ))
+    }
+}
+
+impl PromptStep for AskPurgeLargeBlobs {
+    fn new() -> Self
+    where
+        Self: Sized,
+    {
+        AskPurgeLargeBlobs {
+            name: "ask_purge_large_blobs".to_owned(),
+            blobs: vec![],
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn successors(&self) -> &'static [&'static str] {
+        &[STOP]
+    }
+
+    fn checkpoint_context(&self) -> Option<Value> {
+        Some(json!({ "blobs": self.blobs }))
+    }
+
+    fn restore_context(&mut self, mut context: Value) -> Result<(), serde_json::Error> {
+        self.blobs = serde_json::from_value(context["blobs"].take())?;
+        Ok(())
+    }
+
+    fn execute(
+        &self,
+        _step_config_flags: Option<&StepFlags>,
+        workflow_rules_config: Option<&WorkflowRules>,
+        global_config: &BGitGlobalConfig,
+    ) -> Result<Step, Box<BGitError>> {
+        let items: Vec<String> = self
+            .blobs
+            .iter()
+            .map(|(path, size)| format!("{path} ({})", HumanBytes(*size)))
+            .collect();
+
+        let selections = prompt::multi_select(
+            &self.name,
+            "Select paths to remove from the entire history (leave empty to skip)",
+            &items,
+            &[],
+        )
+        .map_err(|e| self.to_input_error(e))?;
+
+        if selections.is_empty() {
+            return Ok(Step::Stop);
+        }
+
+        println!(
+            "{}",
+            "Removing files from history rewrites every affected commit. Collaborators will need to re-clone, and you will have to force push."
+                .yellow()
+        );
+        let confirmed = prompt::confirm(
+            &format!("{}_confirm", self.name),
+            "Rewrite history now?",
+            false,
+        )
+        .map_err(|e| self.to_input_error(e))?;
+
+        if confirmed {
+            let paths: Vec<String> = selections
+                .into_iter()
+                .map(|index| self.blobs[index].0.clone())
+                .collect();
+
+            let mut git_filter_repo = GitFilterRepo::remove_paths(global_config, paths);
+            git_filter_repo
+                .add_pre_check_rule(Box::new(IsGitInstalledLocally::new(workflow_rules_config)));
+            git_filter_repo.execute()?;
+            println!(
+                "History rewritten. Run `git push --force-with-lease` for each affected branch."
+            );
+        }
+
+        Ok(Step::Stop)
+    }
+}
@@ -0,0 +1,172 @@
+use crate::bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE, NO_STEP};
+use crate::config::local::CustomWorkflow;
+use crate::step::{STOP, Step};
+use crate::workflows::registry;
+use log::debug;
+
+/// A custom workflow checked against the step registry, ready to drive a `WorkflowQueue`.
+///
+/// Each step still decides its own outcome; the graph only redirects where the workflow
+/// goes next. An outcome is named after the step the built-in flow would continue with
+/// (or `stop`), so `[custom_workflows.x.steps.is_branch_main] on.ask_if_same_feat = "stop"`
+/// ends the workflow whenever the current branch is not the main branch.
+pub(crate) struct WorkflowGraph {
+    name: String,
+    workflow: CustomWorkflow,
+}
+
+impl WorkflowGraph {
+    pub fn build(name: &str, workflow: &CustomWorkflow) -> Result<Self, Box<BGitError>> {
+        if !registry::is_registered(&workflow.start) {
+            return Err(to_workflow_error(
+                name,
+                &format!("start step '{}' is not a known step", workflow.start),
+            ));
+        }
+
+        for (step, transitions) in &workflow.steps {
+            if !registry::is_registered(step) {
+                return Err(to_workflow_error(
+                    name,
+                    &format!("'{step}' under [steps] is not a known step"),
+                ));
+            }
+            let targets = transitions
+                .on
+                .iter()
+                .flat_map(|(outcome, target)| [outcome, target])
+                .chain(transitions.next.iter());
+            for target in targets {
+                if target != STOP && !registry::is_registered(target) {
+                    return Err(to_workflow_error(
+                        name,
+                        &format!("transition of '{step}' refers to unknown step '{target}'"),
+                    ));
+                }
+            }
+        }
+
+        Ok(WorkflowGraph {
+            name: name.to_string(),
+            workflow: workflow.clone(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn start_step(&self) -> Step {
+        let task = registry::new_task(&self.workflow.start).expect("validated in build");
+        Step::Start(task)
+    }
+
+    pub fn start_name(&self) -> &str {
+        &self.workflow.start
+    }
+
+    /// Name of the step (or `stop`) the workflow continues with when `from` chose `outcome`
+    pub fn target<'a>(&'a self, from: &str, outcome: &'a str) -> &'a str {
+        self.workflow
+            .steps
+            .get(from)
+            .and_then(|transitions| transitions.on.get(outcome).or(transitions.next.as_ref()))
+            .map_or(outcome, String::as_str)
+    }
+
+    /// Where the workflow goes after `from`, given the step `from` itself chose.
+    /// The chosen step is kept as is when the graph agrees with it, so any state it
+    /// carries (a commit message, a list of branches) survives.
+    pub fn route(&self, from: &str, chosen: Step) -> Step {
+        let outcome = match &chosen {
+            Step::Task(task) => task.get_name(),
+            Step::Stop => STOP,
+            Step::Start(_) => return chosen,
+        };
+
+        let target = self.target(from, outcome);
+        if target == outcome {
+            return chosen;
+        }
+        debug!(
+            "Workflow '{}' sends '{from}' to '{target}' instead of '{outcome}'",
+            self.name
+        );
+        if target == STOP {
+            Step::Stop
+        } else {
+            Step::Task(registry::new_task(target).expect("validated in build"))
+        }
+    }
+}
+
+fn to_workflow_error(name: &str, message: &str) -> Box<BGitError> {
+    Box::new(BGitError::new(
+        "Invalid custom workflow",
+        &format!("[custom_workflows.{name}]: {message}"),
+        BGitErrorWorkflowType::Config,
+        NO_STEP,
+        NO_EVENT,
+        NO_RULE,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::local::BGitConfig;
+    use crate::step::{ActionStep, PromptStep, Task};
+    use crate::workflows::default::action::ta11_is_sole_contributor::IsSoleContributor;
+    use crate::workflows::default::prompt::pa09_ask_branch_name::AskBranchName;
+    use crate::workflows::default::prompt::pa10_ask_same_feat::AskIfSameFeat;
+
+    fn graph(toml_content: &str) -> Result<WorkflowGraph, Box<BGitError>> {
+        let config: BGitConfig = toml::from_str(toml_content).unwrap();
+        WorkflowGraph::build("hotfix", config.get_custom_workflow("hotfix").unwrap())
+    }
+
+    #[test]
+    fn test_routes_by_outcome_then_next() {
+        let graph = graph(
+            r#"
+[custom_workflows.hotfix]
+start = "has_unstaged"
+
+[custom_workflows.hotfix.steps.is_branch_main]
+on.is_sole_contributor = "ask_branch_name"
+next = "stop"
+"#,
+        )
+        .unwrap();
+        assert!(
+            graph.start_step()
+                == Step::Start(Task::ActionStepTask(Box::new(
+                    crate::workflows::default::action::ta04_has_unstaged::HasUnstaged::new()
+                )))
+        );
+
+        let main = Step::Task(Task::ActionStepTask(Box::new(IsSoleContributor::new())));
+        let branch = Step::Task(Task::PromptStepTask(Box::new(AskBranchName::new())));
+        assert!(graph.route("is_branch_main", main) == branch);
+
+        let feature = Step::Task(Task::PromptStepTask(Box::new(AskIfSameFeat::new())));
+        assert!(graph.route("is_branch_main", feature) == Step::Stop);
+
+        // Steps without overrides keep the built-in flow
+        let feature = Step::Task(Task::PromptStepTask(Box::new(AskIfSameFeat::new())));
+        let kept = graph.route("has_uncommitted", feature);
+        assert!(kept == Step::Task(Task::PromptStepTask(Box::new(AskIfSameFeat::new()))));
+    }
+
+    #[test]
+    fn test_rejects_unknown_steps() {
+        assert!(graph("[custom_workflows.hotfix]\nstart = \"deploy\"\n").is_err());
+        assert!(
+            graph(
+                "[custom_workflows.hotfix]\nstart = \"has_unstaged\"\n\
+                 [custom_workflows.hotfix.steps.has_unstaged]\nnext = \"deploy\"\n"
+            )
+            .is_err()
+        );
+    }
+}
@@ -32,6 +32,10 @@ impl ActionStep for IsGitRepo {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["has_stash", "ask_to_init_git"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
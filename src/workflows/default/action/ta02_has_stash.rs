@@ -1,6 +1,7 @@
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
 use crate::step::PromptStep;
+use crate::step::STOP;
 use crate::step::Task::ActionStepTask;
 use crate::step::Task::PromptStepTask;
 use crate::workflows::default::action::ta04_has_unstaged::HasUnstaged;
@@ -28,6 +29,10 @@ impl ActionStep for HasStash {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_pop_stash", "has_unstaged", STOP]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
@@ -28,6 +28,10 @@ impl ActionStep for PopStash {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_to_add"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
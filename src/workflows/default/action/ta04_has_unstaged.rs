@@ -28,6 +28,10 @@ impl ActionStep for HasUnstaged {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_to_add", "has_uncommitted"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
@@ -27,6 +27,10 @@ impl ActionStep for HasUncommitted {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["is_branch_main", "is_pushed_pulled"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
@@ -24,6 +24,10 @@ impl ActionStep for IsPushedPulled {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_push_pull"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
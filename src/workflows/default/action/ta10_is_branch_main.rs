@@ -30,6 +30,10 @@ impl ActionStep for IsBranchMain {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["is_sole_contributor", "ask_if_same_feat"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
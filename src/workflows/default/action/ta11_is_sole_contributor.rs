@@ -29,6 +29,10 @@ impl ActionStep for IsSoleContributor {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_branch_name", "ask_commit"]
+    }
+
     fn execute(
         &self,
         step_config_flags: Option<&StepFlags>,
@@ -9,6 +9,7 @@ use crate::{
     bgit_error::BGitError,
     step::{ActionStep, Step},
 };
+use serde_json::{Value, json};
 pub(crate) struct MoveChanges {
     name: String,
     target_branch_name: Option<String>,
@@ -31,6 +32,23 @@ impl ActionStep for MoveChanges {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_commit"]
+    }
+
+    fn checkpoint_context(&self) -> Option<Value> {
+        Some(json!({
+            "target_branch_name": self.target_branch_name,
+            "stash_message": self.stash_message,
+        }))
+    }
+
+    fn restore_context(&mut self, mut context: Value) -> Result<(), serde_json::Error> {
+        self.target_branch_name = serde_json::from_value(context["target_branch_name"].take())?;
+        self.stash_message = serde_json::from_value(context["stash_message"].take())?;
+        Ok(())
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
@@ -1,7 +1,9 @@
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
+use crate::prompt::{self, PromptError};
+use crate::step::STOP;
 use crate::{
-    bgit_error::BGitError,
+    bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
     step::{
         PromptStep, Step,
         Task::{self},
@@ -10,7 +12,6 @@ use crate::{
         prompt::pa02_ask_to_clone_git::CloneGitRepo, prompt::pa03_init_git_repo::InitGitRepo,
     },
 };
-use dialoguer::{Input, Select, theme::ColorfulTheme};
 
 pub(crate) struct AskToInitCloneGit {
     name: String,
@@ -30,6 +31,10 @@ impl PromptStep for AskToInitCloneGit {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["init_git_repo", "clone_repo", STOP]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
@@ -42,21 +47,18 @@ impl PromptStep for AskToInitCloneGit {
             "Cancel",
         ];
 
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("What would you like to do?")
-            .default(0)
-            .items(&options)
-            .interact()
-            .unwrap();
+        let selection = prompt::select(&self.name, "What would you like to do?", &options, 0)
+            .map_err(|e| self.to_input_error(e))?;
 
         match selection {
             // Initialize a new repository
             0 => {
-                let path: String = Input::with_theme(&ColorfulTheme::default())
-                    .with_prompt("Enter path (. for current path)")
-                    .default(".".into())
-                    .interact_text()
-                    .unwrap();
+                let path = prompt::input(
+                    &format!("{}_path", self.name),
+                    "Enter path (. for current path)",
+                    Some("."),
+                )
+                .map_err(|e| self.to_input_error(e))?;
 
                 println!("Initializing Git repository at: {path}");
 
@@ -80,3 +82,16 @@ impl PromptStep for AskToInitCloneGit {
         }
     }
 }
+
+impl AskToInitCloneGit {
+    fn to_input_error(&self, e: PromptError) -> Box<BGitError> {
+        Box::new(BGitError::new(
+            "Input Error",
+            &e.to_string(),
+            BGitErrorWorkflowType::PromptStep,
+            &self.name,
+            NO_EVENT,
+            NO_RULE,
+        ))
+    }
+}
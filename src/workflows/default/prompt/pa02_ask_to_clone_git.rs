@@ -1,6 +1,7 @@
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
-use dialoguer::{Confirm, Input};
+use crate::prompt;
+use crate::step::STOP;
 
 use crate::rules::Rule;
 use crate::{
@@ -28,6 +29,10 @@ impl PromptStep for CloneGitRepo {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &[STOP]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
@@ -35,19 +40,21 @@ impl PromptStep for CloneGitRepo {
         global_config: &BGitGlobalConfig,
     ) -> Result<Step, Box<BGitError>> {
         // Take link input in cli
-        let clone_link: String = Input::new()
-            .with_prompt("Enter the link to the repository you want to clone")
-            .interact()
-            .map_err(|e| {
-                Box::new(BGitError::new(
-                    "Input Error",
-                    &e.to_string(),
-                    BGitErrorWorkflowType::ActionStep,
-                    &self.name,
-                    "",
-                    "",
-                ))
-            })?;
+        let clone_link: String = prompt::input(
+            &self.name,
+            "Enter the link to the repository you want to clone",
+            None,
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "Input Error",
+                &e.to_string(),
+                BGitErrorWorkflowType::ActionStep,
+                &self.name,
+                "",
+                "",
+            ))
+        })?;
 
         // Check if repository is potentially large
         let suggest_shallow = clone_link.contains("large")
@@ -56,10 +63,11 @@ impl PromptStep for CloneGitRepo {
 
         let mut shallow_clone = false;
         if suggest_shallow {
-            shallow_clone = Confirm::new()
-                .with_prompt("This repository might be large. Would you like to perform a shallow clone? (faster, but with limited history)")
-                .default(true)
-                .interact()
+            shallow_clone = prompt::confirm(
+                &format!("{}_shallow", self.name),
+                "This repository might be large. Would you like to perform a shallow clone? (faster, but with limited history)",
+                true,
+            )
                 .map_err(|e| Box::new(BGitError::new("Input Error", &e.to_string(), BGitErrorWorkflowType::ActionStep, &self.name, "", "")))?;
         }
 
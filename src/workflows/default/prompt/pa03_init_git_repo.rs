@@ -1,12 +1,15 @@
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
 use crate::rules::Rule;
+use crate::step::STOP;
 use crate::{
     bgit_error::BGitError,
     events::{AtomicEvent, git_init::GitInit},
     rules::a01_git_install::IsGitInstalledLocally,
+    rules::a10_git_default_config::GitDefaultConfig,
     step::{PromptStep, Step},
 };
+use serde_json::{Value, json};
 pub(crate) struct InitGitRepo {
     name: String,
     path: String,
@@ -30,6 +33,19 @@ impl PromptStep for InitGitRepo {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &[STOP]
+    }
+
+    fn checkpoint_context(&self) -> Option<Value> {
+        Some(json!({ "path": self.path }))
+    }
+
+    fn restore_context(&mut self, mut context: Value) -> Result<(), serde_json::Error> {
+        self.path = serde_json::from_value(context["path"].take())?;
+        Ok(())
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
@@ -38,6 +54,7 @@ impl PromptStep for InitGitRepo {
     ) -> Result<Step, Box<BGitError>> {
         let mut git_init = GitInit::new(global_config).with_path(&self.path);
         git_init.add_pre_check_rule(Box::new(IsGitInstalledLocally::new(workflow_rules_config)));
+        git_init.add_pre_check_rule(Box::new(GitDefaultConfig::new(workflow_rules_config)));
         git_init.execute()?;
         Ok(Step::Stop)
     }
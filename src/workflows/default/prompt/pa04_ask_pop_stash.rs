@@ -1,12 +1,12 @@
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
+use crate::prompt;
 use crate::workflows::default::action::ta03_pop_stash::PopStash;
 use crate::workflows::default::action::ta04_has_unstaged::HasUnstaged;
 use crate::{
     bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
     step::{ActionStep, PromptStep, Step, Task::ActionStepTask},
 };
-use dialoguer::{Select, theme::ColorfulTheme};
 
 pub(crate) struct AskPopStash {
     name: String,
@@ -26,27 +26,32 @@ impl PromptStep for AskPopStash {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["pop_stash", "has_unstaged"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
         _workflow_rules_config: Option<&WorkflowRules>,
         _global_config: &BGitGlobalConfig,
     ) -> Result<Step, Box<BGitError>> {
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Do you want to pop the stash?")
-            .default(0)
-            .items(["Yes", "No"])
-            .interact()
-            .map_err(|e| {
-                Box::new(BGitError::new(
-                    "Input Error",
-                    &e.to_string(),
-                    BGitErrorWorkflowType::PromptStep,
-                    &self.name,
-                    NO_EVENT,
-                    NO_RULE,
-                ))
-            })?;
+        let selection = prompt::select(
+            &self.name,
+            "Do you want to pop the stash?",
+            &["Yes", "No"],
+            0,
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "Input Error",
+                &e.to_string(),
+                BGitErrorWorkflowType::PromptStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
 
         match selection {
             0 => Ok(Step::Task(ActionStepTask(Box::new(PopStash::new())))),
@@ -1,12 +1,12 @@
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
+use crate::prompt;
 use crate::step::Task::PromptStepTask;
 use crate::workflows::default::prompt::pa05x_ask_add_mode::AskAddMode;
 use crate::{
     bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
     step::{PromptStep, Step},
 };
-use dialoguer::{Select, theme::ColorfulTheme};
 
 use super::pa06_ask_restore_changes::AskToRestore;
 pub(crate) struct AskToAdd {
@@ -27,27 +27,32 @@ impl PromptStep for AskToAdd {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_add_mode", "ask_to_restore"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
         _workflow_rules_config: Option<&WorkflowRules>,
         _global_config: &BGitGlobalConfig,
     ) -> Result<Step, Box<BGitError>> {
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Do you want to add the unstaged files?")
-            .default(0)
-            .items(["Yes", "No"])
-            .interact()
-            .map_err(|e| {
-                Box::new(BGitError::new(
-                    "Input Error",
-                    &e.to_string(),
-                    BGitErrorWorkflowType::PromptStep,
-                    &self.name,
-                    NO_EVENT,
-                    NO_RULE,
-                ))
-            })?;
+        let selection = prompt::select(
+            &self.name,
+            "Do you want to add the unstaged files?",
+            &["Yes", "No"],
+            0,
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "Input Error",
+                &e.to_string(),
+                BGitErrorWorkflowType::PromptStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
 
         match selection {
             0 => Ok(Step::Task(PromptStepTask(Box::new(AskAddMode::new())))),
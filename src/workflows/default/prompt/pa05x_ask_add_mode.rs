@@ -2,19 +2,20 @@ use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
 use crate::events::git_add::{AddMode, GitAdd};
 use crate::events::{AtomicEvent, git_status};
+use crate::prompt;
 use crate::rules::Rule;
 use crate::rules::a12_no_secrets_staged::NoSecretsStaged;
 use crate::rules::a12b_no_secret_files_staged::NoSecretFilesStaged;
+use crate::rules::a15_file_not_gitignored::FileNotGitignored;
 use crate::rules::a16_no_large_file::NoLargeFile;
 use crate::step::ActionStep;
+use crate::step::STOP;
 use crate::step::Task::ActionStepTask;
 use crate::workflows::default::action::ta07_has_uncommitted::HasUncommitted;
 use crate::{
     bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
     step::{PromptStep, Step},
 };
-
-use dialoguer::{MultiSelect, Select, theme::ColorfulTheme};
 pub(crate) struct AskAddMode {
     name: String,
 }
@@ -33,6 +34,10 @@ impl PromptStep for AskAddMode {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["has_uncommitted", STOP]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
@@ -40,12 +45,8 @@ impl PromptStep for AskAddMode {
         global_config: &BGitGlobalConfig,
     ) -> Result<Step, Box<BGitError>> {
         let options = vec!["Add all unstaged files", "Select specific files to add"];
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Choose add mode:")
-            .default(0)
-            .items(&options)
-            .interact()
-            .map_err(|e| {
+        let selection =
+            prompt::select(&self.name, "Choose add mode:", &options, 0).map_err(|e| {
                 Box::new(BGitError::new(
                     "BGitError",
                     &format!("Failed to get user selection: {e}"),
@@ -56,6 +57,7 @@ impl PromptStep for AskAddMode {
                 ))
             })?;
 
+        let mut file_not_gitignored = FileNotGitignored::new(workflow_rules_config);
         let add_mode = match selection {
             0 => AddMode::All,
             1 => {
@@ -65,6 +67,7 @@ impl PromptStep for AskAddMode {
                     println!("No files selected.");
                     return Ok(Step::Stop);
                 }
+                file_not_gitignored = file_not_gitignored.with_paths(selected_files.clone());
                 AddMode::Selective(selected_files)
             }
             _ => AddMode::All,
@@ -75,6 +78,7 @@ impl PromptStep for AskAddMode {
 
         git_add.add_pre_check_rule(Box::new(NoSecretsStaged::new(workflow_rules_config)));
         git_add.add_pre_check_rule(Box::new(NoSecretFilesStaged::new(workflow_rules_config)));
+        git_add.add_pre_check_rule(Box::new(file_not_gitignored));
         git_add.add_pre_check_rule(Box::new(NoLargeFile::new(workflow_rules_config)));
 
         git_add.execute()?;
@@ -105,20 +109,22 @@ impl AskAddMode {
             return Ok(vec![]);
         }
 
-        let selections = MultiSelect::with_theme(&ColorfulTheme::default())
-            .with_prompt("Select files to add (use Space to select, Enter to confirm):")
-            .items(&file_display)
-            .interact()
-            .map_err(|e| {
-                Box::new(BGitError::new(
-                    "BGitError",
-                    &format!("Failed to get file selections: {e}"),
-                    BGitErrorWorkflowType::AtomicEvent,
-                    NO_EVENT,
-                    &self.name,
-                    NO_RULE,
-                ))
-            })?;
+        let selections = prompt::multi_select(
+            &format!("{}_files", self.name),
+            "Select files to add (use Space to select, Enter to confirm):",
+            &file_display,
+            &[],
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "BGitError",
+                &format!("Failed to get file selections: {e}"),
+                BGitErrorWorkflowType::AtomicEvent,
+                NO_EVENT,
+                &self.name,
+                NO_RULE,
+            ))
+        })?;
 
         if selections.is_empty() {
             return Ok(vec![]);
@@ -1,13 +1,13 @@
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
 use crate::events::AtomicEvent;
+use crate::prompt;
 use crate::workflows::default::action::ta07_has_uncommitted::HasUncommitted;
 use crate::{
     bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
     events::git_restore::{GitRestore, RestoreMode},
     step::{ActionStep, PromptStep, Step, Task::ActionStepTask},
 };
-use dialoguer::{MultiSelect, theme::ColorfulTheme};
 pub(crate) struct AskToRestore {
     name: String,
 }
@@ -26,19 +26,25 @@ impl PromptStep for AskToRestore {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["has_uncommitted"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
         _workflow_rules_config: Option<&WorkflowRules>,
         global_config: &BGitGlobalConfig,
     ) -> Result<Step, Box<BGitError>> {
-        let selections = MultiSelect::with_theme(&ColorfulTheme::default())
-            .with_prompt("Select restore options (Space to select, Enter to confirm, or press Enter with nothing selected to cancel)")            
-            .items([
+        let selections = prompt::multi_select(
+            &self.name,
+            "Select restore options (Space to select, Enter to confirm, or press Enter with nothing selected to cancel)",
+            &[
                 "Restore unstaged changes (git restore .)",
                 "Unstage all files (git restore --staged .)",
-            ])
-            .interact()
+            ],
+            &[],
+        )
             .map_err(|e| {
                 Box::new(BGitError::new(
                     "Input Error",
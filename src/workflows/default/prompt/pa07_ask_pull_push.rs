@@ -1,11 +1,12 @@
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
+use crate::prompt;
+use crate::step::STOP;
 use crate::workflows::default::prompt::pa13_pull_push::PullAndPush;
 use crate::{
     bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
     step::{PromptStep, Step, Task::PromptStepTask},
 };
-use dialoguer::{Select, theme::ColorfulTheme};
 pub(crate) struct AskPushPull {
     name: String,
 }
@@ -24,27 +25,32 @@ impl PromptStep for AskPushPull {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["pull_and_push", STOP]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
         _workflow_rules_config: Option<&WorkflowRules>,
         _global_config: &BGitGlobalConfig,
     ) -> Result<Step, Box<BGitError>> {
-        let selection: usize = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Do you want to pull/push commits?")
-            .default(0)
-            .items(["Yes", "No"])
-            .interact()
-            .map_err(|e| {
-                Box::new(BGitError::new(
-                    "Input Error",
-                    &e.to_string(),
-                    BGitErrorWorkflowType::PromptStep,
-                    &self.name,
-                    NO_EVENT,
-                    NO_RULE,
-                ))
-            })?;
+        let selection: usize = prompt::select(
+            &self.name,
+            "Do you want to pull/push commits?",
+            &["Yes", "No"],
+            0,
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "Input Error",
+                &e.to_string(),
+                BGitErrorWorkflowType::PromptStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
 
         match selection {
             0 => Ok(Step::Task(PromptStepTask(Box::new(PullAndPush::new())))),
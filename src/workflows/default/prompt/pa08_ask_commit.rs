@@ -1,6 +1,7 @@
 use super::pa11_ask_ai_commit_msg::AskAICommitMessage;
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
+use crate::prompt;
 use crate::step::ActionStep;
 use crate::step::Task::ActionStepTask;
 use crate::step::Task::PromptStepTask;
@@ -9,7 +10,6 @@ use crate::{
     step::{PromptStep, Step},
     workflows::default::action::ta08_is_pulled_pushed::IsPushedPulled,
 };
-use dialoguer::{Select, theme::ColorfulTheme};
 pub(crate) struct AskCommit {
     name: String,
 }
@@ -28,27 +28,32 @@ impl PromptStep for AskCommit {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_ai_commit_message", "is_pushed_pulled"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
         _workflow_rules_config: Option<&WorkflowRules>,
         _global_config: &BGitGlobalConfig,
     ) -> Result<Step, Box<BGitError>> {
-        let selection: usize = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Do you want to commit changes?")
-            .default(0)
-            .items(["Yes", "No"])
-            .interact()
-            .map_err(|e| {
-                Box::new(BGitError::new(
-                    "Input Error",
-                    &e.to_string(),
-                    BGitErrorWorkflowType::PromptStep,
-                    &self.name,
-                    NO_EVENT,
-                    NO_RULE,
-                ))
-            })?;
+        let selection: usize = prompt::select(
+            &self.name,
+            "Do you want to commit changes?",
+            &["Yes", "No"],
+            0,
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "Input Error",
+                &e.to_string(),
+                BGitErrorWorkflowType::PromptStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
 
         match selection {
             0 => Ok(Step::Task(PromptStepTask(Box::new(
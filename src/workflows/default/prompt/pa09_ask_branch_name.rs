@@ -1,11 +1,11 @@
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
+use crate::prompt;
 use crate::{
     bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
     step::{ActionStep, PromptStep, Step, Task::ActionStepTask},
     workflows::default::action::ta12_move_changes::MoveChanges,
 };
-use dialoguer::{Input, theme::ColorfulTheme};
 
 pub(crate) struct AskBranchName {
     name: String,
@@ -25,16 +25,18 @@ impl PromptStep for AskBranchName {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["move_changes"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
         _workflow_rules_config: Option<&WorkflowRules>,
         _global_config: &BGitGlobalConfig,
     ) -> Result<Step, Box<BGitError>> {
-        let branch_name: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Enter branch name")
-            .interact()
-            .map_err(|e| {
+        let branch_name: String =
+            prompt::input(&self.name, "Enter branch name", None).map_err(|e| {
                 Box::new(BGitError::new(
                     "Input Error",
                     &e.to_string(),
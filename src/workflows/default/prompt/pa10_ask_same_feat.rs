@@ -1,5 +1,6 @@
 use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
+use crate::prompt;
 use crate::step::Task::PromptStepTask;
 use crate::workflows::default::prompt::pa09_ask_branch_name::AskBranchName;
 use crate::{
@@ -7,8 +8,6 @@ use crate::{
     step::{PromptStep, Step},
     workflows::default::prompt::pa08_ask_commit::AskCommit,
 };
-use dialoguer::Select;
-use dialoguer::theme::ColorfulTheme;
 pub(crate) struct AskIfSameFeat {
     name: String,
 }
@@ -27,6 +26,10 @@ impl PromptStep for AskIfSameFeat {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["ask_commit", "ask_branch_name"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
@@ -34,21 +37,22 @@ impl PromptStep for AskIfSameFeat {
         _global_config: &BGitGlobalConfig,
     ) -> Result<Step, Box<BGitError>> {
         let options = vec!["Yes", "No"];
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Are you working on the same feature as older?")
-            .default(1) // Default to "No" (index 1)
-            .items(&options)
-            .interact()
-            .map_err(|e| {
-                Box::new(BGitError::new(
-                    "Input Error",
-                    &e.to_string(),
-                    BGitErrorWorkflowType::PromptStep,
-                    &self.name,
-                    NO_EVENT,
-                    NO_RULE,
-                ))
-            })?;
+        let selection = prompt::select(
+            &self.name,
+            "Are you working on the same feature as older?",
+            &options,
+            1, // Default to "No" (index 1)
+        )
+        .map_err(|e| {
+            Box::new(BGitError::new(
+                "Input Error",
+                &e.to_string(),
+                BGitErrorWorkflowType::PromptStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
 
         let is_same_feature = selection == 0;
 
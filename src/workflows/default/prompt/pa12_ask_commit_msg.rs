@@ -2,10 +2,13 @@ use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
 use crate::events::AtomicEvent;
 use crate::events::git_commit::GitCommit;
+use crate::prompt;
 use crate::rules::Rule;
 use crate::rules::a02_git_name_email_setup::GitNameEmailSetup;
+use crate::rules::a09_commit_gpg_sign::CommitGpgSign;
 use crate::rules::a12_no_secrets_staged::NoSecretsStaged;
 use crate::rules::a12b_no_secret_files_staged::NoSecretFilesStaged;
+use crate::rules::a13_git_lfs::GitLfs;
 use crate::rules::a16_no_large_file::NoLargeFile;
 use crate::rules::a17_conventional_commit_message::ConventionalCommitMessage;
 use crate::step::ActionStep;
@@ -15,7 +18,6 @@ use crate::{
     bgit_error::{BGitError, BGitErrorWorkflowType, NO_EVENT, NO_RULE},
     step::{PromptStep, Step},
 };
-use dialoguer::{Input, theme::ColorfulTheme};
 pub(crate) struct AskHumanCommitMessage {
     name: String,
 }
@@ -34,25 +36,27 @@ impl PromptStep for AskHumanCommitMessage {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["is_pushed_pulled"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
         workflow_rules_config: Option<&WorkflowRules>,
         global_config: &BGitGlobalConfig,
     ) -> Result<Step, Box<BGitError>> {
-        let commit_message: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("Enter your commit message")
-            .interact_text()
+        let commit_message: String = prompt::input(&self.name, "Enter your commit message", None)
             .map_err(|e| {
-                Box::new(BGitError::new(
-                    "Input Error",
-                    &e.to_string(),
-                    BGitErrorWorkflowType::PromptStep,
-                    &self.name,
-                    NO_EVENT,
-                    NO_RULE,
-                ))
-            })?;
+            Box::new(BGitError::new(
+                "Input Error",
+                &e.to_string(),
+                BGitErrorWorkflowType::PromptStep,
+                &self.name,
+                NO_EVENT,
+                NO_RULE,
+            ))
+        })?;
 
         // Check if commit message is empty
         if commit_message.trim().is_empty() {
@@ -75,7 +79,9 @@ impl PromptStep for AskHumanCommitMessage {
         git_commit.add_pre_check_rule(Box::new(NoSecretsStaged::new(workflow_rules_config)));
         git_commit.add_pre_check_rule(Box::new(NoSecretFilesStaged::new(workflow_rules_config)));
         git_commit.add_pre_check_rule(Box::new(NoLargeFile::new(workflow_rules_config)));
+        git_commit.add_pre_check_rule(Box::new(GitLfs::new(workflow_rules_config)));
         git_commit.add_pre_check_rule(Box::new(GitNameEmailSetup::new(workflow_rules_config)));
+        git_commit.add_pre_check_rule(Box::new(CommitGpgSign::new(workflow_rules_config)));
 
         git_commit.execute()?;
 
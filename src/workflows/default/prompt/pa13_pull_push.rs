@@ -3,8 +3,10 @@ use crate::config::local::{StepFlags, WorkflowRules};
 use crate::events::AtomicEvent;
 use crate::events::git_pull::GitPull;
 use crate::events::git_push::GitPush;
+use crate::step::STOP;
 
 use crate::rules::Rule;
+use crate::rules::a11_git_remote_http_ssh::GitRemoteHttpSsh;
 use crate::rules::a14_big_repo_size::IsRepoSizeTooBig;
 use crate::rules::a18_remote_exists::RemoteExists;
 use crate::step::PromptStep;
@@ -28,6 +30,10 @@ impl PromptStep for PullAndPush {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &[STOP]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
@@ -36,6 +42,10 @@ impl PromptStep for PullAndPush {
     ) -> Result<Step, Box<BGitError>> {
         let mut git_pull = GitPull::new(global_config).with_rebase(true);
         git_pull.add_pre_check_rule(Box::new(RemoteExists::new(workflow_rules_config)));
+        // Pull is the first network operation, so the remote URL is checked before any traffic
+        git_pull.add_pre_check_rule(Box::new(
+            GitRemoteHttpSsh::new(workflow_rules_config).with_auth(&global_config.auth),
+        ));
 
         match git_pull.execute() {
             Ok(_) => {
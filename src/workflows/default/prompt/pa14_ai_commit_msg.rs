@@ -2,10 +2,13 @@ use crate::config::global::BGitGlobalConfig;
 use crate::config::local::{StepFlags, WorkflowRules};
 use crate::events::git_commit::GitCommit;
 use crate::llm_tools::conventional_commit_tool::ValidateConventionalCommit;
+use crate::prompt;
 use crate::rules::Rule;
 use crate::rules::a02_git_name_email_setup::GitNameEmailSetup;
+use crate::rules::a09_commit_gpg_sign::CommitGpgSign;
 use crate::rules::a12_no_secrets_staged::NoSecretsStaged;
 use crate::rules::a12b_no_secret_files_staged::NoSecretFilesStaged;
+use crate::rules::a13_git_lfs::GitLfs;
 use crate::rules::a16_no_large_file::NoLargeFile;
 use crate::rules::a17_conventional_commit_message::ConventionalCommitMessage;
 use crate::step::Task::ActionStepTask;
@@ -14,7 +17,6 @@ use crate::{
     bgit_error::BGitError,
     step::{ActionStep, PromptStep, Step},
 };
-use dialoguer::{Confirm, Password, theme::ColorfulTheme};
 use git2::{DiffOptions, Repository};
 use log::debug;
 use rig::providers::gemini::completion::gemini_api_types::{
@@ -45,6 +47,10 @@ impl PromptStep for AICommit {
         &self.name
     }
 
+    fn successors(&self) -> &'static [&'static str] {
+        &["is_pushed_pulled"]
+    }
+
     fn execute(
         &self,
         _step_config_flags: Option<&StepFlags>,
@@ -55,24 +61,21 @@ impl PromptStep for AICommit {
         let api_key = if let Some(key) = &self.api_key {
             key.clone()
         } else if let Some(k) = global_config.get_google_api_key() {
-            k.to_string()
+            k
         } else if let Ok(k) = std::env::var("GOOGLE_API_KEY") {
             k
         } else {
             // Prompt user for Google API key (hidden input)
-            Password::with_theme(&ColorfulTheme::default())
-                .with_prompt("Enter your Google API Key")
-                .interact()
-                .map_err(|e| {
-                    Box::new(BGitError::new(
-                        "BGitError",
-                        &format!("Failed to read Google API Key: {e}"),
-                        crate::bgit_error::BGitErrorWorkflowType::ActionStep,
-                        &self.name,
-                        crate::bgit_error::NO_EVENT,
-                        crate::bgit_error::NO_RULE,
-                    ))
-                })?
+            prompt::password(&self.name, "Enter your Google API Key", false).map_err(|e| {
+                Box::new(BGitError::new(
+                    "BGitError",
+                    &format!("Failed to read Google API Key: {e}"),
+                    crate::bgit_error::BGitErrorWorkflowType::ActionStep,
+                    &self.name,
+                    crate::bgit_error::NO_EVENT,
+                    crate::bgit_error::NO_RULE,
+                ))
+            })?
         };
 
         if api_key.trim().is_empty() {
@@ -89,11 +92,12 @@ impl PromptStep for AICommit {
         // Offer to save key to global config if not already present or different
         let existing = global_config.get_google_api_key();
         if existing.map(|e| e != api_key).unwrap_or(true) {
-            let confirm = Confirm::with_theme(&ColorfulTheme::default())
-                .with_prompt("Save Google API Key to global config? (stored base64-encoded)")
-                .default(true)
-                .interact()
-                .unwrap_or(false);
+            let confirm = prompt::confirm(
+                &format!("{}_save_key", self.name),
+                "Save Google API Key to global config? (stored in the encrypted vault)",
+                true,
+            )
+            .unwrap_or(false);
             if confirm {
                 let mut cfg_owned = global_config.clone();
                 cfg_owned.integrations.google_api_key = Some(api_key.clone());
@@ -137,7 +141,9 @@ impl PromptStep for AICommit {
         git_commit.add_pre_check_rule(Box::new(NoSecretsStaged::new(workflow_rules_config)));
         git_commit.add_pre_check_rule(Box::new(NoSecretFilesStaged::new(workflow_rules_config)));
         git_commit.add_pre_check_rule(Box::new(NoLargeFile::new(workflow_rules_config)));
+        git_commit.add_pre_check_rule(Box::new(GitLfs::new(workflow_rules_config)));
         git_commit.add_pre_check_rule(Box::new(GitNameEmailSetup::new(workflow_rules_config)));
+        git_commit.add_pre_check_rule(Box::new(CommitGpgSign::new(workflow_rules_config)));
 
         git_commit.execute()?;
 
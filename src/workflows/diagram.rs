@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+
+use crate::step::{STOP, Task};
+use crate::workflows::custom::WorkflowGraph;
+use crate::workflows::registry;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NodeKind {
+    Action,
+    Prompt,
+    Stop,
+}
+
+/// The steps a workflow can reach from its start step, as declared by each step's
+/// `successors`, ready to be rendered as Graphviz DOT or Mermaid
+pub(crate) struct StepDiagram {
+    name: String,
+    start: String,
+    nodes: Vec<(String, NodeKind)>,
+    edges: Vec<(String, String)>,
+}
+
+impl StepDiagram {
+    /// Diagram of `default`, `check` or another workflow built into bgit
+    pub fn built_in(name: &str) -> Option<Self> {
+        let (_, start) = registry::BUILT_IN_WORKFLOWS
+            .iter()
+            .find(|(workflow, _)| *workflow == name)?;
+        Some(Self::walk(name, start, |_, outcome| outcome.to_string()))
+    }
+
+    /// Diagram of a custom workflow, with its transition overrides applied
+    pub fn custom(graph: &WorkflowGraph) -> Self {
+        Self::walk(graph.name(), graph.start_name(), |from, outcome| {
+            graph.target(from, outcome).to_string()
+        })
+    }
+
+    /// Breadth-first walk from `start`, sending each declared successor through `route`
+    fn walk(name: &str, start: &str, route: impl Fn(&str, &str) -> String) -> Self {
+        let mut diagram = StepDiagram {
+            name: name.to_string(),
+            start: start.to_string(),
+            nodes: vec![],
+            edges: vec![],
+        };
+        let mut queue = VecDeque::from([start.to_string()]);
+
+        while let Some(step) = queue.pop_front() {
+            if diagram.nodes.iter().any(|(node, _)| *node == step) {
+                continue;
+            }
+            if step == STOP {
+                diagram.nodes.push((step, NodeKind::Stop));
+                continue;
+            }
+            let task = registry::new_task(&step).expect("successors are registered steps");
+            let kind = match task {
+                Task::ActionStepTask(_) => NodeKind::Action,
+                Task::PromptStepTask(_) => NodeKind::Prompt,
+            };
+
+            for successor in task.successors() {
+                let target = route(&step, successor);
+                let edge = (step.clone(), target.clone());
+                if !diagram.edges.contains(&edge) {
+                    diagram.edges.push(edge);
+                }
+                queue.push_back(target);
+            }
+            diagram.nodes.push((step, kind));
+        }
+        diagram
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = format!("digraph \"{}\" {{\n", self.name);
+        out.push_str("    node [fontname=\"Helvetica\"];\n");
+        for (node, kind) in &self.nodes {
+            let shape = match kind {
+                NodeKind::Action => "box",
+                NodeKind::Prompt => "ellipse",
+                NodeKind::Stop => "doublecircle",
+            };
+            let style = if *node == self.start {
+                ", style=bold"
+            } else {
+                ""
+            };
+            out.push_str(&format!("    \"{node}\" [shape={shape}{style}];\n"));
+        }
+        for (from, to) in &self.edges {
+            out.push_str(&format!("    \"{from}\" -> \"{to}\";\n"));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart TD\n");
+        for (node, kind) in &self.nodes {
+            let shape = match kind {
+                NodeKind::Action => format!("{node}[{node}]"),
+                NodeKind::Prompt => format!("{node}([{node}])"),
+                NodeKind::Stop => format!("{node}(({node}))"),
+            };
+            out.push_str(&format!("    {shape}\n"));
+        }
+        for (from, to) in &self.edges {
+            out.push_str(&format!("    {from} --> {to}\n"));
+        }
+        out.push_str("    classDef start stroke-width:3px\n");
+        out.push_str(&format!("    class {} start\n", self.start));
+        out
+    }
+}
+
+#[cfg(test)]
+impl StepDiagram {
+    fn has_edge(&self, from: &str, to: &str) -> bool {
+        self.edges.iter().any(|(f, t)| f == from && t == to)
+    }
+
+    fn has_node(&self, name: &str) -> bool {
+        self.nodes.iter().any(|(node, _)| node == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::local::BGitConfig;
+
+    #[test]
+    fn test_default_workflow_reaches_every_default_step() {
+        let diagram = StepDiagram::built_in("default").unwrap();
+        assert!(diagram.has_edge("is_git_repo", "has_stash"));
+        assert!(diagram.has_edge("ask_push_pull", STOP));
+        assert!(diagram.has_node("ai_commit"));
+        assert!(!diagram.has_node("check_repo_size"));
+
+        let dot = diagram.to_dot();
+        assert!(dot.starts_with("digraph \"default\" {"));
+        assert!(dot.contains("\"is_git_repo\" [shape=box, style=bold];"));
+        assert!(dot.contains("\"ask_commit\" [shape=ellipse];"));
+        assert!(
+            diagram
+                .to_mermaid()
+                .contains("    has_stash --> ask_pop_stash\n")
+        );
+        assert!(StepDiagram::built_in("deploy").is_none());
+    }
+
+    #[test]
+    fn test_custom_workflow_applies_overrides() {
+        let config: BGitConfig = toml::from_str(
+            r#"
+[custom_workflows.hotfix]
+start = "has_uncommitted"
+
+[custom_workflows.hotfix.steps.is_branch_main]
+next = "stop"
+"#,
+        )
+        .unwrap();
+        let graph =
+            WorkflowGraph::build("hotfix", config.get_custom_workflow("hotfix").unwrap()).unwrap();
+        let diagram = StepDiagram::custom(&graph);
+
+        assert!(diagram.has_edge("is_branch_main", STOP));
+        assert!(!diagram.has_node("is_sole_contributor"));
+        assert!(!diagram.has_node("is_git_repo"));
+    }
+}
@@ -0,0 +1,177 @@
+use crate::step::Task::{self, ActionStepTask, PromptStepTask};
+use crate::step::{ActionStep, PromptStep};
+use crate::workflows::check::action::{
+    ta01_check_repo_size::CheckRepoSize, ta02_check_loose_objects::CheckLooseObjects,
+    ta03_check_merged_branches::CheckMergedBranches, ta04_check_stale_branches::CheckStaleBranches,
+    ta05_check_stashes::CheckStashes, ta06_check_large_blobs::CheckLargeBlobs,
+};
+use crate::workflows::check::prompt::{
+    pa01_ask_repack::AskRepack, pa02_ask_prune_objects::AskPruneObjects,
+    pa03_ask_delete_merged_branches::AskDeleteMergedBranches,
+    pa04_ask_delete_stale_branches::AskDeleteStaleBranches, pa05_ask_drop_stashes::AskDropStashes,
+    pa06_ask_purge_large_blobs::AskPurgeLargeBlobs,
+};
+use crate::workflows::default::action::{
+    ta01_is_git_repo::IsGitRepo, ta02_has_stash::HasStash, ta03_pop_stash::PopStash,
+    ta04_has_unstaged::HasUnstaged, ta07_has_uncommitted::HasUncommitted,
+    ta08_is_pulled_pushed::IsPushedPulled, ta10_is_branch_main::IsBranchMain,
+    ta11_is_sole_contributor::IsSoleContributor, ta12_move_changes::MoveChanges,
+};
+use crate::workflows::default::prompt::{
+    pa01_ask_to_init_clone_git::AskToInitCloneGit, pa02_ask_to_clone_git::CloneGitRepo,
+    pa03_init_git_repo::InitGitRepo, pa04_ask_pop_stash::AskPopStash, pa05_ask_to_add::AskToAdd,
+    pa05x_ask_add_mode::AskAddMode, pa06_ask_restore_changes::AskToRestore,
+    pa07_ask_pull_push::AskPushPull, pa08_ask_commit::AskCommit,
+    pa09_ask_branch_name::AskBranchName, pa10_ask_same_feat::AskIfSameFeat,
+    pa11_ask_ai_commit_msg::AskAICommitMessage, pa12_ask_commit_msg::AskHumanCommitMessage,
+    pa13_pull_push::PullAndPush, pa14_ai_commit_msg::AICommit,
+};
+
+type StepBuilder = fn() -> Task;
+
+/// Workflows built into bgit, with the step each starts at
+pub(crate) const BUILT_IN_WORKFLOWS: &[(&str, &str)] =
+    &[("default", "is_git_repo"), ("check", "check_repo_size")];
+
+/// Every step custom workflows can refer to, by the name its `get_name` returns.
+///
+/// Steps built fresh from here start without the context a predecessor would hand them
+/// (e.g. `ask_repack` needs the size `check_repo_size` measured), so those only do useful
+/// work when reached from that predecessor.
+const STEPS: &[(&str, StepBuilder)] = &[
+    ("is_git_repo", || ActionStepTask(Box::new(IsGitRepo::new()))),
+    ("has_stash", || ActionStepTask(Box::new(HasStash::new()))),
+    ("pop_stash", || ActionStepTask(Box::new(PopStash::new()))),
+    ("has_unstaged", || {
+        ActionStepTask(Box::new(HasUnstaged::new()))
+    }),
+    ("has_uncommitted", || {
+        ActionStepTask(Box::new(HasUncommitted::new()))
+    }),
+    ("is_pushed_pulled", || {
+        ActionStepTask(Box::new(IsPushedPulled::new()))
+    }),
+    ("is_branch_main", || {
+        ActionStepTask(Box::new(IsBranchMain::new()))
+    }),
+    ("is_sole_contributor", || {
+        ActionStepTask(Box::new(IsSoleContributor::new()))
+    }),
+    ("move_changes", || {
+        ActionStepTask(Box::new(MoveChanges::new()))
+    }),
+    ("ask_to_init_git", || {
+        PromptStepTask(Box::new(AskToInitCloneGit::new()))
+    }),
+    ("clone_repo", || {
+        PromptStepTask(Box::new(CloneGitRepo::new()))
+    }),
+    ("init_git_repo", || {
+        PromptStepTask(Box::new(InitGitRepo::new()))
+    }),
+    ("ask_pop_stash", || {
+        PromptStepTask(Box::new(AskPopStash::new()))
+    }),
+    ("ask_to_add", || PromptStepTask(Box::new(AskToAdd::new()))),
+    ("ask_add_mode", || {
+        PromptStepTask(Box::new(AskAddMode::new()))
+    }),
+    ("ask_to_restore", || {
+        PromptStepTask(Box::new(AskToRestore::new()))
+    }),
+    ("ask_push_pull", || {
+        PromptStepTask(Box::new(AskPushPull::new()))
+    }),
+    ("ask_commit", || PromptStepTask(Box::new(AskCommit::new()))),
+    ("ask_branch_name", || {
+        PromptStepTask(Box::new(AskBranchName::new()))
+    }),
+    ("ask_if_same_feat", || {
+        PromptStepTask(Box::new(AskIfSameFeat::new()))
+    }),
+    ("ask_ai_commit_message", || {
+        PromptStepTask(Box::new(AskAICommitMessage::new()))
+    }),
+    ("ask_human_commit_message", || {
+        PromptStepTask(Box::new(AskHumanCommitMessage::new()))
+    }),
+    ("pull_and_push", || {
+        PromptStepTask(Box::new(PullAndPush::new()))
+    }),
+    ("ai_commit", || PromptStepTask(Box::new(AICommit::new()))),
+    ("check_repo_size", || {
+        ActionStepTask(Box::new(CheckRepoSize::new()))
+    }),
+    ("check_loose_objects", || {
+        ActionStepTask(Box::new(CheckLooseObjects::new()))
+    }),
+    ("check_merged_branches", || {
+        ActionStepTask(Box::new(CheckMergedBranches::new()))
+    }),
+    ("check_stale_branches", || {
+        ActionStepTask(Box::new(CheckStaleBranches::new()))
+    }),
+    ("check_stashes", || {
+        ActionStepTask(Box::new(CheckStashes::new()))
+    }),
+    ("check_large_blobs", || {
+        ActionStepTask(Box::new(CheckLargeBlobs::new()))
+    }),
+    ("ask_repack", || PromptStepTask(Box::new(AskRepack::new()))),
+    ("ask_prune_objects", || {
+        PromptStepTask(Box::new(AskPruneObjects::new()))
+    }),
+    ("ask_delete_merged_branches", || {
+        PromptStepTask(Box::new(AskDeleteMergedBranches::new()))
+    }),
+    ("ask_delete_stale_branches", || {
+        PromptStepTask(Box::new(AskDeleteStaleBranches::new()))
+    }),
+    ("ask_drop_stashes", || {
+        PromptStepTask(Box::new(AskDropStashes::new()))
+    }),
+    ("ask_purge_large_blobs", || {
+        PromptStepTask(Box::new(AskPurgeLargeBlobs::new()))
+    }),
+];
+
+/// Build a fresh instance of the step registered under `name`
+pub(crate) fn new_task(name: &str) -> Option<Task> {
+    STEPS
+        .iter()
+        .find(|(step_name, _)| *step_name == name)
+        .map(|(_, build)| build())
+}
+
+pub(crate) fn is_registered(name: &str) -> bool {
+    STEPS.iter().any(|(step_name, _)| *step_name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::step::STOP;
+
+    #[test]
+    fn test_registered_names_match_step_names() {
+        for (name, build) in STEPS {
+            assert_eq!(build().get_name(), *name);
+        }
+        assert!(!is_registered(STOP));
+    }
+
+    #[test]
+    fn test_successors_are_registered_steps() {
+        for (name, build) in STEPS {
+            for successor in build().successors() {
+                assert!(
+                    *successor == STOP || is_registered(successor),
+                    "'{name}' declares unknown successor '{successor}'"
+                );
+            }
+        }
+        for (_, start) in BUILT_IN_WORKFLOWS {
+            assert!(is_registered(start));
+        }
+    }
+}
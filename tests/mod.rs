@@ -1 +1,2 @@
 mod utils;
+mod workflows;
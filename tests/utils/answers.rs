@@ -0,0 +1,18 @@
+/// Stage every change when the default workflow asks what to add
+pub const ADD_ALL: &str = r#"
+ask_to_add = "Yes"
+ask_add_mode = "Add all unstaged files"
+"#;
+
+/// Commit with a hand-written `message`, then push and pull only when `push` is set
+pub fn commit_with(message: &str, push: bool) -> String {
+    format!(
+        r#"
+ask_commit = "Yes"
+ask_ai_commit_message = "No"
+ask_human_commit_message = "{message}"
+ask_push_pull = "{}"
+"#,
+        if push { "Yes" } else { "No" }
+    )
+}
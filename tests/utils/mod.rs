@@ -1,4 +1,5 @@
 #![allow(unused)]
 
+pub mod answers;
 pub mod prevalidation;
 pub mod test_env;
@@ -7,6 +7,8 @@ use tempfile::TempDir;
 pub struct TestEnv {
     pub temp_dir: TempDir,
     pub repo_path: PathBuf,
+    /// HOME for bgit runs, so the user's global git and bgit config never leak into tests
+    pub home_dir: TempDir,
 }
 
 impl TestEnv {
@@ -25,9 +27,16 @@ impl TestEnv {
             return Err("Failed to initialize git repository".into());
         }
 
+        let home_dir = TempDir::with_prefix("bgit_test_home_")?;
+        fs::write(
+            home_dir.path().join(".gitconfig"),
+            "[user]\n\tname = Test User\n\temail = test@example.com\n",
+        )?;
+
         let test_env = TestEnv {
             temp_dir,
             repo_path,
+            home_dir,
         };
 
         Ok(test_env)
@@ -97,6 +106,13 @@ impl TestEnv {
         Ok(())
     }
 
+    /// Commit a README, so workflows start from a repository with history
+    pub fn with_initial_commit(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.create_file("README.md", "# hello\n")?;
+        self.stage_files(&["README.md"])?;
+        self.commit("chore: initial commit")
+    }
+
     pub fn create_files(&self, files: &[(&str, &str)]) -> Result<(), Box<dyn std::error::Error>> {
         for (path, content) in files {
             self.create_file(path, content)?;
@@ -123,14 +139,47 @@ impl TestEnv {
         &self,
         args: &[&str],
     ) -> Result<std::process::Output, Box<dyn std::error::Error>> {
+        let home = self.home_dir.path();
         let output = assert_cmd::Command::cargo_bin("bgit")?
             .args(args)
             .current_dir(&self.repo_path)
+            .env("HOME", home)
+            .env("XDG_CONFIG_HOME", home.join(".config"))
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env_remove("GOOGLE_API_KEY")
+            .env_remove("BGIT_VAULT_PASSPHRASE")
             .output()?;
 
         Ok(output)
     }
 
+    /// Run bgit non-interactively, answering its questions from `answers` (TOML)
+    pub fn run_bgit_with_answers(
+        &self,
+        answers: &str,
+        args: &[&str],
+    ) -> Result<std::process::Output, Box<dyn std::error::Error>> {
+        let answers_path = self.home_dir.path().join("answers.toml");
+        fs::write(&answers_path, answers)?;
+
+        let answers_arg = answers_path.to_string_lossy().into_owned();
+        let mut full_args = vec!["--answers", answers_arg.as_str()];
+        full_args.extend_from_slice(args);
+        self.run_bgit(&full_args)
+    }
+
+    pub fn git_log_subjects(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let output = Command::new("git")
+            .args(["log", "--format=%s"])
+            .current_dir(&self.repo_path)
+            .output()?;
+
+        Ok(String::from_utf8(output.stdout)?
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
     pub fn path(&self) -> &Path {
         &self.repo_path
     }
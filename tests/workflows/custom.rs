@@ -0,0 +1,53 @@
+use crate::test_env;
+
+const QUICK_COMMIT: &str = r#"
+[custom_workflows.quick_commit]
+description = "Stage everything and commit on the current branch"
+start = "ask_add_mode"
+
+[custom_workflows.quick_commit.steps.has_uncommitted]
+on.is_branch_main = "ask_human_commit_message"
+
+[custom_workflows.quick_commit.steps.ask_human_commit_message]
+next = "stop"
+"#;
+
+#[test]
+fn test_runs_custom_workflow_graph() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.create_file(".bgit/config.toml", QUICK_COMMIT)?;
+    env.stage_files(&[".bgit/config.toml"])?;
+    env.commit("chore: add bgit config")?;
+    env.create_file("fix.txt", "fixed\n")?;
+
+    let output = env.run_bgit_with_answers(
+        r#"
+ask_add_mode = "Add all unstaged files"
+ask_human_commit_message = "fix: quick"
+"#,
+        &["run", "quick_commit"],
+    )?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(env.git_log_subjects()?[0], "fix: quick");
+    assert_eq!(env.git_status()?, "");
+    // Routed straight from has_uncommitted to the commit message, then stopped
+    assert!(!stderr.contains("is_branch_main"), "{stderr}");
+    assert!(!stderr.contains("is_pushed_pulled"), "{stderr}");
+    Ok(())
+}
+
+#[test]
+fn test_unknown_workflow_lists_available() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.create_file(".bgit/config.toml", QUICK_COMMIT)?;
+
+    let output = env.run_bgit(&["run", "release"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("available: quick_commit"), "{stderr}");
+
+    let output = env.run_bgit(&["run"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Stage everything and commit"), "{stdout}");
+    Ok(())
+}
@@ -0,0 +1,338 @@
+use std::process::Command;
+
+use crate::test_env;
+use crate::utils::answers::{ADD_ALL, commit_with};
+
+#[test]
+fn test_adds_and_commits_everything() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.with_initial_commit()?;
+    env.create_files(&[
+        ("src/lib.rs", "pub fn answer() -> u8 { 42 }\n"),
+        ("notes.txt", "todo\n"),
+    ])?;
+
+    let output = env.run_bgit_with_answers(
+        &format!("{ADD_ALL}{}", commit_with("feat: add lib", false)),
+        &[],
+    )?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        stdout.contains("Enter your commit message › feat: add lib"),
+        "{stdout}"
+    );
+    assert_eq!(env.git_log_subjects()?[0], "feat: add lib");
+    assert_eq!(env.git_status()?, "");
+
+    let traces = std::fs::read_dir(env.path().join(".git/bgit/traces"))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(traces.len(), 1);
+    let trace: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&traces[0])?)?;
+    assert_eq!(trace["workflow"], "default");
+    assert_eq!(trace["completed"], true);
+    let commit_step = trace["steps"]
+        .as_array()
+        .and_then(|steps| {
+            steps
+                .iter()
+                .find(|step| step["name"] == "ask_human_commit_message")
+        })
+        .ok_or("no commit step in trace")?;
+    assert_eq!(commit_step["events"][0]["name"], "git_commit");
+    assert_eq!(commit_step["events"][0]["result"], true);
+    Ok(())
+}
+
+#[test]
+fn test_adds_only_selected_files() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.with_initial_commit()?;
+    env.create_files(&[("keep.txt", "keep\n"), ("skip.txt", "skip\n")])?;
+
+    env.run_bgit_with_answers(
+        r#"
+ask_to_add = true
+ask_add_mode = 1
+ask_add_mode_files = ["keep.txt"]
+ask_commit = true
+ask_ai_commit_message = false
+ask_human_commit_message = "docs: keep notes"
+ask_push_pull = false
+"#,
+        &[],
+    )?;
+
+    assert_eq!(env.git_log_subjects()?[0], "docs: keep notes");
+    assert_eq!(env.git_status()?, "?? skip.txt\n");
+    Ok(())
+}
+
+#[test]
+fn test_unselected_junk_does_not_block_adding() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    // At Error level, judging the unselected file would stop the run at its unanswered fix
+    env.create_file(
+        ".bgit/config.toml",
+        "[rules.default]\nFileNotGitignored = \"Error\"\n",
+    )?;
+    env.stage_files(&[".bgit/config.toml"])?;
+    env.commit("chore: add bgit config")?;
+    env.create_files(&[("keep.txt", "keep\n"), (".DS_Store", "junk\n")])?;
+
+    env.run_bgit_with_answers(
+        &format!(
+            "ask_to_add = \"Yes\"\nask_add_mode = 1\nask_add_mode_files = [\"keep.txt\"]\n{}",
+            commit_with("docs: keep notes", false)
+        ),
+        &[],
+    )?;
+
+    assert_eq!(env.git_log_subjects()?[0], "docs: keep notes");
+    assert_eq!(env.git_status()?, "?? .DS_Store\n");
+    Ok(())
+}
+
+#[test]
+fn test_missing_answer_fails_without_prompting() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.with_initial_commit()?;
+    env.create_file("change.txt", "change\n")?;
+
+    // `--yes` picks the AI commit message by default, whose API key has no default
+    let output = env.run_bgit(&["--yes"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains("No answer for 'ai_commit'"), "{stderr}");
+    assert_eq!(env.git_log_subjects()?, vec!["chore: initial commit"]);
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_prints_commands_without_committing() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.with_initial_commit()?;
+    env.create_file("staged.txt", "staged\n")?;
+    env.stage_files(&["staged.txt"])?;
+
+    let output =
+        env.run_bgit_with_answers(&commit_with("feat: add staged", false), &["--dry-run"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        stderr.contains("Would run: git commit -m 'feat: add staged'"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("ask_commit (dry run)"), "{stderr}");
+    assert_eq!(env.git_log_subjects()?, vec!["chore: initial commit"]);
+    assert_eq!(env.git_status()?, "A  staged.txt\n");
+    assert!(!env.path().join(".git/bgit/traces").exists());
+    Ok(())
+}
+
+#[test]
+fn test_resume_restarts_at_failed_step() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.with_initial_commit()?;
+    env.create_file("staged.txt", "staged\n")?;
+    env.stage_files(&["staged.txt"])?;
+
+    // There is no remote to push to, so the workflow fails after committing
+    let output = env.run_bgit_with_answers(&commit_with("feat: add staged", true), &[])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("bgit --resume"), "{stderr}");
+    assert_eq!(env.git_log_subjects()?[0], "feat: add staged");
+
+    let checkpoint_path = env.path().join(".git/bgit/checkpoint.json");
+    let checkpoint: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&checkpoint_path)?)?;
+    assert_eq!(checkpoint["workflow"], "default");
+    assert_eq!(checkpoint["step"], "pull_and_push");
+
+    let output = env.run_bgit(&["--resume", "--yes"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Resuming workflow default at step pull_and_push"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("ask_commit"), "{stderr}");
+    assert_eq!(env.git_log_subjects()?.len(), 2);
+    assert!(checkpoint_path.exists());
+    Ok(())
+}
+
+#[test]
+fn test_rolls_back_add_and_commit_when_push_fails() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.with_initial_commit()?;
+    env.create_file("notes.txt", "todo\n")?;
+
+    // There is no remote to push to, so the workflow fails after committing
+    let output = env.run_bgit_with_answers(
+        &format!(
+            "{ADD_ALL}{}[workflow]\nrollback = true\n",
+            commit_with("feat: add notes", true)
+        ),
+        &[],
+    )?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains("Rolled back 2 change(s)"), "{stderr}");
+    assert_eq!(env.git_log_subjects()?, vec!["chore: initial commit"]);
+    assert_eq!(env.git_status()?, "?? notes.txt\n");
+    assert!(!env.path().join(".git/bgit/checkpoint.json").exists());
+    Ok(())
+}
+
+#[test]
+fn test_undo_walks_back_add_and_commit() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.with_initial_commit()?;
+    env.create_file("notes.txt", "todo\n")?;
+
+    env.run_bgit_with_answers(
+        &format!("{ADD_ALL}{}", commit_with("feat: add notes", false)),
+        &[],
+    )?;
+    assert_eq!(env.git_log_subjects()?[0], "feat: add notes");
+
+    let output = env.run_bgit(&["undo", "--list"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("git commit -m 'feat: add notes'"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("git add -A"), "{stdout}");
+
+    env.run_bgit(&["undo", "--yes"])?;
+    assert_eq!(env.git_log_subjects()?, vec!["chore: initial commit"]);
+    assert_eq!(env.git_status()?, "A  notes.txt\n");
+
+    env.run_bgit(&["undo", "--yes"])?;
+    assert_eq!(env.git_status()?, "?? notes.txt\n");
+
+    let output = env.run_bgit(&["undo", "--yes"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Nothing to undo"), "{stdout}");
+    Ok(())
+}
+
+#[test]
+fn test_json_output_reports_error_and_exit_code() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.with_initial_commit()?;
+    env.create_file("change.txt", "change\n")?;
+
+    let output = env.run_bgit(&["--yes", "--output", "json"])?;
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    assert_eq!(result["command"], "default");
+    assert_eq!(result["status"], "error");
+    assert_eq!(result["error"]["workflow_type"], "ActionStep");
+    assert_eq!(result["error"]["step_name"], "ai_commit");
+    assert_eq!(result["workflow"]["workflow"], "default");
+    assert_eq!(output.status.code(), Some(16));
+    assert_eq!(result["exit_code"], 16);
+    Ok(())
+}
+
+#[test]
+fn test_init_fails_when_an_environment_rule_fails() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.create_file(
+        ".bgit/config.toml",
+        "[rules.default]\nGitDefaultConfig = \"Error\"\n",
+    )?;
+
+    let output = env.run_bgit_with_answers(
+        "\"git_default_config.scope\" = \"Leave them as they are\"\n",
+        &["init", "--output", "json"],
+    )?;
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    assert_eq!(result["status"], "error");
+    assert_eq!(result["error"]["rule_name"], "GitDefaultConfig");
+    assert_eq!(output.status.code(), Some(10));
+    Ok(())
+}
+
+#[test]
+fn test_legacy_secrets_are_left_alone_outside_init() -> Result<(), Box<dyn std::error::Error>> {
+    test_env!(env);
+    env.with_initial_commit()?;
+    let config_dir = env.home_dir.path().join(".config/bgit");
+    std::fs::create_dir_all(&config_dir)?;
+    // "dG9rXzEyMw==" is base64 for "tok_123"
+    let legacy_config = "[auth.https]\nusername = \"alice\"\npat = \"dG9rXzEyMw==\"\n";
+    std::fs::write(config_dir.join("config.toml"), legacy_config)?;
+
+    let output = env.run_bgit(&["--yes", "log"])?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "{stderr}");
+    assert!(stderr.contains("run `bgit init`"), "{stderr}");
+    assert_eq!(
+        std::fs::read_to_string(config_dir.join("config.toml"))?,
+        legacy_config
+    );
+    assert!(!config_dir.join("secrets.vault").exists());
+    Ok(())
+}
+
+#[test]
+fn test_signs_commit_with_ssh_key() -> Result<(), Box<dyn std::error::Error>> {
+    if which::which("ssh-keygen").is_err() {
+        return Ok(());
+    }
+    test_env!(env);
+    env.with_initial_commit()?;
+
+    let key = env.home_dir.path().join("signing_key");
+    let generated = Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+        .arg(&key)
+        .status()?;
+    assert!(generated.success());
+    let key_path = key.display().to_string();
+    for (name, value) in [
+        ("commit.gpgSign", "true"),
+        ("gpg.format", "ssh"),
+        ("user.signingKey", key_path.as_str()),
+    ] {
+        Command::new("git")
+            .args(["config", name, value])
+            .current_dir(env.path())
+            .status()?;
+    }
+    env.create_file(
+        ".bgit/config.toml",
+        "[rules.default]\nCommitGpgSign = \"Error\"\n",
+    )?;
+    env.create_file("notes.txt", "todo\n")?;
+
+    env.run_bgit_with_answers(
+        &format!("{ADD_ALL}{}", commit_with("feat: add notes", false)),
+        &[],
+    )?;
+    assert_eq!(env.git_log_subjects()?[0], "feat: add notes");
+
+    let public_key = std::fs::read_to_string(format!("{key_path}.pub"))?;
+    let allowed_signers = env.home_dir.path().join("allowed_signers");
+    std::fs::write(&allowed_signers, format!("test@example.com {public_key}"))?;
+    let verified = Command::new("git")
+        .arg("-c")
+        .arg(format!(
+            "gpg.ssh.allowedSignersFile={}",
+            allowed_signers.display()
+        ))
+        .args(["verify-commit", "HEAD"])
+        .current_dir(env.path())
+        .output()?;
+    assert!(
+        verified.status.success(),
+        "{}",
+        String::from_utf8_lossy(&verified.stderr)
+    );
+    Ok(())
+}
@@ -0,0 +1,2 @@
+mod custom;
+mod default;